@@ -0,0 +1,53 @@
+use failure::Fail;
+use std::path::PathBuf;
+
+/// Represents the errors of the confidential identity CLIs.
+#[derive(Fail, Clone, Debug, Eq, PartialEq)]
+pub enum ScpError {
+    /// The UUID string is not in the `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` hex format.
+    #[fail(display = "Invalid UUID format: {:?}", uuid)]
+    InvalidUuidFormat { uuid: String },
+
+    /// The proof encoding is neither `json` nor `base64`.
+    #[fail(display = "Unknown proof encoding: {:?}", encoding)]
+    UnknownProofEncoding { encoding: String },
+
+    /// The proof package could not be serialized or deserialized.
+    #[fail(display = "Invalid proof package: {}", reason)]
+    InvalidProofPackage { reason: String },
+
+    /// The claim is not a Json object.
+    #[fail(display = "Invalid claim: {}", reason)]
+    InvalidClaimJson { reason: String },
+
+    /// A field of the claim is missing or malformed.
+    #[fail(display = "Invalid claim: {} {}", field, reason)]
+    InvalidClaimField { field: String, reason: String },
+
+    /// The claim was neither given nor requested to be generated randomly.
+    #[fail(
+        display = "Missing the {}. Either pass in a {} file, set the {} environment variable, or pass `--rand` to generate it randomly.",
+        name, name, env_var
+    )]
+    MissingClaim { name: String, env_var: String },
+
+    /// A field of the proof package has the wrong length or is not a canonical encoding.
+    #[fail(display = "Invalid proof field {}: {}", field, reason)]
+    InvalidProofField { field: String, reason: String },
+
+    /// A file could not be read.
+    #[fail(display = "Failed to read the {} from {:?}: {}", name, path, reason)]
+    FileReadError {
+        name: String,
+        path: PathBuf,
+        reason: String,
+    },
+
+    /// The CDD Id could not be deserialized.
+    #[fail(display = "Invalid CDD Id: {}", reason)]
+    InvalidCddId { reason: String },
+
+    /// The CDD Id was not created from the CDD claim.
+    #[fail(display = "The CDD Id does not match the CDD claim")]
+    CddIdMismatch,
+}
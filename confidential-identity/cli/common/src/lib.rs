@@ -1,5 +1,18 @@
-use confidential_identity::{claim_proofs::ScopeClaimProof, CddId};
-use serde::{Deserialize, Serialize};
+use codec::Encode;
+use confidential_identity::{
+    claim_proofs::{
+        decode_proof_point, slice_to_scalar, ScopeClaimProof, Verifier,
+        SCOPE_CLAIM_PROOF_JSON_POINTS,
+    },
+    errors::{Error, ErrorKind, Fallible},
+    CddId, RistrettoPoint, VerifierTrait,
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{convert::TryFrom, str::FromStr};
+use subtle::{Choice, ConstantTimeEq};
+
+pub mod errors;
+use errors::ScpError;
 
 // IdentityId is the investor's DID.
 pub type InvestorDID = [u8; 32];
@@ -20,3 +33,639 @@ pub struct Proof {
     pub scope_did: ScopeDID,
     pub proof: ScopeClaimProof,
 }
+
+/// The formats in which a proof package can be written.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofEncoding {
+    /// The proof package as Json, where the byte fields are arrays of integers.
+    Json,
+    /// The proof package as Json, where the byte fields are base64 strings.
+    Base64,
+}
+
+impl FromStr for ProofEncoding {
+    type Err = ScpError;
+
+    fn from_str(encoding: &str) -> Result<Self, Self::Err> {
+        match encoding {
+            "json" => Ok(ProofEncoding::Json),
+            "base64" => Ok(ProofEncoding::Base64),
+            _ => Err(ScpError::UnknownProofEncoding {
+                encoding: encoding.to_string(),
+            }),
+        }
+    }
+}
+
+/// A byte array that is serialized as a base64 string.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Base64Bytes(pub Vec<u8>);
+
+impl Serialize for Base64Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for Base64Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        base64::decode(&encoded)
+            .map(Base64Bytes)
+            .map_err(de::Error::custom)
+    }
+}
+
+/// The compact form of `Proof`, in which every field is base64 encoded.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Base64Proof {
+    pub cdd_id: Base64Bytes,
+    pub investor_did: Base64Bytes,
+    pub scope_did: Base64Bytes,
+    pub proof: Base64Bytes,
+}
+
+impl From<&Proof> for Base64Proof {
+    fn from(proof: &Proof) -> Self {
+        Base64Proof {
+            cdd_id: Base64Bytes(proof.cdd_id.0.compress().to_bytes().to_vec()),
+            investor_did: Base64Bytes(proof.investor_did.to_vec()),
+            scope_did: Base64Bytes(proof.scope_did.to_vec()),
+            proof: Base64Bytes(proof.proof.encode()),
+        }
+    }
+}
+
+pub use confidential_identity::claim_proofs::SCOPE_CLAIM_PROOF_LEN;
+
+fn check_field_length(field: &str, bytes: &[u8], expected: usize) -> Result<(), ScpError> {
+    if bytes.len() != expected {
+        return Err(ScpError::InvalidProofField {
+            field: field.to_string(),
+            reason: format!("expected {} bytes, got {}", expected, bytes.len()),
+        });
+    }
+    Ok(())
+}
+
+/// Converts the field errors of the proof decoding into `ScpError::InvalidProofField`.
+fn proof_field_error(error: Error) -> ScpError {
+    match error.kind() {
+        ErrorKind::InvalidProofFieldLength {
+            field,
+            length,
+            expected,
+        } => ScpError::InvalidProofField {
+            field: field.to_string(),
+            reason: format!("expected {} bytes, got {}", expected, length),
+        },
+        ErrorKind::InvalidProofPoint { field } => ScpError::InvalidProofField {
+            field: field.to_string(),
+            reason: "not a canonical Ristretto point".to_string(),
+        },
+        _ => ScpError::InvalidProofPackage {
+            reason: error.to_string(),
+        },
+    }
+}
+
+impl Base64Proof {
+    /// Checks the length of each field, and that each curve point is canonically encoded.
+    /// Returns `ScpError::InvalidProofField` naming the first invalid field.
+    pub fn validate(&self) -> Result<(), ScpError> {
+        decode_proof_point("cdd_id", &self.cdd_id.0).map_err(proof_field_error)?;
+        check_field_length("investor_did", &self.investor_did.0, INVESTORDID_LEN)?;
+        check_field_length("scope_did", &self.scope_did.0, SCOPEDID_LEN)?;
+        ScopeClaimProof::decode_fields(&self.proof.0).map_err(proof_field_error)?;
+        Ok(())
+    }
+}
+
+fn json_field_bytes(
+    proof: &serde_json::Value,
+    field: &str,
+    pointer: &str,
+) -> Result<Vec<u8>, ScpError> {
+    let value = proof
+        .pointer(pointer)
+        .cloned()
+        .unwrap_or(serde_json::Value::Null);
+    serde_json::from_value(value).map_err(|error| ScpError::InvalidProofField {
+        field: field.to_string(),
+        reason: error.to_string(),
+    })
+}
+
+impl Proof {
+    /// Checks a Json encoded proof package the same way `Base64Proof::validate` checks a
+    /// base64 encoded one: the length of each byte field, and that each curve point is
+    /// canonically encoded. Returns `ScpError::InvalidProofField` naming the first invalid
+    /// field.
+    pub fn validate_json(proof: &serde_json::Value) -> Result<(), ScpError> {
+        let cdd_id = json_field_bytes(proof, "cdd_id", "/cdd_id")?;
+        decode_proof_point("cdd_id", &cdd_id).map_err(proof_field_error)?;
+        let investor_did = json_field_bytes(proof, "investor_did", "/investor_did")?;
+        check_field_length("investor_did", &investor_did, INVESTORDID_LEN)?;
+        let scope_did = json_field_bytes(proof, "scope_did", "/scope_did")?;
+        check_field_length("scope_did", &scope_did, SCOPEDID_LEN)?;
+        for (field, pointer) in SCOPE_CLAIM_PROOF_JSON_POINTS.iter() {
+            let point = json_field_bytes(proof, field, &format!("/proof{}", pointer))?;
+            decode_proof_point(*field, &point).map_err(proof_field_error)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<Base64Proof> for Proof {
+    type Error = ScpError;
+
+    fn try_from(proof: Base64Proof) -> Result<Self, Self::Error> {
+        proof.validate()?;
+        let error = |field: &str| ScpError::InvalidProofPackage {
+            reason: format!("invalid {}", field),
+        };
+
+        let cdd_id = decode_proof_point("cdd_id", &proof.cdd_id.0).map_err(proof_field_error)?;
+        let investor_did = InvestorDID::try_from(proof.investor_did.0.as_slice())
+            .map_err(|_| error("investor_did"))?;
+        let scope_did =
+            ScopeDID::try_from(proof.scope_did.0.as_slice()).map_err(|_| error("scope_did"))?;
+        let claim_proof =
+            ScopeClaimProof::decode_fields(&proof.proof.0).map_err(proof_field_error)?;
+
+        Ok(Proof {
+            cdd_id: CddId(cdd_id),
+            investor_did,
+            scope_did,
+            proof: claim_proof,
+        })
+    }
+}
+
+/// Serializes a proof package using the given encoding.
+pub fn serialize_proof(proof: &Proof, encoding: ProofEncoding) -> Result<String, ScpError> {
+    match encoding {
+        ProofEncoding::Json => serde_json::to_string(proof),
+        ProofEncoding::Base64 => serde_json::to_string(&Base64Proof::from(proof)),
+    }
+    .map_err(|error| ScpError::InvalidProofPackage {
+        reason: error.to_string(),
+    })
+}
+
+/// Deserializes a proof package that was serialized with `serialize_proof`.
+pub fn deserialize_proof(proof: &str, encoding: ProofEncoding) -> Result<Proof, ScpError> {
+    let to_error = |error: serde_json::Error| ScpError::InvalidProofPackage {
+        reason: error.to_string(),
+    };
+    match encoding {
+        ProofEncoding::Json => {
+            let proof: serde_json::Value = serde_json::from_str(proof).map_err(to_error)?;
+            Proof::validate_json(&proof)?;
+            serde_json::from_value(proof).map_err(to_error)
+        }
+        ProofEncoding::Base64 => {
+            Proof::try_from(serde_json::from_str::<Base64Proof>(proof).map_err(to_error)?)
+        }
+    }
+}
+
+/// Verifies a proof package end-to-end, by deriving the investor and scope DIDs the same way
+/// the prover did and verifying the claim proof against them and the CDD id.
+pub fn verify_proof_package(proof: &Proof) -> Fallible<()> {
+    Verifier::verify_scope_claim_proof(
+        &proof.proof,
+        &slice_to_scalar(&proof.investor_did),
+        &slice_to_scalar(&proof.scope_did),
+        &proof.cdd_id,
+    )
+}
+
+/// Verifies a proof package as in `verify_proof_package`, after checking that its CDD id is
+/// one of the `registered_cdd_ids`, e.g., the ids that the trusted CDD providers registered on
+/// chain. The registry is scanned in constant time, so the time taken does not reveal which,
+/// if any, of the registered ids matched.
+pub fn verify_proof_against_registry(
+    proof: &Proof,
+    registered_cdd_ids: &[RistrettoPoint],
+) -> Fallible<()> {
+    let registered = registered_cdd_ids
+        .iter()
+        .fold(Choice::from(0), |found, cdd_id| {
+            found | cdd_id.ct_eq(&proof.cdd_id.0)
+        });
+    if !bool::from(registered) {
+        return Err(ErrorKind::UnregisteredCddId.into());
+    }
+
+    verify_proof_package(proof)
+}
+
+/// The byte ranges of the five groups of a canonically formatted UUID.
+const UUID_GROUPS: [(usize, usize); 5] = [(0, 4), (4, 6), (6, 8), (8, 10), (10, 16)];
+
+/// Formats a unique id into the canonical UUID format,
+/// i.e., `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`.
+pub fn format_uuid(bytes: &UniqueID) -> String {
+    UUID_GROUPS
+        .iter()
+        .map(|(start, end)| hex::encode(&bytes[*start..*end]))
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Parses a unique id from its canonical UUID format. This is the inverse of `format_uuid`.
+pub fn parse_uuid(uuid: &str) -> Result<UniqueID, ScpError> {
+    let error = || ScpError::InvalidUuidFormat {
+        uuid: uuid.to_string(),
+    };
+
+    let groups = uuid.split('-').collect::<Vec<_>>();
+    if groups.len() != UUID_GROUPS.len() {
+        return Err(error());
+    }
+
+    let mut bytes = [0u8; UNIQUEID_LEN];
+    for (group, (start, end)) in groups.iter().zip(UUID_GROUPS.iter()) {
+        hex::decode_to_slice(group, &mut bytes[*start..*end]).map_err(|_| error())?;
+    }
+
+    Ok(bytes)
+}
+
+/// The kinds of the claims that are read from Json.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClaimKind {
+    /// A cdd claim, with the investor DID and the investor unique id.
+    Cdd,
+    /// A scope claim, with the scope DID and the investor unique id.
+    Scope,
+}
+
+impl ClaimKind {
+    /// The name of the claim in the messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            ClaimKind::Cdd => "cdd claim",
+            ClaimKind::Scope => "scope claim",
+        }
+    }
+
+    /// The required fields of the claim, and their lengths in bytes.
+    fn fields(self) -> &'static [(&'static str, usize)] {
+        match self {
+            ClaimKind::Cdd => &[
+                ("investor_did", INVESTORDID_LEN),
+                ("investor_unique_id", UNIQUEID_LEN),
+            ],
+            ClaimKind::Scope => &[
+                ("scope_did", SCOPEDID_LEN),
+                ("investor_unique_id", UNIQUEID_LEN),
+            ],
+        }
+    }
+}
+
+/// Checks that `json` is a well-formed claim of the given `kind`, i.e., that it has the
+/// required fields and that each of them is an array of bytes of the right length.
+/// Unlike the deserialization errors, the returned error names the offending field.
+pub fn validate_claim_json(json: &str, kind: ClaimKind) -> Result<(), ScpError> {
+    let claim: serde_json::Value =
+        serde_json::from_str(json).map_err(|error| ScpError::InvalidClaimJson {
+            reason: error.to_string(),
+        })?;
+    let claim = claim
+        .as_object()
+        .ok_or_else(|| ScpError::InvalidClaimJson {
+            reason: String::from("the claim must be a Json object"),
+        })?;
+
+    for (field, len) in kind.fields() {
+        let error = |reason: String| ScpError::InvalidClaimField {
+            field: field.to_string(),
+            reason,
+        };
+        let bytes = claim
+            .get(*field)
+            .ok_or_else(|| error(String::from("is missing")))?
+            .as_array()
+            .ok_or_else(|| error(format!("must be an array of {} bytes", len)))?;
+        if bytes.len() != *len {
+            return Err(error(format!(
+                "must be {} bytes, but has {}",
+                len,
+                bytes.len()
+            )));
+        }
+        if let Some((index, byte)) = bytes
+            .iter()
+            .enumerate()
+            .find(|(_, byte)| byte.as_u64().map_or(true, |byte| byte > 255))
+        {
+            return Err(error(format!(
+                "must only contain bytes, but element {} is {}",
+                index, byte
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use confidential_identity::{
+        claim_proofs::{Investor, Provider},
+        CddClaimData, InvestorTrait, ProviderTrait, ScopeClaimData,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_verify_proof_package() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let investor_did: InvestorDID = [1u8; INVESTORDID_LEN];
+        let scope_did: ScopeDID = [2u8; SCOPEDID_LEN];
+        let unique_id: UniqueID = [3u8; UNIQUEID_LEN];
+
+        let cdd_claim = CddClaimData::new(&investor_did, &unique_id);
+        let scope_claim = ScopeClaimData::new(&scope_did, &unique_id);
+        let mut proof = Proof {
+            cdd_id: Provider::create_cdd_id(&cdd_claim),
+            investor_did,
+            scope_did,
+            proof: Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng),
+        };
+        assert!(verify_proof_package(&proof).is_ok());
+
+        // Negative test: alter the scope DID after proving.
+        proof.scope_did[0] ^= 1;
+        assert!(verify_proof_package(&proof).is_err());
+    }
+
+    #[test]
+    fn test_base64_proof_round_trip() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let investor_did: InvestorDID = [1u8; INVESTORDID_LEN];
+        let scope_did: ScopeDID = [2u8; SCOPEDID_LEN];
+        let unique_id: UniqueID = [3u8; UNIQUEID_LEN];
+
+        let cdd_claim = CddClaimData::new(&investor_did, &unique_id);
+        let scope_claim = ScopeClaimData::new(&scope_did, &unique_id);
+        let proof = Proof {
+            cdd_id: Provider::create_cdd_id(&cdd_claim),
+            investor_did,
+            scope_did,
+            proof: Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng),
+        };
+
+        // The base64 encoding round-trips to the original bytes and is more compact than Json.
+        let json = serialize_proof(&proof, ProofEncoding::Json).unwrap();
+        let encoded = serialize_proof(&proof, ProofEncoding::Base64).unwrap();
+        assert!(encoded.len() < json.len());
+        let decoded = deserialize_proof(&encoded, ProofEncoding::Base64).unwrap();
+        assert_eq!(decoded.cdd_id.0, proof.cdd_id.0);
+        assert_eq!(decoded.investor_did, proof.investor_did);
+        assert_eq!(decoded.scope_did, proof.scope_did);
+        assert_eq!(decoded.proof.encode(), proof.proof.encode());
+        assert_eq!(
+            serialize_proof(&decoded, ProofEncoding::Json).unwrap(),
+            json
+        );
+        assert!(verify_proof_package(&decoded).is_ok());
+
+        // A Json encoded package is not valid base64.
+        assert!(deserialize_proof(&json, ProofEncoding::Base64).is_err());
+        assert_eq!("base64".parse::<ProofEncoding>(), Ok(ProofEncoding::Base64));
+        assert_eq!("json".parse::<ProofEncoding>(), Ok(ProofEncoding::Json));
+        assert!("hex".parse::<ProofEncoding>().is_err());
+    }
+
+    #[test]
+    fn test_validate_base64_proof() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let investor_did: InvestorDID = [1u8; INVESTORDID_LEN];
+        let scope_did: ScopeDID = [2u8; SCOPEDID_LEN];
+        let unique_id: UniqueID = [3u8; UNIQUEID_LEN];
+
+        let cdd_claim = CddClaimData::new(&investor_did, &unique_id);
+        let scope_claim = ScopeClaimData::new(&scope_did, &unique_id);
+        let proof = Proof {
+            cdd_id: Provider::create_cdd_id(&cdd_claim),
+            investor_did,
+            scope_did,
+            proof: Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng),
+        };
+        let encoded = Base64Proof::from(&proof);
+        assert_eq!(encoded.proof.0.len(), SCOPE_CLAIM_PROOF_LEN);
+        assert!(encoded.validate().is_ok());
+
+        let invalid_field = |encoded: &Base64Proof| {
+            let encoded = serde_json::to_string(encoded).unwrap();
+            match deserialize_proof(&encoded, ProofEncoding::Base64) {
+                Err(ScpError::InvalidProofField { field, .. }) => field,
+                result => panic!("Unexpected result: {:?}", result),
+            }
+        };
+
+        // A corrupt scope id point.
+        let mut corrupt = Base64Proof::from(&proof);
+        corrupt.proof.0[192..].copy_from_slice(&[0xffu8; 32]);
+        assert_eq!(invalid_field(&corrupt), "scope_id");
+
+        // A short proof vector.
+        let mut short = Base64Proof::from(&proof);
+        short.proof.0.truncate(SCOPE_CLAIM_PROOF_LEN - 1);
+        assert_eq!(invalid_field(&short), "proof");
+
+        // A short cdd id.
+        let mut short = Base64Proof::from(&proof);
+        short.cdd_id.0.pop();
+        assert_eq!(invalid_field(&short), "cdd_id");
+    }
+
+    #[test]
+    fn test_validate_json_proof() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let investor_did: InvestorDID = [1u8; INVESTORDID_LEN];
+        let scope_did: ScopeDID = [2u8; SCOPEDID_LEN];
+        let unique_id: UniqueID = [3u8; UNIQUEID_LEN];
+
+        let cdd_claim = CddClaimData::new(&investor_did, &unique_id);
+        let scope_claim = ScopeClaimData::new(&scope_did, &unique_id);
+        let proof = Proof {
+            cdd_id: Provider::create_cdd_id(&cdd_claim),
+            investor_did,
+            scope_did,
+            proof: Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng),
+        };
+        let encoded = serde_json::to_value(&proof).unwrap();
+        assert!(Proof::validate_json(&encoded).is_ok());
+
+        let invalid_field = |encoded: &serde_json::Value| {
+            let encoded = serde_json::to_string(encoded).unwrap();
+            match deserialize_proof(&encoded, ProofEncoding::Json) {
+                Err(ScpError::InvalidProofField { field, .. }) => field,
+                result => panic!("Unexpected result: {:?}", result),
+            }
+        };
+
+        // A corrupt scope id point.
+        let mut corrupt = encoded.clone();
+        *corrupt.pointer_mut("/proof/scope_id").unwrap() =
+            serde_json::to_value([0xffu8; 32]).unwrap();
+        assert_eq!(invalid_field(&corrupt), "scope_id");
+
+        // A short cdd id.
+        let mut short = encoded.clone();
+        short
+            .pointer_mut("/cdd_id")
+            .and_then(serde_json::Value::as_array_mut)
+            .unwrap()
+            .pop();
+        assert_eq!(invalid_field(&short), "cdd_id");
+
+        // A missing scope did.
+        let mut missing = encoded;
+        missing.as_object_mut().unwrap().remove("scope_did");
+        assert_eq!(invalid_field(&missing), "scope_did");
+    }
+
+    #[test]
+    fn test_verify_proof_against_registry() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let investor_did: InvestorDID = [1u8; INVESTORDID_LEN];
+        let scope_did: ScopeDID = [2u8; SCOPEDID_LEN];
+        let unique_id: UniqueID = [3u8; UNIQUEID_LEN];
+
+        let cdd_claim = CddClaimData::new(&investor_did, &unique_id);
+        let scope_claim = ScopeClaimData::new(&scope_did, &unique_id);
+        let proof = Proof {
+            cdd_id: Provider::create_cdd_id(&cdd_claim),
+            investor_did,
+            scope_did,
+            proof: Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng),
+        };
+        let other_cdd_id =
+            Provider::create_cdd_id(&CddClaimData::new(&[4u8; INVESTORDID_LEN], &unique_id));
+
+        // The registered CDD id is accepted.
+        let registry = vec![other_cdd_id.0, proof.cdd_id.0];
+        assert!(verify_proof_against_registry(&proof, &registry).is_ok());
+
+        // An unregistered CDD id is rejected, even though the proof itself is valid.
+        assert!(verify_proof_package(&proof).is_ok());
+        assert_eq!(
+            verify_proof_against_registry(&proof, &[other_cdd_id.0])
+                .unwrap_err()
+                .kind(),
+            &ErrorKind::UnregisteredCddId
+        );
+        assert_eq!(
+            verify_proof_against_registry(&proof, &[])
+                .unwrap_err()
+                .kind(),
+            &ErrorKind::UnregisteredCddId
+        );
+    }
+
+    #[test]
+    fn test_uuid_round_trip() {
+        let bytes: UniqueID = [
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54,
+            0x32, 0x10,
+        ];
+        let uuid = format_uuid(&bytes);
+        assert_eq!(uuid, "01234567-89ab-cdef-fedc-ba9876543210");
+        assert_eq!(parse_uuid(&uuid), Ok(bytes));
+    }
+
+    #[test]
+    fn test_validate_claim_json() {
+        let cdd_claim = format!(
+            "{{\"investor_did\": {:?}, \"investor_unique_id\": {:?}}}",
+            [1u8; INVESTORDID_LEN], [2u8; UNIQUEID_LEN]
+        );
+        let scope_claim = format!(
+            "{{\"scope_did\": {:?}, \"investor_unique_id\": {:?}}}",
+            [3u8; SCOPEDID_LEN], [2u8; UNIQUEID_LEN]
+        );
+        assert_eq!(validate_claim_json(&cdd_claim, ClaimKind::Cdd), Ok(()));
+        assert_eq!(validate_claim_json(&scope_claim, ClaimKind::Scope), Ok(()));
+
+        let field_error = |field: &str, reason: &str| {
+            Err(ScpError::InvalidClaimField {
+                field: field.to_string(),
+                reason: reason.to_string(),
+            })
+        };
+
+        // Not a Json object.
+        assert!(matches!(
+            validate_claim_json("{\"investor_did\": ", ClaimKind::Cdd),
+            Err(ScpError::InvalidClaimJson { .. })
+        ));
+        assert!(matches!(
+            validate_claim_json("[1, 2, 3]", ClaimKind::Cdd),
+            Err(ScpError::InvalidClaimJson { .. })
+        ));
+
+        // A missing field.
+        assert_eq!(
+            validate_claim_json(
+                &format!("{{\"investor_did\": {:?}}}", [1u8; 32]),
+                ClaimKind::Cdd
+            ),
+            field_error("investor_unique_id", "is missing")
+        );
+
+        // A field of the wrong length.
+        assert_eq!(
+            validate_claim_json(&cdd_claim.replacen("1, ", "", 1), ClaimKind::Cdd),
+            field_error("investor_did", "must be 32 bytes, but has 31")
+        );
+
+        // A field of the wrong type.
+        assert_eq!(
+            validate_claim_json(
+                &format!(
+                    "{{\"scope_did\": \"ACME\", \"investor_unique_id\": {:?}}}",
+                    [2u8; UNIQUEID_LEN]
+                ),
+                ClaimKind::Scope
+            ),
+            field_error("scope_did", "must be an array of 12 bytes")
+        );
+
+        // An element that is not a byte.
+        assert_eq!(
+            validate_claim_json(&scope_claim.replacen("3", "256", 1), ClaimKind::Scope),
+            field_error("scope_did", "must only contain bytes, but element 0 is 256")
+        );
+        assert_eq!(
+            validate_claim_json(&scope_claim.replacen("3", "-3", 1), ClaimKind::Scope),
+            field_error("scope_did", "must only contain bytes, but element 0 is -3")
+        );
+
+        // A claim of the other kind is missing the fields of the expected kind.
+        assert_eq!(
+            validate_claim_json(&scope_claim, ClaimKind::Cdd),
+            field_error("investor_did", "is missing")
+        );
+        assert_eq!(
+            validate_claim_json(&cdd_claim, ClaimKind::Scope),
+            field_error("scope_did", "is missing")
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_uuid() {
+        // Wrong number of groups.
+        assert!(parse_uuid("0123456789abcdeffedcba9876543210").is_err());
+        // Wrong group length.
+        assert!(parse_uuid("0123456-789ab-cdef-fedc-ba9876543210").is_err());
+        // Not a hex value.
+        assert!(parse_uuid("0123456z-89ab-cdef-fedc-ba9876543210").is_err());
+    }
+}
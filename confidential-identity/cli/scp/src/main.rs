@@ -4,17 +4,29 @@
 //!
 
 use cli_common::{
-    InvestorDID, Proof, ScopeDID, UniqueID, INVESTORDID_LEN, SCOPEDID_LEN, UNIQUEID_LEN,
+    errors::ScpError, format_uuid, serialize_proof, validate_claim_json, verify_proof_package,
+    ClaimKind, InvestorDID, Proof, ProofEncoding, ScopeDID, UniqueID, INVESTORDID_LEN,
+    SCOPEDID_LEN, UNIQUEID_LEN,
 };
 use confidential_identity::{
     claim_proofs::{Investor, Provider},
-    mocked, CddClaimData, InvestorTrait, ProviderTrait, ScopeClaimData,
+    mocked, verify_cdd_id, CddClaimData, CddId, InvestorTrait, ProviderTrait, ScopeClaimData,
 };
 use rand::{rngs::StdRng, SeedableRng};
 use rand_core::{CryptoRng, OsRng, RngCore};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
 use structopt::StructOpt;
 
+/// The environment variable that holds the Json formatted cdd claim, when no file is given.
+const CDD_CLAIM_ENV_VAR: &str = "SCP_CDD_CLAIM";
+
+/// The environment variable that holds the Json formatted scope claim, when no file is given.
+const SCOPE_CLAIM_ENV_VAR: &str = "SCP_SCOPE_CLAIM";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RawCddClaimData {
     pub investor_did: InvestorDID,
@@ -52,11 +64,13 @@ pub struct CreateClaimProofInfo {
     /// Get the Json formatted claim from file.
     /// If this option is provided along with `rand`,
     /// it will save the randomly generated claim to file.
+    /// If it is absent, the claim is read from the `SCP_CDD_CLAIM` environment variable.
     #[structopt(short, long, parse(from_os_str))]
     cdd_claim: Option<std::path::PathBuf>,
 
     /// The investor provided input which claims the investor DID holds an asset in a
     /// certain scope.
+    /// If it is absent, the claim is read from the `SCP_SCOPE_CLAIM` environment variable.
     #[structopt(short, long, parse(from_os_str))]
     scope_claim: Option<std::path::PathBuf>,
 
@@ -64,6 +78,12 @@ pub struct CreateClaimProofInfo {
     #[structopt(short, long, parse(from_os_str))]
     proof: Option<std::path::PathBuf>,
 
+    /// The encoding of the proof file, either `json` or `base64`.
+    /// With `base64`, the byte fields of the proof are written as base64 strings,
+    /// which is considerably more compact.
+    #[structopt(long, default_value = "json")]
+    encoding: ProofEncoding,
+
     /// Be verbose.
     #[structopt(short, long)]
     verbose: bool,
@@ -89,6 +109,7 @@ pub struct CreateCDDIdInfo {
     /// Get the Json formatted claim from file.
     /// If this option is provided along with `rand`,
     /// it will save the randomly generated claim to file.
+    /// If it is absent, the claim is read from the `SCP_CDD_CLAIM` environment variable.
     #[structopt(short, long, parse(from_os_str))]
     cdd_claim: Option<std::path::PathBuf>,
 
@@ -101,6 +122,27 @@ pub struct CreateCDDIdInfo {
     verbose: bool,
 }
 
+/// polymath-scp -- a simple claim prover.
+///
+/// The polymath-scp/verify-cdd-id utility which recomputes the CDD Id of a CDD Claim and
+/// compares it with a given CDD Id. This lets a CDD consumer confirm that the CDD Id was
+/// honestly computed from the claim.
+#[derive(Clone, Debug, StructOpt)]
+pub struct VerifyCDDIdInfo {
+    /// Get the Json formatted claim from file.
+    /// If it is absent, the claim is read from the `SCP_CDD_CLAIM` environment variable.
+    #[structopt(short, long, parse(from_os_str))]
+    cdd_claim: Option<std::path::PathBuf>,
+
+    /// Read the Json formatted CDD Id from file.
+    #[structopt(long, parse(from_os_str))]
+    cdd_id: std::path::PathBuf,
+
+    /// Be verbose.
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
 /// The polymath-scp/create-cdd-id utility which creates an Identity with a mocked CDD Id.
 #[derive(Clone, Debug, StructOpt)]
 pub struct CreateMockedInvestorUidInfo {
@@ -113,6 +155,24 @@ pub struct CreateMockedInvestorUidInfo {
     formatted: bool,
 }
 
+/// polymath-scp -- a simple claim prover.
+///
+/// The polymath-scp/repl utility which reads the commands from the standard input,
+/// one per line, and keeps the generated claims in memory between the commands.{n}
+/// Commands:{n}
+/// - generate-claim: generate a random cdd claim and scope claim.{n}
+/// - cdd-id: compute the CDD Id of the cdd claim.{n}
+/// - create-proof: create a proof for the scope claim and print it.{n}
+/// - verify-proof: verify the last created proof.{n}
+/// - help: print the commands.{n}
+/// - exit: end the session.
+#[derive(Clone, Debug, StructOpt)]
+pub struct ReplInfo {
+    /// The encoding of the printed proofs, either `json` or `base64`.
+    #[structopt(long, default_value = "json")]
+    encoding: ProofEncoding,
+}
+
 #[derive(Clone, Debug, StructOpt)]
 pub enum CLI {
     /// Create the CDD Id.
@@ -121,8 +181,14 @@ pub enum CLI {
     /// Create a Claim proof.
     CreateClaimProof(CreateClaimProofInfo),
 
+    /// Verify that a CDD Id was computed from a CDD Claim.
+    VerifyCDDId(VerifyCDDIdInfo),
+
     /// Create Mocked CDD Id.
     CreateMockedInvestorUid(CreateMockedInvestorUidInfo),
+
+    /// Start an interactive session.
+    Repl(ReplInfo),
 }
 
 /// Generate a random `InvestorDID` for experiments.
@@ -146,7 +212,38 @@ fn random_unique_id<R: RngCore + CryptoRng>(rng: &mut R) -> UniqueID {
     unique_id
 }
 
-fn process_create_cdd_id(cfg: CreateCDDIdInfo) {
+/// Reads a Json formatted claim of the given `kind` from the file at `path`. If `path` is
+/// absent, the claim is read from the `env_var` environment variable instead. Returns
+/// `ScpError::MissingClaim` if neither is given.
+fn read_claim<T: DeserializeOwned>(
+    path: Option<PathBuf>,
+    env_var: &str,
+    kind: ClaimKind,
+) -> Result<T, ScpError> {
+    let name = kind.name();
+    let json_content = match path {
+        Some(c) => read_file(&c, name)?,
+        None => std::env::var(env_var).map_err(|_| ScpError::MissingClaim {
+            name: name.to_string(),
+            env_var: env_var.to_string(),
+        })?,
+    };
+    validate_claim_json(&json_content, kind)?;
+    serde_json::from_str(&json_content).map_err(|error| ScpError::InvalidClaimJson {
+        reason: error.to_string(),
+    })
+}
+
+/// Reads the file at `path`, whose content is described by `name` in the errors.
+fn read_file(path: &Path, name: &str) -> Result<String, ScpError> {
+    std::fs::read_to_string(path).map_err(|error| ScpError::FileReadError {
+        name: name.to_string(),
+        path: path.to_path_buf(),
+        reason: error.to_string(),
+    })
+}
+
+fn process_create_cdd_id(cfg: CreateCDDIdInfo) -> Result<(), ScpError> {
     let raw_cdd_data = if cfg.rand {
         let mut rng = StdRng::from_seed([42u8; 32]);
         let rand_investor_did = random_investor_did(&mut rng);
@@ -171,16 +268,7 @@ fn process_create_cdd_id(cfg: CreateCDDIdInfo) {
 
         raw_cdd_data
     } else {
-        match cfg.cdd_claim {
-            Some(c) => {
-                let json_file_content =
-                    std::fs::read_to_string(&c).expect("Failed to read the cdd claim from file.");
-                serde_json::from_str(&json_file_content).unwrap_or_else(|error| {
-                    panic!("Failed to deserialize the cdd claim: {}", error)
-                })
-            }
-            None => panic!("You must either pass in a claim file or generate it randomly."),
-        }
+        read_claim(cfg.cdd_claim, CDD_CLAIM_ENV_VAR, ClaimKind::Cdd)?
     };
 
     let cdd_claim = CddClaimData::new(&raw_cdd_data.investor_did, &raw_cdd_data.investor_unique_id);
@@ -206,9 +294,73 @@ fn process_create_cdd_id(cfg: CreateCDDIdInfo) {
         std::fs::write(p, cdd_id_str.as_bytes()).expect("Failed to write the CDD Id to file.");
         println!("Successfully wrote the CDD Id.");
     }
+
+    Ok(())
 }
 
-fn process_create_claim_proof(cfg: CreateClaimProofInfo) {
+/// Returns true if `cdd_id` is the CDD Id of `raw_cdd_claim`.
+fn cdd_id_matches(raw_cdd_claim: &RawCddClaimData, cdd_id: &CddId) -> bool {
+    let cdd_claim = CddClaimData::new(
+        &raw_cdd_claim.investor_did,
+        &raw_cdd_claim.investor_unique_id,
+    );
+    verify_cdd_id(&cdd_claim, &cdd_id.0)
+}
+
+fn process_verify_cdd_id(cfg: VerifyCDDIdInfo) -> Result<(), ScpError> {
+    let raw_cdd_claim: RawCddClaimData =
+        read_claim(cfg.cdd_claim, CDD_CLAIM_ENV_VAR, ClaimKind::Cdd)?;
+    let cdd_id_str = read_file(&cfg.cdd_id, "CDD Id")?;
+    let cdd_id: CddId =
+        serde_json::from_str(&cdd_id_str).map_err(|error| ScpError::InvalidCddId {
+            reason: error.to_string(),
+        })?;
+
+    if cfg.verbose {
+        println!("CDD Id: {:?}", cdd_id_str);
+    }
+
+    if !cdd_id_matches(&raw_cdd_claim, &cdd_id) {
+        return Err(ScpError::CddIdMismatch);
+    }
+    println!("Successfully verified the CDD Id!");
+
+    Ok(())
+}
+
+/// Creates the scope claim proof and packages it with the public inputs of the verifier.
+fn create_proof_package<R: RngCore + CryptoRng>(
+    raw_cdd_claim: &RawCddClaimData,
+    raw_scope_claim: &RawScopeClaimData,
+    rng: &mut R,
+) -> Proof {
+    let cdd_claim = CddClaimData::new(
+        &raw_cdd_claim.investor_did,
+        &raw_cdd_claim.investor_unique_id,
+    );
+    let scope_claim = ScopeClaimData::new(
+        &raw_scope_claim.scope_did,
+        &raw_scope_claim.investor_unique_id,
+    );
+
+    let proof = Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, rng);
+
+    // The verifier needs the cdd_id for the verification. In the wasm/chain interaction, the chain
+    // will pass the cdd_id to the verification function. But, here in the CLI, to make things
+    // easier to implement, we write the CDD_ID as part of the proof for the verifier to read.
+    let cdd_id = Provider::create_cdd_id(&cdd_claim);
+
+    // Similarly to the cdd_id, the investor_did and the scope_did are also placed in the proof
+    // package for easier implementation.
+    Proof {
+        investor_did: raw_cdd_claim.investor_did,
+        scope_did: raw_scope_claim.scope_did,
+        cdd_id,
+        proof,
+    }
+}
+
+fn process_create_claim_proof(cfg: CreateClaimProofInfo) -> Result<(), ScpError> {
     let (raw_cdd_claim, raw_scope_claim) = if cfg.rand {
         let mut rng = StdRng::from_seed([42u8; 32]);
         // let (rand_cdd_claim, rand_scope_claim) = random_claim(&mut rng);
@@ -253,27 +405,9 @@ fn process_create_claim_proof(cfg: CreateClaimProofInfo) {
 
         (raw_cdd_data, raw_scope_data)
     } else {
-        let file_cdd_claim = match cfg.cdd_claim {
-            Some(c) => {
-                let json_file_content =
-                    std::fs::read_to_string(&c).expect("Failed to read the cdd claim from file.");
-                serde_json::from_str(&json_file_content).unwrap_or_else(|error| {
-                    panic!("Failed to deserialize the cdd claim: {}", error)
-                })
-            }
-            None => panic!("You must either pass in a claim file or generate it randomly."),
-        };
-        let file_scope_claim = match cfg.scope_claim {
-            Some(c) => {
-                let json_file_content =
-                    std::fs::read_to_string(&c).expect("Failed to read the scope claim from file.");
-                serde_json::from_str(&json_file_content).unwrap_or_else(|error| {
-                    panic!("Failed to deserialize the scope claim: {}", error)
-                })
-            }
-            None => panic!("You must either pass in a claim file or generate it randomly."),
-        };
-        (file_cdd_claim, file_scope_claim)
+        let cdd_claim = read_claim(cfg.cdd_claim, CDD_CLAIM_ENV_VAR, ClaimKind::Cdd)?;
+        let scope_claim = read_claim(cfg.scope_claim, SCOPE_CLAIM_ENV_VAR, ClaimKind::Scope)?;
+        (cdd_claim, scope_claim)
     };
 
     if cfg.verbose {
@@ -287,36 +421,13 @@ fn process_create_claim_proof(cfg: CreateClaimProofInfo) {
         );
     }
 
-    let cdd_claim = CddClaimData::new(
-        &raw_cdd_claim.investor_did,
-        &raw_cdd_claim.investor_unique_id,
-    );
-    let scope_claim = ScopeClaimData::new(
-        &raw_scope_claim.scope_did,
-        &raw_scope_claim.investor_unique_id,
-    );
-
     let mut seed = [0u8; 32];
     OsRng.fill_bytes(&mut seed);
     let mut rng = StdRng::from_seed(seed);
 
-    let proof = Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng);
-
-    // The verifier needs the cdd_id for the verification. In the wasm/chain interaction, the chain
-    // will pass the cdd_id to the verification function. But, here in the CLI, to make things
-    // easier to implement, we write the CDD_ID as part of the proof for the verifier to read.
-    let cdd_id = Provider::create_cdd_id(&cdd_claim);
-
-    // Similarly to the cdd_id, the investor_did and the scope_did are also placed in the proof
-    // package for easier implementation.
-    let packaged_proof = Proof {
-        investor_did: raw_cdd_claim.investor_did,
-        scope_did: raw_scope_claim.scope_did,
-        cdd_id: cdd_id,
-        proof,
-    };
+    let packaged_proof = create_proof_package(&raw_cdd_claim, &raw_scope_claim, &mut rng);
 
-    let proof_str = serde_json::to_string(&packaged_proof)
+    let proof_str = serialize_proof(&packaged_proof, cfg.encoding)
         .unwrap_or_else(|error| panic!("Failed to serialize the proof: {}", error));
 
     if cfg.verbose {
@@ -327,6 +438,8 @@ fn process_create_claim_proof(cfg: CreateClaimProofInfo) {
         std::fs::write(p, proof_str.as_bytes()).expect("Failed to write the proof to file.");
         println!("Successfully wrote the proof.");
     }
+
+    Ok(())
 }
 
 fn process_create_mocked_investor_uid(cfg: CreateMockedInvestorUidInfo) {
@@ -342,25 +455,279 @@ fn process_create_mocked_investor_uid(cfg: CreateMockedInvestorUidInfo) {
     // Generate the mocked InvestorUid
     let investor_uid = mocked::make_investor_uid(&raw_did);
     if cfg.formatted {
-        println!(
-            "{}-{}-{}-{}-{}",
-            hex::encode(&investor_uid[0..4]),
-            hex::encode(&investor_uid[4..6]),
-            hex::encode(&investor_uid[6..8]),
-            hex::encode(&investor_uid[8..10]),
-            hex::encode(&investor_uid[10..16])
-        );
+        println!("{}", format_uuid(&investor_uid));
     } else {
         println!("{}", hex::encode(investor_uid));
     }
 }
 
+const REPL_HELP: &str = "Commands: generate-claim, cdd-id, create-proof, verify-proof, help, exit";
+
+/// The claims and proofs that are kept in memory during a REPL session.
+#[derive(Default)]
+struct ReplState {
+    cdd_claim: Option<RawCddClaimData>,
+    scope_claim: Option<RawScopeClaimData>,
+    proof: Option<Proof>,
+}
+
+/// Runs the REPL commands read from `input` until `exit` or the end of the input,
+/// and writes the results to `output`. Returns the final state of the session.
+fn run_repl<I: BufRead, O: Write, R: RngCore + CryptoRng>(
+    input: I,
+    output: &mut O,
+    encoding: ProofEncoding,
+    rng: &mut R,
+) -> io::Result<ReplState> {
+    let mut state = ReplState::default();
+    writeln!(output, "{}", REPL_HELP)?;
+
+    for line in input.lines() {
+        let line = line?;
+        match line.trim() {
+            "" => continue,
+            "generate-claim" => {
+                let investor_unique_id = random_unique_id(rng);
+                state.cdd_claim = Some(RawCddClaimData {
+                    investor_did: random_investor_did(rng),
+                    investor_unique_id,
+                });
+                state.scope_claim = Some(RawScopeClaimData {
+                    scope_did: random_scope_did(rng),
+                    investor_unique_id,
+                });
+                state.proof = None;
+                writeln!(output, "Generated a random cdd claim and scope claim.")?;
+            }
+            "cdd-id" => match &state.cdd_claim {
+                Some(raw_cdd_claim) => {
+                    let cdd_claim = CddClaimData::new(
+                        &raw_cdd_claim.investor_did,
+                        &raw_cdd_claim.investor_unique_id,
+                    );
+                    let cdd_id = Provider::create_cdd_id(&cdd_claim);
+                    writeln!(
+                        output,
+                        "CDD Id: {}",
+                        serde_json::to_string(&cdd_id).unwrap_or_else(|error| error.to_string())
+                    )?;
+                }
+                None => writeln!(output, "No cdd claim, run generate-claim first.")?,
+            },
+            "create-proof" => match (&state.cdd_claim, &state.scope_claim) {
+                (Some(raw_cdd_claim), Some(raw_scope_claim)) => {
+                    let proof = create_proof_package(raw_cdd_claim, raw_scope_claim, rng);
+                    match serialize_proof(&proof, encoding) {
+                        Ok(proof_str) => writeln!(output, "Proof: {}", proof_str)?,
+                        Err(error) => writeln!(output, "Failed to serialize the proof: {}", error)?,
+                    }
+                    state.proof = Some(proof);
+                }
+                _ => writeln!(output, "No claims, run generate-claim first.")?,
+            },
+            "verify-proof" => match &state.proof {
+                Some(proof) => match verify_proof_package(proof) {
+                    Ok(()) => writeln!(output, "Successfully verified the proof.")?,
+                    Err(error) => writeln!(output, "Failed to verify the proof: {}", error)?,
+                },
+                None => writeln!(output, "No proof, run create-proof first.")?,
+            },
+            "help" => writeln!(output, "{}", REPL_HELP)?,
+            "exit" | "quit" => break,
+            command => writeln!(output, "Unknown command: {}. {}", command, REPL_HELP)?,
+        }
+    }
+
+    Ok(state)
+}
+
+fn process_repl(cfg: ReplInfo) {
+    let mut seed = [0u8; 32];
+    OsRng.fill_bytes(&mut seed);
+    let mut rng = StdRng::from_seed(seed);
+
+    let stdin = io::stdin();
+    run_repl(stdin.lock(), &mut io::stdout(), cfg.encoding, &mut rng)
+        .unwrap_or_else(|error| panic!("Failed to run the interactive session: {}", error));
+}
+
 fn main() {
     let args: CLI = CLI::from_args();
 
-    match args {
+    let result = match args {
         CLI::CreateCDDId(cfg) => process_create_cdd_id(cfg),
         CLI::CreateClaimProof(cfg) => process_create_claim_proof(cfg),
-        CLI::CreateMockedInvestorUid(cfg) => process_create_mocked_investor_uid(cfg),
+        CLI::VerifyCDDId(cfg) => process_verify_cdd_id(cfg),
+        CLI::CreateMockedInvestorUid(cfg) => {
+            process_create_mocked_investor_uid(cfg);
+            Ok(())
+        }
+        CLI::Repl(cfg) => {
+            process_repl(cfg);
+            Ok(())
+        }
+    };
+
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cdd_id_matches() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let raw_cdd_claim = RawCddClaimData {
+            investor_did: random_investor_did(&mut rng),
+            investor_unique_id: random_unique_id(&mut rng),
+        };
+        let cdd_claim = CddClaimData::new(
+            &raw_cdd_claim.investor_did,
+            &raw_cdd_claim.investor_unique_id,
+        );
+        let cdd_id = Provider::create_cdd_id(&cdd_claim);
+        let cdd_id: CddId = serde_json::from_str(&serde_json::to_string(&cdd_id).unwrap()).unwrap();
+        assert!(cdd_id_matches(&raw_cdd_claim, &cdd_id));
+
+        // A claim with another unique id does not match.
+        let other_raw_cdd_claim = RawCddClaimData {
+            investor_did: raw_cdd_claim.investor_did,
+            investor_unique_id: random_unique_id(&mut rng),
+        };
+        assert!(!cdd_id_matches(&other_raw_cdd_claim, &cdd_id));
+    }
+
+    #[test]
+    fn test_read_claim_from_env() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let raw_cdd_data = RawCddClaimData {
+            investor_did: random_investor_did(&mut rng),
+            investor_unique_id: random_unique_id(&mut rng),
+        };
+        std::env::set_var(
+            CDD_CLAIM_ENV_VAR,
+            serde_json::to_string(&raw_cdd_data).unwrap(),
+        );
+
+        let claim: RawCddClaimData = read_claim(None, CDD_CLAIM_ENV_VAR, ClaimKind::Cdd).unwrap();
+        assert_eq!(claim.investor_did, raw_cdd_data.investor_did);
+        assert_eq!(claim.investor_unique_id, raw_cdd_data.investor_unique_id);
+
+        // An explicit file path takes precedence over the environment variable.
+        let file_path = std::env::temp_dir().join("scp_test_read_claim_from_env.json");
+        let raw_file_data = RawCddClaimData {
+            investor_did: random_investor_did(&mut rng),
+            investor_unique_id: random_unique_id(&mut rng),
+        };
+        std::fs::write(&file_path, serde_json::to_string(&raw_file_data).unwrap()).unwrap();
+        let claim: RawCddClaimData =
+            read_claim(Some(file_path.clone()), CDD_CLAIM_ENV_VAR, ClaimKind::Cdd).unwrap();
+        let _ = std::fs::remove_file(&file_path);
+        std::env::remove_var(CDD_CLAIM_ENV_VAR);
+        assert_eq!(claim.investor_did, raw_file_data.investor_did);
+    }
+
+    #[test]
+    fn test_verify_cdd_id() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let raw_cdd_claim = RawCddClaimData {
+            investor_did: random_investor_did(&mut rng),
+            investor_unique_id: random_unique_id(&mut rng),
+        };
+        let other_raw_cdd_claim = RawCddClaimData {
+            investor_did: random_investor_did(&mut rng),
+            investor_unique_id: random_unique_id(&mut rng),
+        };
+        let cdd_id = Provider::create_cdd_id(&CddClaimData::new(
+            &raw_cdd_claim.investor_did,
+            &raw_cdd_claim.investor_unique_id,
+        ));
+
+        let dir = std::env::temp_dir();
+        let claim_path = dir.join("scp_test_verify_cdd_id_claim.json");
+        let other_claim_path = dir.join("scp_test_verify_cdd_id_other_claim.json");
+        let cdd_id_path = dir.join("scp_test_verify_cdd_id.json");
+        let invalid_cdd_id_path = dir.join("scp_test_verify_cdd_id_invalid.json");
+        std::fs::write(&claim_path, serde_json::to_string(&raw_cdd_claim).unwrap()).unwrap();
+        std::fs::write(
+            &other_claim_path,
+            serde_json::to_string(&other_raw_cdd_claim).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(&cdd_id_path, serde_json::to_string(&cdd_id).unwrap()).unwrap();
+        std::fs::write(&invalid_cdd_id_path, "[1, 2, 3]").unwrap();
+
+        let verify = |cdd_claim: &PathBuf, cdd_id: &PathBuf| {
+            process_verify_cdd_id(VerifyCDDIdInfo {
+                cdd_claim: Some(cdd_claim.clone()),
+                cdd_id: cdd_id.clone(),
+                verbose: false,
+            })
+        };
+        let matching = verify(&claim_path, &cdd_id_path);
+        let mismatching = verify(&other_claim_path, &cdd_id_path);
+        let invalid = verify(&claim_path, &invalid_cdd_id_path);
+        let missing = verify(
+            &claim_path,
+            &dir.join("scp_test_verify_cdd_id_missing.json"),
+        );
+        for path in &[
+            claim_path,
+            other_claim_path,
+            cdd_id_path,
+            invalid_cdd_id_path,
+        ] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert_eq!(matching, Ok(()));
+        assert_eq!(mismatching, Err(ScpError::CddIdMismatch));
+        assert!(matches!(invalid, Err(ScpError::InvalidCddId { .. })));
+        assert!(matches!(missing, Err(ScpError::FileReadError { .. })));
+    }
+
+    #[test]
+    fn test_read_claim_without_claims() {
+        // A variable that no test sets, so that the result does not depend on the
+        // environment of the process or on the order of the tests.
+        let env_var = "SCP_TEST_READ_CLAIM_WITHOUT_CLAIMS_UNSET";
+
+        let error = read_claim::<RawCddClaimData>(None, env_var, ClaimKind::Cdd).unwrap_err();
+        assert_eq!(
+            error,
+            ScpError::MissingClaim {
+                name: "cdd claim".to_string(),
+                env_var: env_var.to_string(),
+            }
+        );
+        let message = error.to_string();
+        assert!(message.contains("file"));
+        assert!(message.contains(env_var));
+        assert!(message.contains("--rand"));
+    }
+
+    #[test]
+    fn test_repl_session() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let script = "create-proof\ngenerate-claim\ncdd-id\ncreate-proof\nverify-proof\nexit\n";
+        let mut output = Vec::new();
+
+        let state = run_repl(
+            script.as_bytes(),
+            &mut output,
+            ProofEncoding::Json,
+            &mut rng,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("No claims, run generate-claim first."));
+        assert!(output.contains("CDD Id: "));
+        assert!(output.contains("Successfully verified the proof."));
+        verify_proof_package(&state.proof.unwrap()).unwrap();
     }
 }
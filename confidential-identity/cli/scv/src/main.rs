@@ -3,11 +3,7 @@
 //! Use `polymath-scv --help` to see the usage.
 //!
 
-use cli_common::Proof;
-use confidential_identity::{
-    claim_proofs::{slice_to_scalar, Verifier},
-    VerifierTrait,
-};
+use cli_common::{deserialize_proof, verify_proof_package, ProofEncoding};
 use serde::{Deserialize, Serialize};
 use structopt::StructOpt;
 
@@ -19,6 +15,10 @@ struct Cli {
     #[structopt(short, long, parse(from_os_str))]
     proof: Option<std::path::PathBuf>,
 
+    /// The encoding of the proof file, either `json` or `base64`.
+    #[structopt(long, default_value = "json")]
+    encoding: ProofEncoding,
+
     /// Verbosity level.
     #[structopt(short, long)]
     verbose: bool,
@@ -31,19 +31,14 @@ fn main() {
         None => panic!("You must provide a proof!"),
     };
 
-    let proof: Proof = serde_json::from_str(&proof_str)
+    let proof = deserialize_proof(&proof_str, args.encoding)
         .unwrap_or_else(|error| panic!("Failed to deserialize the proof: {}", error));
 
     if args.verbose {
         println!("Proof: {:?}", proof_str);
     }
 
-    let result = Verifier::verify_scope_claim_proof(
-        &proof.proof,
-        &slice_to_scalar(&proof.investor_did),
-        &slice_to_scalar(&proof.scope_did),
-        &proof.cdd_id,
-    );
+    let result = verify_proof_package(&proof);
 
     if result.is_ok() {
         println!("Successfully verified the claim!");
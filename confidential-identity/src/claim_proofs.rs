@@ -37,20 +37,28 @@
 
 use crate::{
     errors::{ErrorKind, Fallible},
-    sign::{PublicKey, SecretKey, Signature},
+    sign::{verify_signatures_batch, PublicKey, SecretKey, Signature},
     InvestorTrait, ProviderTrait, VerifierTrait,
 };
 use blake2::{Blake2b, Blake2s, Digest};
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
 use cryptography_core::{
-    cdd_claim::pedersen_commitments::{generate_blinding_factor, PedersenGenerators},
+    asset_proofs::BatchLimits,
+    cdd_claim::pedersen_commitments::{
+        generate_blinding_factor, PedersenGenerators, PEDERSEN_COMMITMENT_NUM_GENERATORS,
+    },
     codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
 };
-use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use curve25519_dalek::{
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+};
 use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use sha2::Sha512;
 use sp_std::prelude::*;
+use subtle::ConstantTimeEq;
 
 /// Create a scalar from a slice of data.
 pub fn slice_to_scalar(data: &[u8]) -> Scalar {
@@ -65,6 +73,78 @@ pub fn slice_to_ristretto_point(data: &[u8]) -> RistrettoPoint {
     RistrettoPoint::from_uniform_bytes(&hash)
 }
 
+/// The method used to map a SCOPE_DID to a Ristretto point when computing the SCOPE_ID.
+///
+/// Both methods map a 64-byte digest to the curve using Elligator, and only differ in
+/// the digest. The prover and the verifier must agree on the method, since a proof
+/// generated with one method never verifies with the other.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HashToCurve {
+    /// Elligator over a Blake2b digest. This is the default.
+    Blake2bElligator,
+    /// `RistrettoPoint::hash_from_bytes::<Sha512>`, using SHA-512 from the `sha2` crate.
+    Sha512,
+}
+
+impl Default for HashToCurve {
+    fn default() -> Self {
+        HashToCurve::Blake2bElligator
+    }
+}
+
+impl HashToCurve {
+    /// Maps `data` to a Ristretto point using this method.
+    pub fn map(&self, data: &[u8]) -> RistrettoPoint {
+        match self {
+            HashToCurve::Blake2bElligator => slice_to_ristretto_point(data),
+            HashToCurve::Sha512 => RistrettoPoint::hash_from_bytes::<Sha512>(data),
+        }
+    }
+}
+
+/// Compute the SCOPE_ID. \
+/// SCOPE_ID = INVESTOR_UNIQUE_ID * HashToCurve(SCOPE_DID)
+pub fn compute_scope_id(
+    scope_claim: &ScopeClaimData,
+    hash_to_curve: HashToCurve,
+) -> RistrettoPoint {
+    scope_claim.investor_unique_id * hash_to_curve.map(scope_claim.scope_did.as_bytes())
+}
+
+/// The maximum length of a SCOPE_DID, e.g., a 12 bytes ticker or a 32 bytes DID.
+pub const MAX_SCOPE_DID_LEN: usize = 32;
+
+/// The length of an INVESTOR_UNIQUE_ID, which is a UUID.
+pub const UNIQUE_ID_LEN: usize = 16;
+
+/// Compute the SCOPE_ID from the raw bytes of the SCOPE_DID and of the INVESTOR_UNIQUE_ID,
+/// for the light clients that do not have a `ScopeClaimData`. The result is the same as
+/// `compute_scope_id` of `ScopeClaimData::new(scope_did, unique_id)`.
+pub fn compute_scope_id_from_parts(
+    scope_did: &[u8],
+    unique_id: &[u8],
+    hash_to_curve: HashToCurve,
+) -> Fallible<RistrettoPoint> {
+    ensure!(
+        !scope_did.is_empty() && scope_did.len() <= MAX_SCOPE_DID_LEN,
+        ErrorKind::InvalidScopeDidLength {
+            length: scope_did.len()
+        }
+    );
+    ensure!(
+        unique_id.len() == UNIQUE_ID_LEN,
+        ErrorKind::InvalidUniqueIdLength {
+            length: unique_id.len()
+        }
+    );
+
+    Ok(compute_scope_id(
+        &ScopeClaimData::new(scope_did, unique_id),
+        hash_to_curve,
+    ))
+}
+
 /// The data needed to generate a CDD ID.
 pub type CddClaimData = cryptography_core::cdd_claim::CddClaimData;
 
@@ -139,6 +219,69 @@ impl Decode for ScopeClaimProof {
     }
 }
 
+/// The length of an encoded `ScopeClaimProof`: the signature, the two challenge responses
+/// and the two points of the `ZkProofData`, and the scope id.
+pub const SCOPE_CLAIM_PROOF_LEN: usize = 224;
+
+/// The curve points of an encoded `ScopeClaimProof`, and their byte offsets.
+pub const SCOPE_CLAIM_PROOF_POINTS: [(&str, usize); 3] = [
+    ("subtract_expressions_res", 128),
+    ("blinded_scope_did_hash", 160),
+    ("scope_id", 192),
+];
+
+/// The curve points of a `ScopeClaimProof` that is serialized with serde, and their Json
+/// pointers relative to the serialized proof.
+#[cfg(feature = "serde")]
+pub const SCOPE_CLAIM_PROOF_JSON_POINTS: [(&str, &str); 3] = [
+    (
+        "subtract_expressions_res",
+        "/proof_scope_id_cdd_id_match/subtract_expressions_res",
+    ),
+    (
+        "blinded_scope_did_hash",
+        "/proof_scope_id_cdd_id_match/blinded_scope_did_hash",
+    ),
+    ("scope_id", "/scope_id"),
+];
+
+/// Decodes the compressed curve point `field` of a proof. Returns
+/// `ErrorKind::InvalidProofFieldLength` if it is not 32 bytes long, and
+/// `ErrorKind::InvalidProofPoint` if it is not a canonical encoding of a Ristretto point.
+pub fn decode_proof_point(field: &'static str, bytes: &[u8]) -> Fallible<RistrettoPoint> {
+    ensure!(
+        bytes.len() == 32,
+        ErrorKind::InvalidProofFieldLength {
+            field,
+            length: bytes.len(),
+            expected: 32,
+        }
+    );
+    CompressedRistretto::from_slice(bytes)
+        .decompress()
+        .ok_or_else(|| ErrorKind::InvalidProofPoint { field }.into())
+}
+
+impl ScopeClaimProof {
+    /// Decodes an encoded `ScopeClaimProof` as `Decode` does, but reports the field that
+    /// is invalid: `ErrorKind::InvalidProofFieldLength` for the `proof` field if it is not
+    /// `SCOPE_CLAIM_PROOF_LEN` bytes long, and `ErrorKind::InvalidProofPoint` naming the
+    /// first curve point that is not canonically encoded.
+    pub fn decode_fields(bytes: &[u8]) -> Fallible<Self> {
+        let length_error = || ErrorKind::InvalidProofFieldLength {
+            field: "proof",
+            length: bytes.len(),
+            expected: SCOPE_CLAIM_PROOF_LEN,
+        };
+        ensure!(bytes.len() == SCOPE_CLAIM_PROOF_LEN, length_error());
+        for (field, offset) in SCOPE_CLAIM_PROOF_POINTS.iter() {
+            decode_proof_point(*field, &bytes[*offset..*offset + 32])?;
+        }
+        // The length and the points are the only parts of the decoding that can fail.
+        Self::decode(&mut &bytes[..]).map_err(|_| length_error().into())
+    }
+}
+
 const ZK_PROOF_DATA_CHG_RESPONSES: usize = 2;
 
 /// Stores the zero knowlegde proof data for scope_id and cdd_id matching.
@@ -192,8 +335,134 @@ impl ZkProofData {
     }
 }
 
+/// Contains a single Zero Knowledge proof that several SCOPE_IDs are wellformed and
+/// match the same CDD_ID. The investors use it instead of one `ScopeClaimProof`
+/// per scope when they disclose multiple scopes at once.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BatchedScopeClaimProof {
+    pub proof_scope_ids_cdd_id_match: BatchedZkProofData,
+    pub scope_ids: Vec<RistrettoPoint>,
+}
+
+impl Encode for BatchedScopeClaimProof {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.proof_scope_ids_cdd_id_match.size_hint() + 4 + 32 * self.scope_ids.len()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        let scope_ids = self
+            .scope_ids
+            .iter()
+            .map(RistrettoPointEncoder)
+            .collect::<Vec<_>>();
+
+        self.proof_scope_ids_cdd_id_match.encode_to(dest);
+        scope_ids.encode_to(dest);
+    }
+}
+
+impl Decode for BatchedScopeClaimProof {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let proof_scope_ids_cdd_id_match = <BatchedZkProofData>::decode(input)?;
+        let scope_ids = <Vec<RistrettoPointDecoder>>::decode(input)?
+            .into_iter()
+            .map(|decoder| decoder.0)
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            proof_scope_ids_cdd_id_match,
+            scope_ids,
+        })
+    }
+}
+
+/// Stores the zero knowlegde proof data for matching multiple scope_ids with a cdd_id.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BatchedZkProofData {
+    challenge_responses: [Scalar; ZK_PROOF_DATA_CHG_RESPONSES],
+    blinded_cdd_id: RistrettoPoint,
+    blinded_scope_did_hashes: Vec<RistrettoPoint>,
+}
+
+impl Encode for BatchedZkProofData {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        [
+            ScalarEncoder(&self.challenge_responses[0]),
+            ScalarEncoder(&self.challenge_responses[1]),
+        ]
+        .size_hint()
+            + RistrettoPointEncoder(&self.blinded_cdd_id).size_hint()
+            + 4
+            + 32 * self.blinded_scope_did_hashes.len()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        let blinded_scope_did_hashes = self
+            .blinded_scope_did_hashes
+            .iter()
+            .map(RistrettoPointEncoder)
+            .collect::<Vec<_>>();
+
+        [
+            ScalarEncoder(&self.challenge_responses[0]),
+            ScalarEncoder(&self.challenge_responses[1]),
+        ]
+        .encode_to(dest);
+        RistrettoPointEncoder(&self.blinded_cdd_id).encode_to(dest);
+        blinded_scope_did_hashes.encode_to(dest);
+    }
+}
+
+impl Decode for BatchedZkProofData {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let challenge_responses_decoder = <[ScalarDecoder; 2]>::decode(input)?;
+        let blinded_cdd_id_decoder = <RistrettoPointDecoder>::decode(input)?;
+        let blinded_scope_did_hashes = <Vec<RistrettoPointDecoder>>::decode(input)?
+            .into_iter()
+            .map(|decoder| decoder.0)
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            challenge_responses: [
+                challenge_responses_decoder[0].0,
+                challenge_responses_decoder[1].0,
+            ],
+            blinded_cdd_id: blinded_cdd_id_decoder.0,
+            blinded_scope_did_hashes,
+        })
+    }
+}
+
 const SIGNATURE_MESSAGE: &str = "SCOPE_ID is Wellformed";
 
+/// Returns the message that the wellformedness signature of a scope claim proof signs when
+/// the proof is bound to a verifier-supplied `nonce`. Besides the nonce, the message binds
+/// the INVESTOR_DID and the SCOPE_DID, so a proof cannot be replayed in another session.
+pub fn make_message_with_nonce(
+    investor_did: &Scalar,
+    scope_did: &Scalar,
+    nonce: &[u8],
+) -> [u8; 32] {
+    Blake2s::default()
+        .chain(SIGNATURE_MESSAGE.as_bytes())
+        .chain(investor_did.as_bytes())
+        .chain(scope_did.as_bytes())
+        .chain(nonce)
+        .finalize()
+        .into()
+}
+
+/// Returns `ErrorKind::BatchTooLarge` if a batch of `size` entries exceeds `max`, one of the
+/// bounds of a `BatchLimits`.
+fn ensure_batch_size(size: usize, max: usize) -> Fallible<()> {
+    ensure!(size <= max, ErrorKind::BatchTooLarge { size, max });
+    Ok(())
+}
+
 // -------------------------------------------------------------------------------------------
 // -                                Trait Implementations                                    -
 // -------------------------------------------------------------------------------------------
@@ -207,22 +476,122 @@ impl ProviderTrait for Provider {
     }
 }
 
+/// Recomputes the CDD Id of `claim` and compares it with `expected` in constant time.
+/// This lets a CDD consumer confirm that a CDD Id was honestly computed from a claim.
+pub fn verify_cdd_id(claim: &CddClaimData, expected: &RistrettoPoint) -> bool {
+    let cdd_id = Provider::create_cdd_id(claim);
+    cdd_id.0.ct_eq(expected).into()
+}
+
 /// Implements the APIs of the Investor.
 pub struct Investor;
 
-impl InvestorTrait for Investor {
-    fn create_scope_claim_proof<R: RngCore + CryptoRng>(
+impl Investor {
+    /// Creates a SCOPE_ID for each of the `scope_dids` and generates a single proof that: \
+    /// 1. Every scope id has the following form: INVESTOR_UNIQUE_ID * Hash(SCOPE_DID) \
+    /// 2. The cdd_id and all the scope ids share the same INVESTOR_UNIQUE_ID
+    ///
+    /// The batched proof is smaller than one `ScopeClaimProof` per scope.
+    ///
+    /// # Arguments
+    /// * `cdd_claim` is the CDD claim from which the CDD_ID was generated.
+    /// * `scope_dids` are the SCOPE_DIDs of the scopes being disclosed.
+    ///
+    /// # Errors
+    /// * `EmptyBatch`: If `scope_dids` is empty.
+    pub fn create_batched_scope_claim_proof<R: RngCore + CryptoRng>(
+        cdd_claim: &CddClaimData,
+        scope_dids: &[Scalar],
+        rng: &mut R,
+    ) -> Fallible<BatchedScopeClaimProof> {
+        Self::create_batched_scope_claim_proof_with_limits(
+            cdd_claim,
+            scope_dids,
+            &BatchLimits::default(),
+            rng,
+        )
+    }
+
+    /// Same as `create_batched_scope_claim_proof`, but rejects more than
+    /// `limits.max_aggregation` scopes.
+    pub fn create_batched_scope_claim_proof_with_limits<R: RngCore + CryptoRng>(
+        cdd_claim: &CddClaimData,
+        scope_dids: &[Scalar],
+        limits: &BatchLimits,
+        rng: &mut R,
+    ) -> Fallible<BatchedScopeClaimProof> {
+        ensure!(!scope_dids.is_empty(), ErrorKind::EmptyBatch);
+        ensure_batch_size(scope_dids.len(), limits.max_aggregation)?;
+
+        let scope_did_hashes = scope_dids
+            .iter()
+            .map(|scope_did| slice_to_ristretto_point(scope_did.as_bytes()))
+            .collect::<Vec<_>>();
+        let scope_ids = scope_did_hashes
+            .iter()
+            .map(|scope_did_hash| cdd_claim.investor_unique_id * scope_did_hash)
+            .collect::<Vec<_>>();
+        let cdd_id = cryptography_core::cdd_claim::compute_cdd_id(cdd_claim);
+
+        let proof_scope_ids_cdd_id_match =
+            gen_batched_zkp(&scope_did_hashes, &scope_ids, &cdd_id.0, cdd_claim, rng);
+
+        Ok(BatchedScopeClaimProof {
+            proof_scope_ids_cdd_id_match,
+            scope_ids,
+        })
+    }
+
+    /// Same as `create_scope_claim_proof`, but maps the SCOPE_DID to a point using
+    /// `hash_to_curve`. The verifier must use the same method.
+    pub fn create_scope_claim_proof_with_hash_to_curve<R: RngCore + CryptoRng>(
+        cdd_claim: &CddClaimData,
+        scope_claim: &ScopeClaimData,
+        hash_to_curve: HashToCurve,
+        rng: &mut R,
+    ) -> ScopeClaimProof {
+        Self::create_scope_claim_proof_with_message(
+            cdd_claim,
+            scope_claim,
+            hash_to_curve,
+            SIGNATURE_MESSAGE.as_bytes(),
+            rng,
+        )
+    }
+
+    /// Same as `create_scope_claim_proof`, but binds the verifier-supplied `nonce` into the
+    /// proof, so that the proof only verifies in the session of that nonce.
+    pub fn create_scope_claim_proof_with_nonce<R: RngCore + CryptoRng>(
+        cdd_claim: &CddClaimData,
+        scope_claim: &ScopeClaimData,
+        nonce: &[u8],
+        rng: &mut R,
+    ) -> ScopeClaimProof {
+        let message =
+            make_message_with_nonce(&cdd_claim.investor_did, &scope_claim.scope_did, nonce);
+        Self::create_scope_claim_proof_with_message(
+            cdd_claim,
+            scope_claim,
+            HashToCurve::default(),
+            &message,
+            rng,
+        )
+    }
+
+    fn create_scope_claim_proof_with_message<R: RngCore + CryptoRng>(
         cdd_claim: &CddClaimData,
         scope_claim: &ScopeClaimData,
+        hash_to_curve: HashToCurve,
+        message: &[u8],
         rng: &mut R,
     ) -> ScopeClaimProof {
-        let scope_did_hash = slice_to_ristretto_point(scope_claim.scope_did.as_bytes());
+        let scope_did_hash = hash_to_curve.map(scope_claim.scope_did.as_bytes());
         let scope_id = scope_claim.investor_unique_id * scope_did_hash;
         let cdd_id = cryptography_core::cdd_claim::compute_cdd_id(cdd_claim);
 
         let public_key = PublicKey { key: scope_id };
         let signature = SecretKey::new(scope_claim.investor_unique_id).sign(
-            SIGNATURE_MESSAGE.as_bytes(),
+            message,
             &public_key,
             &scope_did_hash,
         );
@@ -238,27 +607,151 @@ impl InvestorTrait for Investor {
     }
 }
 
+impl InvestorTrait for Investor {
+    fn create_scope_claim_proof<R: RngCore + CryptoRng>(
+        cdd_claim: &CddClaimData,
+        scope_claim: &ScopeClaimData,
+        rng: &mut R,
+    ) -> ScopeClaimProof {
+        Self::create_scope_claim_proof_with_hash_to_curve(
+            cdd_claim,
+            scope_claim,
+            HashToCurve::default(),
+            rng,
+        )
+    }
+}
+
+/// The parts of the scope claim proofs that only depend on the investor's CDD claim, computed
+/// once. Use this to create the scope claim proofs of an investor for many scopes.
+pub struct PreparedInvestor {
+    investor_unique_id: Scalar,
+    /// The signing key of the scope ids is the INVESTOR_UNIQUE_ID, for every scope.
+    secret_key: SecretKey,
+    cdd_id: CddId,
+    /// CDD_ID - INVESTOR_DID * G0, which the ZKP proves to be a commitment to the
+    /// INVESTOR_UNIQUE_ID.
+    cdd_id_minus_did: RistrettoPoint,
+    cdd_id_random_blind: Scalar,
+    /// The Pedersen generators, which are costly to derive.
+    g: [RistrettoPoint; PEDERSEN_COMMITMENT_NUM_GENERATORS],
+}
+
+impl PreparedInvestor {
+    pub fn new(cdd_claim: &CddClaimData) -> Self {
+        let g = PedersenGenerators::default().generators;
+        let cdd_id = cryptography_core::cdd_claim::compute_cdd_id(cdd_claim);
+        PreparedInvestor {
+            investor_unique_id: cdd_claim.investor_unique_id,
+            secret_key: SecretKey::new(cdd_claim.investor_unique_id),
+            cdd_id,
+            cdd_id_minus_did: cdd_id.0 - cdd_claim.investor_did * g[0],
+            cdd_id_random_blind: generate_blinding_factor(
+                cdd_claim.investor_did,
+                cdd_claim.investor_unique_id,
+            ),
+            g,
+        }
+    }
+
+    /// The CDD_ID of the investor.
+    pub fn cdd_id(&self) -> CddId {
+        self.cdd_id
+    }
+
+    /// Creates the scope claim proof for `scope_did`. The proof is the same as the one of
+    /// `Investor::create_scope_claim_proof` for the same randomness, and verifies with
+    /// `Verifier::verify_scope_claim_proof`.
+    pub fn prove_for_scope<R: RngCore + CryptoRng>(
+        &self,
+        scope_did: &Scalar,
+        rng: &mut R,
+    ) -> ScopeClaimProof {
+        let scope_did_hash = HashToCurve::default().map(scope_did.as_bytes());
+        let scope_id = self.investor_unique_id * scope_did_hash;
+
+        let signature = self.secret_key.sign(
+            SIGNATURE_MESSAGE.as_bytes(),
+            &PublicKey { key: scope_id },
+            &scope_did_hash,
+        );
+
+        let proof_scope_id_cdd_id_match = gen_zkp_from_parts(
+            &scope_did_hash,
+            &scope_id,
+            &self.cdd_id_minus_did,
+            &self.investor_unique_id,
+            &self.cdd_id_random_blind,
+            &self.g,
+            rng,
+        );
+
+        ScopeClaimProof {
+            proof_scope_id_wellformed: signature,
+            proof_scope_id_cdd_id_match,
+            scope_id,
+        }
+    }
+}
+
 /// Implements the APIs of the Verifier.
 pub struct Verifier;
 
-impl VerifierTrait for Verifier {
-    fn verify_scope_claim_proof(
+impl Verifier {
+    /// Same as `verify_scope_claim_proof`, but maps the SCOPE_DID to a point using
+    /// `hash_to_curve`, which must be the method the investor used.
+    pub fn verify_scope_claim_proof_with_hash_to_curve(
+        proof: &ScopeClaimProof,
+        investor_did: &Scalar,
+        scope_did: &Scalar,
+        cdd_id: &CddId,
+        hash_to_curve: HashToCurve,
+    ) -> Fallible<()> {
+        Self::verify_scope_claim_proof_with_message(
+            proof,
+            investor_did,
+            scope_did,
+            cdd_id,
+            hash_to_curve,
+            SIGNATURE_MESSAGE.as_bytes(),
+        )
+    }
+
+    /// Same as `verify_scope_claim_proof`, but for a proof made with
+    /// `create_scope_claim_proof_with_nonce`. The proof only verifies with the same `nonce`.
+    pub fn verify_scope_claim_proof_with_nonce(
+        proof: &ScopeClaimProof,
+        investor_did: &Scalar,
+        scope_did: &Scalar,
+        cdd_id: &CddId,
+        nonce: &[u8],
+    ) -> Fallible<()> {
+        let message = make_message_with_nonce(investor_did, scope_did, nonce);
+        Self::verify_scope_claim_proof_with_message(
+            proof,
+            investor_did,
+            scope_did,
+            cdd_id,
+            HashToCurve::default(),
+            &message,
+        )
+    }
+
+    fn verify_scope_claim_proof_with_message(
         proof: &ScopeClaimProof,
         investor_did: &Scalar,
         scope_did: &Scalar,
         cdd_id: &CddId,
+        hash_to_curve: HashToCurve,
+        message: &[u8],
     ) -> Fallible<()> {
-        let scope_did_hash = slice_to_ristretto_point(scope_did.as_bytes());
+        let scope_did_hash = hash_to_curve.map(scope_did.as_bytes());
 
         let public_key = PublicKey {
             key: proof.scope_id,
         };
 
-        public_key.verify(
-            SIGNATURE_MESSAGE.as_bytes(),
-            &proof.proof_scope_id_wellformed,
-            &scope_did_hash,
-        )?;
+        public_key.verify(message, &proof.proof_scope_id_wellformed, &scope_did_hash)?;
 
         ensure! {
         verify_zkp(
@@ -271,6 +764,178 @@ impl VerifierTrait for Verifier {
 
         Ok(())
     }
+
+    /// Verifies only the ZKP of a scope claim proof, i.e., that the CDD_ID and the SCOPE_ID of
+    /// the proof are derived from the same INVESTOR_UNIQUE_ID, without the signature that
+    /// proves the wellformedness of the SCOPE_ID. This lets a verifier check the "same
+    /// investor" statement separately from the "owns this scope" statement.
+    pub fn verify_shared_unique_id(
+        cdd_id: &CddId,
+        investor_did: &Scalar,
+        scope_did: &Scalar,
+        proof: &ScopeClaimProof,
+    ) -> Fallible<()> {
+        let scope_did_hash = HashToCurve::default().map(scope_did.as_bytes());
+        ensure!(
+            verify_zkp(
+                &proof.proof_scope_id_cdd_id_match,
+                &proof.scope_id,
+                &cdd_id.0,
+                investor_did,
+                &scope_did_hash,
+            ),
+            ErrorKind::ZkpError
+        );
+
+        Ok(())
+    }
+
+    /// Verifies a batched proof over all the `scope_dids` at once.
+    ///
+    /// # Arguments
+    /// * `proof`: the proof obtained from the call to `create_batched_scope_claim_proof`.
+    /// * `investor_did`: the INVESTOR_DID.
+    /// * `scope_dids`: the SCOPE_DIDs, in the same order as they were proven.
+    /// * `cdd_id`: the CDD_ID.
+    ///
+    /// # Errors
+    /// * `BatchSizeMismatch`: If the proof does not cover exactly `scope_dids`.
+    /// * `ZkpError`: If any scope id is not wellformed or does not match the cdd id.
+    pub fn verify_batched_scope_claim_proof(
+        proof: &BatchedScopeClaimProof,
+        investor_did: &Scalar,
+        scope_dids: &[Scalar],
+        cdd_id: &CddId,
+    ) -> Fallible<()> {
+        Self::verify_batched_scope_claim_proof_with_limits(
+            proof,
+            investor_did,
+            scope_dids,
+            cdd_id,
+            &BatchLimits::default(),
+        )
+    }
+
+    /// Same as `verify_batched_scope_claim_proof`, but rejects more than
+    /// `limits.max_aggregation` scopes before verifying the proof.
+    pub fn verify_batched_scope_claim_proof_with_limits(
+        proof: &BatchedScopeClaimProof,
+        investor_did: &Scalar,
+        scope_dids: &[Scalar],
+        cdd_id: &CddId,
+        limits: &BatchLimits,
+    ) -> Fallible<()> {
+        ensure!(!scope_dids.is_empty(), ErrorKind::EmptyBatch);
+        ensure_batch_size(scope_dids.len(), limits.max_aggregation)?;
+        let proof_size = proof.scope_ids.len();
+        let blinded_size = proof
+            .proof_scope_ids_cdd_id_match
+            .blinded_scope_did_hashes
+            .len();
+        ensure!(
+            proof_size == scope_dids.len() && proof_size == blinded_size,
+            ErrorKind::BatchSizeMismatch {
+                proof: proof_size,
+                given: scope_dids.len(),
+            }
+        );
+
+        let scope_did_hashes = scope_dids
+            .iter()
+            .map(|scope_did| slice_to_ristretto_point(scope_did.as_bytes()))
+            .collect::<Vec<_>>();
+
+        ensure!(
+            verify_batched_zkp(
+                &proof.proof_scope_ids_cdd_id_match,
+                &proof.scope_ids,
+                &cdd_id.0,
+                investor_did,
+                &scope_did_hashes,
+            ),
+            ErrorKind::ZkpError
+        );
+
+        Ok(())
+    }
+
+    /// Verifies several scope claim proofs. Each item holds the proof, the INVESTOR_DID, the
+    /// SCOPE_DID, and the CDD_ID it is verified against.
+    ///
+    /// The wellformedness signatures of all the scope ids are verified as a single batch.
+    /// A `BatchSignatureError` names the first proof whose signature is invalid.
+    pub fn verify_scope_claim_proofs_batch<R: RngCore + CryptoRng>(
+        items: &[(&ScopeClaimProof, &Scalar, &Scalar, &CddId)],
+        rng: &mut R,
+    ) -> Fallible<()> {
+        Self::verify_scope_claim_proofs_batch_with_limits(items, &BatchLimits::default(), rng)
+    }
+
+    /// Same as `verify_scope_claim_proofs_batch`, but rejects more than `limits.max_items`
+    /// proofs before verifying any of them.
+    pub fn verify_scope_claim_proofs_batch_with_limits<R: RngCore + CryptoRng>(
+        items: &[(&ScopeClaimProof, &Scalar, &Scalar, &CddId)],
+        limits: &BatchLimits,
+        rng: &mut R,
+    ) -> Fallible<()> {
+        ensure_batch_size(items.len(), limits.max_items)?;
+
+        let hash_to_curve = HashToCurve::default();
+        let scope_did_hashes = items
+            .iter()
+            .map(|(_, _, scope_did, _)| hash_to_curve.map(scope_did.as_bytes()))
+            .collect::<Vec<_>>();
+
+        let signatures = items
+            .iter()
+            .zip(&scope_did_hashes)
+            .map(|((proof, _, _, _), scope_did_hash)| {
+                (
+                    SIGNATURE_MESSAGE.as_bytes(),
+                    proof.proof_scope_id_wellformed,
+                    PublicKey {
+                        key: proof.scope_id,
+                    },
+                    *scope_did_hash,
+                )
+            })
+            .collect::<Vec<_>>();
+        verify_signatures_batch(&signatures, rng)?;
+
+        for ((proof, investor_did, _, cdd_id), scope_did_hash) in
+            items.iter().zip(&scope_did_hashes)
+        {
+            ensure!(
+                verify_zkp(
+                    &proof.proof_scope_id_cdd_id_match,
+                    &proof.scope_id,
+                    &cdd_id.0,
+                    investor_did,
+                    scope_did_hash,
+                ),
+                ErrorKind::ZkpError
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl VerifierTrait for Verifier {
+    fn verify_scope_claim_proof(
+        proof: &ScopeClaimProof,
+        investor_did: &Scalar,
+        scope_did: &Scalar,
+        cdd_id: &CddId,
+    ) -> Fallible<()> {
+        Self::verify_scope_claim_proof_with_hash_to_curve(
+            proof,
+            investor_did,
+            scope_did,
+            cdd_id,
+            HashToCurve::default(),
+        )
+    }
 }
 
 // -------------------------------------------------------------------------------------------
@@ -290,6 +955,29 @@ fn gen_zkp<R: RngCore + CryptoRng>(
     let cdd_id_random_blind =
         generate_blinding_factor(cdd_claim.investor_did, cdd_claim.investor_unique_id);
 
+    gen_zkp_from_parts(
+        scope_did_hash,
+        scope_id,
+        &expr2,
+        &cdd_claim.investor_unique_id,
+        &cdd_id_random_blind,
+        &g,
+        rng,
+    )
+}
+
+/// Same as `gen_zkp`, but takes the parts that only depend on the CDD claim, i.e.,
+/// CDD_ID - INVESTOR_DID * G0, and the blinding factor of the CDD_ID, and the Pedersen
+/// generators `g`.
+fn gen_zkp_from_parts<R: RngCore + CryptoRng>(
+    scope_did_hash: &RistrettoPoint,
+    scope_id: &RistrettoPoint,
+    expr2: &RistrettoPoint,
+    investor_unique_id: &Scalar,
+    cdd_id_random_blind: &Scalar,
+    g: &[RistrettoPoint; PEDERSEN_COMMITMENT_NUM_GENERATORS],
+    rng: &mut R,
+) -> ZkProofData {
     let subtract_expressions_res = expr2 - scope_id;
     let rands: [Scalar; 2] = [Scalar::random(rng), Scalar::random(rng)];
     let blinded_scope_did_hash = rands[0] * (g[1] - scope_did_hash) + rands[1] * g[2];
@@ -303,7 +991,7 @@ fn gen_zkp<R: RngCore + CryptoRng>(
     let challenge = slice_to_scalar(&challenge);
 
     let challenge_responses = [
-        cdd_claim.investor_unique_id * challenge + rands[0],
+        investor_unique_id * challenge + rands[0],
         cdd_id_random_blind * challenge + rands[1],
     ];
 
@@ -339,6 +1027,91 @@ fn verify_zkp(
     lhs == rhs
 }
 
+/// Computes the Fiat-Shamir challenge of the batched ZKP.
+fn batched_zkp_challenge(
+    proof: &BatchedZkProofData,
+    scope_ids: &[RistrettoPoint],
+    scope_did_hashes: &[RistrettoPoint],
+    expr2: &RistrettoPoint,
+) -> Scalar {
+    let hasher = Blake2s::default()
+        .chain(proof.blinded_cdd_id.compress().to_bytes())
+        .chain(expr2.compress().to_bytes());
+    let hasher = proof
+        .blinded_scope_did_hashes
+        .iter()
+        .chain(scope_ids)
+        .chain(scope_did_hashes)
+        .fold(hasher, |hasher, point| {
+            hasher.chain(point.compress().to_bytes())
+        });
+    let challenge: [u8; 32] = hasher.finalize().into();
+
+    slice_to_scalar(&challenge)
+}
+
+/// ZKP that all the scope ids use the same scalar as the one in the cdd id.
+/// The blinding of the investor unique id and its response are shared by all the
+/// scope ids, therefore the proof only grows by one point per scope.
+fn gen_batched_zkp<R: RngCore + CryptoRng>(
+    scope_did_hashes: &[RistrettoPoint],
+    scope_ids: &[RistrettoPoint],
+    cdd_id: &RistrettoPoint,
+    cdd_claim: &CddClaimData,
+    rng: &mut R,
+) -> BatchedZkProofData {
+    let g = PedersenGenerators::default().generators;
+    let expr2 = cdd_id - cdd_claim.investor_did * g[0];
+    let cdd_id_random_blind =
+        generate_blinding_factor(cdd_claim.investor_did, cdd_claim.investor_unique_id);
+
+    let rands: [Scalar; 2] = [Scalar::random(rng), Scalar::random(rng)];
+    let mut proof = BatchedZkProofData {
+        challenge_responses: [Scalar::zero(); ZK_PROOF_DATA_CHG_RESPONSES],
+        blinded_cdd_id: rands[0] * g[1] + rands[1] * g[2],
+        blinded_scope_did_hashes: scope_did_hashes
+            .iter()
+            .map(|scope_did_hash| rands[0] * scope_did_hash)
+            .collect(),
+    };
+
+    let challenge = batched_zkp_challenge(&proof, scope_ids, scope_did_hashes, &expr2);
+    proof.challenge_responses = [
+        cdd_claim.investor_unique_id * challenge + rands[0],
+        cdd_id_random_blind * challenge + rands[1],
+    ];
+
+    proof
+}
+
+/// Verify the batched ZKP.
+fn verify_batched_zkp(
+    proof: &BatchedZkProofData,
+    scope_ids: &[RistrettoPoint],
+    cdd_id: &RistrettoPoint,
+    investor_did: &Scalar,
+    scope_did_hashes: &[RistrettoPoint],
+) -> bool {
+    let g = PedersenGenerators::default().generators;
+    let expr2 = cdd_id - investor_did * g[0];
+    let challenge = batched_zkp_challenge(proof, scope_ids, scope_did_hashes, &expr2);
+
+    let lhs = proof.challenge_responses[0] * g[1] + proof.challenge_responses[1] * g[2];
+    let rhs = challenge * expr2 + proof.blinded_cdd_id;
+    if lhs != rhs {
+        return false;
+    }
+
+    scope_did_hashes
+        .iter()
+        .zip(scope_ids)
+        .zip(&proof.blinded_scope_did_hashes)
+        .all(|((scope_did_hash, scope_id), blinded_scope_did_hash)| {
+            proof.challenge_responses[0] * scope_did_hash
+                == challenge * scope_id + blinded_scope_did_hash
+        })
+}
+
 // ------------------------------------------------------------------------
 // Tests
 // ------------------------------------------------------------------------
@@ -384,6 +1157,153 @@ mod tests {
         result.unwrap();
     }
 
+    #[test]
+    fn verify_prepared_proofs() {
+        let mut rng = StdRng::from_seed(SEED);
+        let (cdd_claim, _) = crate::random_claim(&mut rng);
+        let cdd_id = Provider::create_cdd_id(&cdd_claim);
+        let prepared = PreparedInvestor::new(&cdd_claim);
+        assert_eq!(prepared.cdd_id().0, cdd_id.0);
+
+        for _ in 0..3 {
+            let scope_claim = ScopeClaimData {
+                scope_did: Scalar::random(&mut rng),
+                investor_unique_id: cdd_claim.investor_unique_id,
+            };
+
+            // With the same randomness, both paths create the same proof.
+            let mut prepared_rng = StdRng::from_seed(SEED);
+            let mut one_shot_rng = StdRng::from_seed(SEED);
+            let prepared_proof =
+                prepared.prove_for_scope(&scope_claim.scope_did, &mut prepared_rng);
+            let one_shot_proof =
+                Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut one_shot_rng);
+            assert_eq!(prepared_proof, one_shot_proof);
+
+            Verifier::verify_scope_claim_proof(
+                &prepared_proof,
+                &cdd_claim.investor_did,
+                &scope_claim.scope_did,
+                &cdd_id,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn verify_shared_unique_id_link() {
+        let mut rng = StdRng::from_seed(SEED);
+        let (cdd_claim, scope_claim) = crate::random_claim(&mut rng);
+        let cdd_id = Provider::create_cdd_id(&cdd_claim);
+        let verify = |proof: &ScopeClaimProof| {
+            Verifier::verify_shared_unique_id(
+                &cdd_id,
+                &cdd_claim.investor_did,
+                &scope_claim.scope_did,
+                proof,
+            )
+        };
+
+        let proof = Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng);
+        verify(&proof).unwrap();
+
+        // The link does not depend on the wellformedness signature.
+        let (other_cdd_claim, other_scope_claim) = crate::random_claim(&mut rng);
+        let other_proof =
+            Investor::create_scope_claim_proof(&other_cdd_claim, &other_scope_claim, &mut rng);
+        let mut unsigned_proof = proof;
+        unsigned_proof.proof_scope_id_wellformed = other_proof.proof_scope_id_wellformed;
+        verify(&unsigned_proof).unwrap();
+
+        // A SCOPE_ID of another INVESTOR_UNIQUE_ID is not linked to the CDD_ID.
+        let mismatched_scope_claim = ScopeClaimData {
+            scope_did: scope_claim.scope_did,
+            investor_unique_id: other_cdd_claim.investor_unique_id,
+        };
+        let mismatched_proof =
+            Investor::create_scope_claim_proof(&cdd_claim, &mismatched_scope_claim, &mut rng);
+        assert_err!(verify(&mismatched_proof), ErrorKind::ZkpError);
+    }
+
+    #[test]
+    fn verify_proofs_with_nonce() {
+        let mut rng = StdRng::from_seed(SEED);
+        let (cdd_claim, scope_claim) = crate::random_claim(&mut rng);
+        let cdd_id = Provider::create_cdd_id(&cdd_claim);
+        let verify = |proof: &ScopeClaimProof, nonce: &[u8]| {
+            Verifier::verify_scope_claim_proof_with_nonce(
+                proof,
+                &cdd_claim.investor_did,
+                &scope_claim.scope_did,
+                &cdd_id,
+                nonce,
+            )
+        };
+
+        let proof = Investor::create_scope_claim_proof_with_nonce(
+            &cdd_claim,
+            &scope_claim,
+            b"nonce A",
+            &mut rng,
+        );
+        verify(&proof, b"nonce A").unwrap();
+
+        // The proof cannot be replayed under another nonce, or without one.
+        assert_err!(verify(&proof, b"nonce B"), ErrorKind::SignatureError);
+        assert!(Verifier::verify_scope_claim_proof(
+            &proof,
+            &cdd_claim.investor_did,
+            &scope_claim.scope_did,
+            &cdd_id,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn verify_proofs_batch() {
+        let mut rng = StdRng::from_seed(SEED);
+
+        let claims = (0..4)
+            .map(|_| crate::random_claim(&mut rng))
+            .collect::<Vec<_>>();
+        let cdd_ids = claims
+            .iter()
+            .map(|(cdd_claim, _)| Provider::create_cdd_id(cdd_claim))
+            .collect::<Vec<_>>();
+        let mut proofs = claims
+            .iter()
+            .map(|(cdd_claim, scope_claim)| {
+                Investor::create_scope_claim_proof(cdd_claim, scope_claim, &mut rng)
+            })
+            .collect::<Vec<_>>();
+
+        let verify = |proofs: &[ScopeClaimProof], rng: &mut StdRng| {
+            let items = proofs
+                .iter()
+                .zip(&claims)
+                .zip(&cdd_ids)
+                .map(|((proof, (cdd_claim, scope_claim)), cdd_id)| {
+                    (
+                        proof,
+                        &cdd_claim.investor_did,
+                        &scope_claim.scope_did,
+                        cdd_id,
+                    )
+                })
+                .collect::<Vec<_>>();
+            Verifier::verify_scope_claim_proofs_batch(&items, rng)
+        };
+
+        verify(&proofs, &mut rng).unwrap();
+
+        // Swap in the signature of another proof at index 2.
+        proofs[2].proof_scope_id_wellformed = proofs[0].proof_scope_id_wellformed;
+        assert_err!(
+            verify(&proofs, &mut rng),
+            ErrorKind::BatchSignatureError { index: 2 }
+        );
+    }
+
     #[test]
     fn test_zkp_proof() {
         let mut rng = StdRng::from_seed(SEED);
@@ -406,4 +1326,245 @@ mod tests {
         let res = verify_zkp(&proof, &scope_id, &cdd_id, &investor_did, &base);
         assert!(res);
     }
+
+    #[test]
+    fn batches_exceeding_limits() {
+        let mut rng = StdRng::from_seed(SEED);
+        let limits = BatchLimits {
+            max_items: 2,
+            max_aggregation: 2,
+            ..BatchLimits::default()
+        };
+
+        // Invalid proofs, which fail if they are ever verified.
+        let (cdd_claim, scope_claim) = crate::random_claim(&mut rng);
+        let (other_cdd_claim, _) = crate::random_claim(&mut rng);
+        let wrong_cdd_id = Provider::create_cdd_id(&other_cdd_claim);
+        let proof = Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng);
+        let item = (
+            &proof,
+            &cdd_claim.investor_did,
+            &scope_claim.scope_did,
+            &wrong_cdd_id,
+        );
+
+        assert_err!(
+            Verifier::verify_scope_claim_proofs_batch_with_limits(&[item; 3], &limits, &mut rng),
+            ErrorKind::BatchTooLarge { size: 3, max: 2 }
+        );
+        assert_err!(
+            Verifier::verify_scope_claim_proofs_batch_with_limits(&[item; 2], &limits, &mut rng),
+            ErrorKind::ZkpError
+        );
+
+        // The number of scopes of a batched proof is bounded as well.
+        let scope_dids = [scope_claim.scope_did; 3];
+        assert_err!(
+            Investor::create_batched_scope_claim_proof_with_limits(
+                &cdd_claim,
+                &scope_dids,
+                &limits,
+                &mut rng
+            ),
+            ErrorKind::BatchTooLarge { size: 3, max: 2 }
+        );
+        let batched_proof =
+            Investor::create_batched_scope_claim_proof(&cdd_claim, &scope_dids, &mut rng).unwrap();
+        assert_err!(
+            Verifier::verify_batched_scope_claim_proof_with_limits(
+                &batched_proof,
+                &cdd_claim.investor_did,
+                &scope_dids,
+                &wrong_cdd_id,
+                &limits,
+            ),
+            ErrorKind::BatchTooLarge { size: 3, max: 2 }
+        );
+    }
+
+    #[test]
+    fn verify_batched_proofs() {
+        let mut rng = StdRng::from_seed(SEED);
+
+        let (cdd_claim, _) = crate::random_claim(&mut rng);
+        let scope_dids = [Scalar::random(&mut rng), Scalar::random(&mut rng)];
+        let cdd_id = Provider::create_cdd_id(&cdd_claim);
+
+        let proof =
+            Investor::create_batched_scope_claim_proof(&cdd_claim, &scope_dids, &mut rng).unwrap();
+
+        // The scope ids are the same as the ones of the single scope proofs.
+        for (scope_did, scope_id) in scope_dids.iter().zip(&proof.scope_ids) {
+            let scope_claim = ScopeClaimData {
+                scope_did: *scope_did,
+                investor_unique_id: cdd_claim.investor_unique_id,
+            };
+            let single_proof =
+                Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng);
+            assert_eq!(single_proof.scope_id, *scope_id);
+        }
+
+        // Both scopes verify together.
+        Verifier::verify_batched_scope_claim_proof(
+            &proof,
+            &cdd_claim.investor_did,
+            &scope_dids,
+            &cdd_id,
+        )
+        .unwrap();
+
+        // The proof survives an encoding round trip.
+        let decoded = BatchedScopeClaimProof::decode(&mut &proof.encode()[..]).unwrap();
+        assert_eq!(decoded, proof);
+
+        // A tampered scope fails the whole batch.
+        let tampered_scope_dids = [scope_dids[0], Scalar::random(&mut rng)];
+        assert_err!(
+            Verifier::verify_batched_scope_claim_proof(
+                &proof,
+                &cdd_claim.investor_did,
+                &tampered_scope_dids,
+                &cdd_id,
+            ),
+            ErrorKind::ZkpError
+        );
+
+        // So does a different number of scopes.
+        assert_err!(
+            Verifier::verify_batched_scope_claim_proof(
+                &proof,
+                &cdd_claim.investor_did,
+                &scope_dids[..1],
+                &cdd_id,
+            ),
+            ErrorKind::BatchSizeMismatch { proof: 2, given: 1 }
+        );
+
+        assert_err!(
+            Investor::create_batched_scope_claim_proof(&cdd_claim, &[], &mut rng),
+            ErrorKind::EmptyBatch
+        );
+    }
+
+    #[test]
+    fn verify_cdd_ids() {
+        let mut rng = StdRng::from_seed(SEED);
+
+        let (cdd_claim, _) = crate::random_claim(&mut rng);
+        let cdd_id = Provider::create_cdd_id(&cdd_claim);
+        assert!(verify_cdd_id(&cdd_claim, &cdd_id.0));
+
+        // A CDD Id of another claim does not match.
+        let (other_cdd_claim, _) = crate::random_claim(&mut rng);
+        let other_cdd_id = Provider::create_cdd_id(&other_cdd_claim);
+        assert!(!verify_cdd_id(&cdd_claim, &other_cdd_id.0));
+        assert!(!verify_cdd_id(&other_cdd_claim, &cdd_id.0));
+    }
+
+    #[test]
+    fn scope_id_from_parts() {
+        let scope_did = [4u8; 12];
+        let unique_id = [2u8; UNIQUE_ID_LEN];
+        let scope_claim = ScopeClaimData::new(&scope_did, &unique_id);
+
+        for method in &[HashToCurve::Blake2bElligator, HashToCurve::Sha512] {
+            assert_eq!(
+                compute_scope_id_from_parts(&scope_did, &unique_id, *method).unwrap(),
+                compute_scope_id(&scope_claim, *method)
+            );
+        }
+
+        assert_err!(
+            compute_scope_id_from_parts(&[], &unique_id, HashToCurve::default()),
+            ErrorKind::InvalidScopeDidLength { length: 0 }
+        );
+        assert_err!(
+            compute_scope_id_from_parts(&[4u8; 33], &unique_id, HashToCurve::default()),
+            ErrorKind::InvalidScopeDidLength { length: 33 }
+        );
+        assert_err!(
+            compute_scope_id_from_parts(&scope_did, &[2u8; 32], HashToCurve::default()),
+            ErrorKind::InvalidUniqueIdLength { length: 32 }
+        );
+    }
+
+    #[test]
+    fn hash_to_curve_methods() {
+        let mut rng = StdRng::from_seed(SEED);
+
+        let (cdd_claim, scope_claim) = crate::random_claim(&mut rng);
+        let cdd_id = Provider::create_cdd_id(&cdd_claim);
+        let methods = [HashToCurve::Blake2bElligator, HashToCurve::Sha512];
+
+        // The default is the original mapping.
+        assert_eq!(
+            HashToCurve::default().map(scope_claim.scope_did.as_bytes()),
+            slice_to_ristretto_point(scope_claim.scope_did.as_bytes())
+        );
+
+        // The two methods produce different points for the same input.
+        assert_ne!(
+            compute_scope_id(&scope_claim, methods[0]),
+            compute_scope_id(&scope_claim, methods[1])
+        );
+
+        for method in &methods {
+            let proof = Investor::create_scope_claim_proof_with_hash_to_curve(
+                &cdd_claim,
+                &scope_claim,
+                *method,
+                &mut rng,
+            );
+            assert_eq!(proof.scope_id, compute_scope_id(&scope_claim, *method));
+
+            // Each method is internally consistent.
+            for other in &methods {
+                let result = Verifier::verify_scope_claim_proof_with_hash_to_curve(
+                    &proof,
+                    &cdd_claim.investor_did,
+                    &scope_claim.scope_did,
+                    &cdd_id,
+                    *other,
+                );
+                assert_eq!(result.is_ok(), method == other);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_scope_claim_proof_fields() {
+        let mut rng = StdRng::from_seed(SEED);
+        let (cdd_claim, scope_claim) = crate::random_claim(&mut rng);
+        let proof = Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng);
+        let encoded = proof.encode();
+        assert_eq!(encoded.len(), SCOPE_CLAIM_PROOF_LEN);
+        assert_eq!(ScopeClaimProof::decode_fields(&encoded).unwrap(), proof);
+
+        // Each corrupt point is reported by its name.
+        for (field, offset) in SCOPE_CLAIM_PROOF_POINTS.iter() {
+            let mut corrupt = encoded.clone();
+            corrupt[*offset..*offset + 32].copy_from_slice(&[0xffu8; 32]);
+            assert_err!(
+                ScopeClaimProof::decode_fields(&corrupt),
+                ErrorKind::InvalidProofPoint { field: *field }
+            );
+        }
+
+        assert_err!(
+            ScopeClaimProof::decode_fields(&encoded[..SCOPE_CLAIM_PROOF_LEN - 1]),
+            ErrorKind::InvalidProofFieldLength {
+                field: "proof",
+                length: SCOPE_CLAIM_PROOF_LEN - 1,
+                expected: SCOPE_CLAIM_PROOF_LEN,
+            }
+        );
+        assert_err!(
+            decode_proof_point("scope_id", &encoded[193..]),
+            ErrorKind::InvalidProofFieldLength {
+                field: "scope_id",
+                length: 31,
+                expected: 32,
+            }
+        );
+    }
 }
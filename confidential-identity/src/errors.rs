@@ -56,6 +56,58 @@ pub enum ErrorKind {
     /// Scope id is not wellformed: signature verification failed.
     #[fail(display = "Scope id is not wellformed: signature verification failed.")]
     SignatureError,
+
+    /// The batched proof does not cover the same number of scopes as the ones being verified.
+    #[fail(
+        display = "The batched proof covers {} scopes, but {} were given.",
+        proof, given
+    )]
+    BatchSizeMismatch { proof: usize, given: usize },
+
+    /// A batched proof must cover at least one scope.
+    #[fail(display = "A batched proof must cover at least one scope.")]
+    EmptyBatch,
+
+    /// The batch exceeds the configured `BatchLimits`.
+    #[fail(
+        display = "The batch has {} entries, but at most {} are allowed.",
+        size, max
+    )]
+    BatchTooLarge { size: usize, max: usize },
+
+    /// The SCOPE_DID must be between 1 and `MAX_SCOPE_DID_LEN` bytes long.
+    #[fail(display = "Invalid SCOPE_DID length {}.", length)]
+    InvalidScopeDidLength { length: usize },
+
+    /// The INVESTOR_UNIQUE_ID must be a `UNIQUE_ID_LEN` bytes long UUID.
+    #[fail(display = "Invalid INVESTOR_UNIQUE_ID length {}.", length)]
+    InvalidUniqueIdLength { length: usize },
+
+    /// The signature at the given index of a batch failed the verification.
+    #[fail(display = "Signature {} of the batch failed the verification.", index)]
+    BatchSignatureError { index: usize },
+
+    /// The CDD_ID is not one of the registered CDD_IDs.
+    #[fail(display = "The CDD_ID is not registered.")]
+    UnregisteredCddId,
+
+    /// A field of an encoded proof does not have the expected length.
+    #[fail(
+        display = "The proof field {} has {} bytes instead of {}.",
+        field, length, expected
+    )]
+    InvalidProofFieldLength {
+        field: &'static str,
+        length: usize,
+        expected: usize,
+    },
+
+    /// A curve point of an encoded proof is not a canonical encoding of a Ristretto point.
+    #[fail(
+        display = "The proof field {} is not a canonical Ristretto point.",
+        field
+    )]
+    InvalidProofPoint { field: &'static str },
 }
 
 pub type Fallible<T, E = Error> = Result<T, E>;
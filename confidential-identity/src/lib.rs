@@ -8,8 +8,11 @@
 #[macro_use]
 extern crate alloc;
 
-pub use claim_proofs::{CddClaimData, CddId, ScopeClaimData, ScopeClaimProof, ScopeClaimProofData};
-pub use cryptography_core;
+pub use claim_proofs::{
+    compute_scope_id_from_parts, verify_cdd_id, BatchedScopeClaimProof, CddClaimData, CddId,
+    HashToCurve, ScopeClaimData, ScopeClaimProof, ScopeClaimProofData,
+};
+pub use cryptography_core::{self, asset_proofs::BatchLimits};
 pub use curve25519_dalek::{
     self,
     ristretto::{CompressedRistretto, RistrettoPoint},
@@ -11,10 +11,13 @@ use codec::{Decode, Encode, Error as CodecError, Input, Output};
 use curve25519_dalek::{
     ristretto::{CompressedRistretto, RistrettoPoint},
     scalar::Scalar,
+    traits::{IsIdentity, VartimeMultiscalarMul},
 };
+use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use sha3::{digest::FixedOutput, Digest, Sha3_512};
+use sp_std::prelude::*;
 use zeroize::Zeroize;
 
 /// A Schnorr secret key.
@@ -133,22 +136,80 @@ impl PublicKey {
         signature: &Signature,
         base_point: &RistrettoPoint,
     ) -> Fallible<()> {
-        let mut h = Sha3_512::new();
         let R: RistrettoPoint;
-        let k: Scalar;
+        let k = self.challenge(message, signature);
         let minus_A = -self.key;
 
+        R = k * minus_A + signature.s * base_point;
+
+        ensure!(R.compress() == signature.R, ErrorKind::SignatureError);
+
+        Ok(())
+    }
+
+    fn challenge(&self, message: &[u8], signature: &Signature) -> Scalar {
+        let mut h = Sha3_512::new();
         h.input(signature.R.as_bytes());
         h.input(self.key.compress().as_bytes());
         h.input(&message);
 
-        k = Scalar::from_hash(h);
-        R = k * minus_A + signature.s * base_point;
+        Scalar::from_hash(h)
+    }
+}
 
-        ensure!(R.compress() == signature.R, ErrorKind::SignatureError);
+/// Verifies several signatures at once. Each item holds the message, its signature, the
+/// public key, and the custom base point it was signed with.
+///
+/// The individual verification equations are combined with random weights and checked
+/// with a single multiscalar multiplication. If that check fails, the signatures are
+/// verified one by one to identify the first invalid one.
+///
+/// # Return
+///
+/// Returns `Ok(())` if all the signatures are valid, and a `BatchSignatureError` with the
+/// index of the first invalid signature otherwise.
+#[allow(non_snake_case)]
+pub fn verify_signatures_batch<R: RngCore + CryptoRng>(
+    items: &[(&[u8], Signature, PublicKey, RistrettoPoint)],
+    rng: &mut R,
+) -> Fallible<()> {
+    let mut scalars = Vec::with_capacity(3 * items.len());
+    let mut points = Vec::with_capacity(3 * items.len());
+    let mut all_decompressed = true;
 
-        Ok(())
+    for (message, signature, public_key, base_point) in items {
+        let R = match signature.R.decompress() {
+            Some(R) => R,
+            None => {
+                all_decompressed = false;
+                break;
+            }
+        };
+        let z = Scalar::random(rng);
+        let k = public_key.challenge(message, signature);
+
+        // z * (s * base_point - k * A - R) == 0
+        scalars.push(z * signature.s);
+        points.push(*base_point);
+        scalars.push(-(z * k));
+        points.push(public_key.key);
+        scalars.push(-z);
+        points.push(R);
     }
+
+    if all_decompressed && RistrettoPoint::vartime_multiscalar_mul(scalars, points).is_identity() {
+        return Ok(());
+    }
+
+    for (index, (message, signature, public_key, base_point)) in items.iter().enumerate() {
+        ensure!(
+            public_key.verify(message, signature, base_point).is_ok(),
+            ErrorKind::BatchSignatureError { index }
+        );
+    }
+
+    // Unreachable unless the random weights cancelled out the invalid equations.
+    Err(ErrorKind::SignatureError.into())
 }
 
 #[cfg(test)]
@@ -179,4 +240,45 @@ mod tests {
             .verify("invalid message".as_bytes(), &sig, &base_point)
             .is_ok());
     }
+
+    #[test]
+    fn test_batch_signature_verification() {
+        let mut rng = StdRng::from_seed(SEED);
+
+        let keys = (0..5)
+            .map(|_| {
+                let secret = Scalar::random(&mut rng);
+                let base_point = RistrettoPoint::random(&mut rng);
+                let public_key = PublicKey {
+                    key: secret * base_point,
+                };
+                (SecretKey::new(secret), public_key, base_point)
+            })
+            .collect::<Vec<_>>();
+        let mut items = keys
+            .iter()
+            .map(|(secret_key, public_key, base_point)| {
+                let sig = secret_key.sign("message".as_bytes(), public_key, base_point);
+                ("message".as_bytes(), sig, *public_key, *base_point)
+            })
+            .collect::<Vec<_>>();
+
+        assert!(verify_signatures_batch(&items, &mut rng).is_ok());
+        assert!(verify_signatures_batch(&[], &mut rng).is_ok());
+
+        // Break the signature at index 3.
+        items[3].0 = "invalid message".as_bytes();
+        assert_err!(
+            verify_signatures_batch(&items, &mut rng),
+            ErrorKind::BatchSignatureError { index: 3 }
+        );
+
+        // A signature verified against the wrong base point is also caught.
+        items[3].0 = "message".as_bytes();
+        items[1].3 = keys[2].2;
+        assert_err!(
+            verify_signatures_batch(&items, &mut rng),
+            ErrorKind::BatchSignatureError { index: 1 }
+        );
+    }
 }
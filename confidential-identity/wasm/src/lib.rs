@@ -1,6 +1,6 @@
 use blake2::{Blake2s, Digest};
 use confidential_identity::{
-    claim_proofs::{Investor, Provider},
+    claim_proofs::{self, Investor, Provider},
     mocked, CddClaimData, InvestorTrait, ProviderTrait, ScopeClaimData,
 };
 use rand::{rngs::StdRng, SeedableRng};
@@ -37,6 +37,20 @@ pub fn make_message(investor_did: &InvestorDID, scope_did: &ScopeDID) -> [u8; 32
         .into()
 }
 
+/// Returns the message that a scope claim proof signs when it is bound to the verifier-supplied
+/// `nonce`. See `confidential_identity::claim_proofs::make_message_with_nonce`.
+pub fn make_message_with_nonce(
+    investor_did: &InvestorDID,
+    scope_did: &ScopeDID,
+    nonce: &[u8],
+) -> [u8; 32] {
+    claim_proofs::make_message_with_nonce(
+        &claim_proofs::slice_to_scalar(investor_did),
+        &claim_proofs::slice_to_scalar(scope_did),
+        nonce,
+    )
+}
+
 /// Creates a CDD_ID from investor did and investor uid
 ///
 /// # Arguments
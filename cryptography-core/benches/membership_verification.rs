@@ -2,8 +2,8 @@ use criterion::{criterion_group, criterion_main, Criterion};
 use cryptography_core::asset_proofs::{
     encryption_proofs::{single_property_prover, single_property_verifier},
     membership_proof::{
-        MembershipProofFinalResponse, MembershipProofInitialMessage, MembershipProofVerifier,
-        MembershipProverAwaitingChallenge,
+        MembershipGenerators, MembershipProofFinalResponse, MembershipProofInitialMessage,
+        MembershipProofVerifier, MembershipProverAwaitingChallenge,
     },
     one_out_of_many_proof::OooNProofGenerators,
 };
@@ -75,13 +75,56 @@ fn bench_membership_proof(c: &mut Criterion) {
     bench_membership_verify(c, commited_member, vec![proof])
 }
 
+fn bench_membership_generators(c: &mut Criterion) {
+    let mut rng = StdRng::from_seed(SEED_1);
+
+    let elements: Vec<Scalar> = (0..SET_SIZE as u32).map(Scalar::from).collect();
+    let secret_member = Scalar::from(28345u32);
+    let blinding = Scalar::random(&mut rng);
+
+    let generators = MembershipGenerators::new(elements.len()).unwrap();
+    let prover = generators
+        .prover(secret_member, blinding, elements.as_slice())
+        .unwrap();
+    let proof = single_property_prover(prover, &mut rng).unwrap();
+    let commited_member = OooNProofGenerators::default()
+        .com_gens
+        .commit(secret_member, blinding);
+
+    let per_call_elements = elements.clone();
+    let per_call_proof = proof.clone();
+    c.bench_function(
+        "membership verification with per-call generators",
+        move |b| {
+            b.iter(|| {
+                let generators = MembershipGenerators::new(per_call_elements.len()).unwrap();
+                single_property_verifier(
+                    &generators.verifier(commited_member, per_call_elements.as_slice()),
+                    per_call_proof.clone(),
+                )
+                .unwrap()
+            })
+        },
+    );
+
+    c.bench_function("membership verification with cached generators", move |b| {
+        b.iter(|| {
+            single_property_verifier(
+                &generators.verifier(commited_member, elements.as_slice()),
+                proof.clone(),
+            )
+            .unwrap()
+        })
+    });
+}
+
 criterion_group! {
     name = bench_membership_verification;
     // Lower the sample size to run faster; larger shuffle sizes are
     // long so we're not microbenchmarking anyways.
     // 10 is the minimum allowed sample size in Criterion.
     config = Criterion::default().sample_size(10).measurement_time(Duration::new(600, 0));
-    targets = bench_membership_proof,
+    targets = bench_membership_proof, bench_membership_generators,
 }
 
 criterion_main!(bench_membership_verification);
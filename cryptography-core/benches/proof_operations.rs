@@ -0,0 +1,64 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use cryptography_core::asset_proofs::{
+    bench::{
+        bench_prove_correctness, bench_prove_range, bench_verify_correctness, bench_verify_range,
+        RangeProofGens,
+    },
+    bulletproofs::PedersenGens,
+    correctness_proof::{CorrectnessProverAwaitingChallenge, CorrectnessVerifier},
+    CommitmentWitness, ElgamalSecretKey,
+};
+use curve25519_dalek::scalar::Scalar;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+const SEED_1: [u8; 32] = [42u8; 32];
+const SECRET_VALUE: u32 = 42;
+
+fn bench_correctness(c: &mut Criterion) {
+    let mut rng = StdRng::from_seed(SEED_1);
+    let pc_gens = PedersenGens::default();
+    let pub_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+    let w = CommitmentWitness::new(SECRET_VALUE.into(), Scalar::random(&mut rng));
+    let verifier = CorrectnessVerifier {
+        value: SECRET_VALUE.into(),
+        pub_key,
+        cipher: pub_key.encrypt(&w),
+        pc_gens: &pc_gens,
+    };
+    let prover = || CorrectnessProverAwaitingChallenge {
+        pub_key,
+        w: w.clone(),
+        pc_gens: &pc_gens,
+    };
+    let proof = bench_prove_correctness(prover(), &mut rng).unwrap();
+
+    c.bench_function("correctness proof", |b| {
+        b.iter(|| bench_prove_correctness(prover(), &mut rng).unwrap())
+    });
+    c.bench_function("correctness verification", |b| {
+        b.iter(|| bench_verify_correctness(&verifier, proof).unwrap())
+    });
+}
+
+fn bench_range(c: &mut Criterion) {
+    let mut rng = StdRng::from_seed(SEED_1);
+    let gens = RangeProofGens::default();
+    let blinding = Scalar::random(&mut rng);
+    let proof = bench_prove_range(&gens, SECRET_VALUE.into(), blinding, 32, &mut rng).unwrap();
+
+    c.bench_function("range proof", |b| {
+        b.iter(|| bench_prove_range(&gens, SECRET_VALUE.into(), blinding, 32, &mut rng).unwrap())
+    });
+    c.bench_function("range proof verification", |b| {
+        b.iter(|| bench_verify_range(&gens, &proof, &mut rng).unwrap())
+    });
+}
+
+criterion_group! {
+    name = proof_operations;
+    config = Criterion::default().sample_size(10);
+    targets = bench_correctness, bench_range,
+}
+
+criterion_main!(proof_operations);
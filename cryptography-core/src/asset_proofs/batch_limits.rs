@@ -0,0 +1,54 @@
+//! The bounds on the batches that the batch verification APIs accept. They are checked
+//! before any work is done, so that an oversized batch cannot exhaust the resources of a
+//! verifier.
+
+use crate::asset_proofs::errors::{ErrorKind, Fallible};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The bounds on the batches that the batch verification APIs accept.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BatchLimits {
+    /// The maximum number of transactions that are verified together.
+    pub max_items: usize,
+    /// The maximum number of proofs that are aggregated into a single proof, e.g., the
+    /// scopes of a batched scope claim proof.
+    pub max_aggregation: usize,
+}
+
+impl Default for BatchLimits {
+    fn default() -> Self {
+        BatchLimits {
+            max_items: 1024,
+            max_aggregation: 64,
+        }
+    }
+}
+
+impl BatchLimits {
+    /// Returns `ErrorKind::BatchTooLarge` if a batch of `size` items exceeds `max_items`.
+    pub fn ensure_items(&self, size: usize) -> Fallible<()> {
+        ensure!(
+            size <= self.max_items,
+            ErrorKind::BatchTooLarge {
+                size,
+                max: self.max_items
+            }
+        );
+        Ok(())
+    }
+
+    /// Returns `ErrorKind::BatchTooLarge` if `size` proofs exceed `max_aggregation`.
+    pub fn ensure_aggregation(&self, size: usize) -> Fallible<()> {
+        ensure!(
+            size <= self.max_aggregation,
+            ErrorKind::BatchTooLarge {
+                size,
+                max: self.max_aggregation
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+//! Entry points for benchmarking the individual proof operations in isolation, e.g., from a
+//! `criterion` harness. Each entry point takes pre-built inputs, including the generators, so
+//! that building them is not part of the measurement. Enabled with the `bench` feature.
+
+pub use crate::asset_proofs::range_proof::RangeProofGens;
+use crate::asset_proofs::{
+    correctness_proof::{
+        CorrectnessProof, CorrectnessProverAwaitingChallenge, CorrectnessVerifier,
+    },
+    encryption_proofs::{single_property_prover, single_property_verifier},
+    errors::Fallible,
+    range_proof::{
+        prove_within_range_with_gens, range_proof_transcript, verify_range_proof_with_gens,
+        InRangeProof,
+    },
+};
+use bulletproofs::PedersenGens;
+use curve25519_dalek::scalar::Scalar;
+use rand_core::{CryptoRng, RngCore};
+
+/// Creates a correctness proof, as `single_property_prover` does.
+pub fn bench_prove_correctness<R: RngCore + CryptoRng>(
+    prover: CorrectnessProverAwaitingChallenge,
+    rng: &mut R,
+) -> Fallible<CorrectnessProof> {
+    single_property_prover(prover, rng)
+}
+
+/// Verifies a correctness proof, as `single_property_verifier` does.
+pub fn bench_verify_correctness(
+    verifier: &CorrectnessVerifier,
+    proof: CorrectnessProof,
+) -> Fallible<()> {
+    single_property_verifier(verifier, proof)
+}
+
+/// Creates a range proof, as `prove_within_range` does, with the pre-built `gens`.
+pub fn bench_prove_range<R: RngCore + CryptoRng>(
+    gens: &RangeProofGens,
+    secret_value: u64,
+    rand_blind: Scalar,
+    range: u32,
+    rng: &mut R,
+) -> Fallible<InRangeProof> {
+    prove_within_range_with_gens(
+        &gens.bp_gens,
+        &gens.pc_gens,
+        secret_value,
+        rand_blind,
+        range,
+        rng,
+    )
+}
+
+/// Verifies a range proof, as `verify_within_range` does, with the pre-built `gens`.
+pub fn bench_verify_range<R: RngCore + CryptoRng>(
+    gens: &RangeProofGens,
+    proof: &InRangeProof,
+    rng: &mut R,
+) -> Fallible<()> {
+    verify_range_proof_with_gens(
+        &gens.bp_gens,
+        &gens.pc_gens,
+        &proof.response,
+        &proof.init,
+        proof.range,
+        &mut range_proof_transcript(),
+        rng,
+    )
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::{
+        range_proof::verify_within_range, CommitmentWitness, ElgamalSecretKey,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn bench_entry_points() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let pub_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let w = CommitmentWitness::new(42u32.into(), Scalar::random(&mut rng));
+        let cipher = pub_key.encrypt(&w);
+
+        let pc_gens = PedersenGens::default();
+        let prover = CorrectnessProverAwaitingChallenge {
+            pub_key,
+            w: w.clone(),
+            pc_gens: &pc_gens,
+        };
+        let verifier = CorrectnessVerifier {
+            value: 42u32.into(),
+            pub_key,
+            cipher,
+            pc_gens: &pc_gens,
+        };
+        let proof = bench_prove_correctness(prover, &mut rng).unwrap();
+        assert!(bench_verify_correctness(&verifier, proof).is_ok());
+        assert!(single_property_verifier(&verifier, proof).is_ok());
+
+        let gens = RangeProofGens::default();
+        let range_proof = bench_prove_range(&gens, 42, w.blinding(), 32, &mut rng).unwrap();
+        assert!(bench_verify_range(&gens, &range_proof, &mut rng).is_ok());
+        assert!(verify_within_range(&range_proof, &mut rng).is_ok());
+        assert_eq!(range_proof.init, cipher.y.compress());
+    }
+}
@@ -0,0 +1,150 @@
+//! A debug-only defence against reusing the blinding factors of the encryptions.
+//!
+//! Encrypting two values with the same blinding factor leaks the relationship between them,
+//! since `y1 - y2 = (v1 - v2) * B`. A misconfigured RNG, e.g., one that is reseeded with the
+//! same seed, silently produces such encryptions. The `BlindTracker` records the blinding
+//! factors that a session uses and reports the first one that repeats.
+//!
+//! The provers feed their blinding factors into a tracker through
+//! `single_property_prover_with_tracker`. The tracker is only compiled with the
+//! `blind-tracker` feature, and is meant to be used in tests.
+
+use crate::asset_proofs::{
+    errors::{ErrorKind, Fallible},
+    CipherText, CommitmentWitness, ElgamalPublicKey,
+};
+use curve25519_dalek::scalar::Scalar;
+use sha3::{digest::FixedOutput, Digest, Sha3_256};
+use sp_std::collections::btree_set::BTreeSet;
+
+/// Records the blinding factors of a session. Only the hashes of the blinding factors
+/// are kept, so that the tracker does not hold on to secret material.
+#[derive(Default)]
+pub struct BlindTracker {
+    seen: BTreeSet<[u8; 32]>,
+}
+
+impl BlindTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `blinding`, and returns `ErrorKind::ReusedBlinding` if it was already used
+    /// in this session.
+    pub fn track(&mut self, blinding: &Scalar) -> Fallible<()> {
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(
+            &Sha3_256::default()
+                .chain(blinding.as_bytes())
+                .fixed_result(),
+        );
+        ensure!(self.seen.insert(hash), ErrorKind::ReusedBlinding);
+        Ok(())
+    }
+
+    /// Records the blinding factor of `witness`. See `track`.
+    pub fn track_witness(&mut self, witness: &CommitmentWitness) -> Fallible<()> {
+        self.track(&witness.blinding())
+    }
+
+    /// Encrypts `witness` with `pub_key`, after recording its blinding factor.
+    pub fn encrypt(
+        &mut self,
+        pub_key: &ElgamalPublicKey,
+        witness: &CommitmentWitness,
+    ) -> Fallible<CipherText> {
+        self.track_witness(witness)?;
+        Ok(pub_key.encrypt(witness))
+    }
+
+    /// The number of distinct blinding factors recorded so far.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::{
+        encryption_proofs::single_property_prover_with_tracker,
+        wellformedness_proof::WellformednessProverAwaitingChallenge, ElgamalSecretKey,
+    };
+    use bulletproofs::PedersenGens;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+    use zeroize::Zeroizing;
+
+    const SEED: [u8; 32] = [17u8; 32];
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn detect_reused_blinds() {
+        let mut rng = StdRng::from_seed(SEED);
+        let pub_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let mut tracker = BlindTracker::new();
+
+        // Distinct blinds pass.
+        for value in 0u32..4 {
+            let witness = CommitmentWitness::new(value.into(), Scalar::random(&mut rng));
+            tracker.encrypt(&pub_key, &witness).unwrap();
+        }
+        assert_eq!(tracker.len(), 4);
+
+        // A misconfigured RNG that is reseeded with the same seed reuses the blinds.
+        let blind = Scalar::random(&mut StdRng::from_seed(SEED));
+        let reused_blind = Scalar::random(&mut StdRng::from_seed(SEED));
+        tracker
+            .track_witness(&CommitmentWitness::new(7u32.into(), blind))
+            .unwrap();
+        assert_err!(
+            tracker.encrypt(&pub_key, &CommitmentWitness::new(8u32.into(), reused_blind)),
+            ErrorKind::ReusedBlinding
+        );
+        assert_eq!(tracker.len(), 5);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn provers_feed_the_tracker() {
+        let mut rng = StdRng::from_seed(SEED);
+        let pub_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let gens = PedersenGens::default();
+        let mut tracker = BlindTracker::new();
+
+        let mut prove = |witness: CommitmentWitness, tracker: &mut BlindTracker| {
+            let prover = WellformednessProverAwaitingChallenge {
+                pub_key,
+                w: Zeroizing::new(witness),
+                pc_gens: &gens,
+            };
+            single_property_prover_with_tracker(prover, tracker, &mut rng)
+        };
+
+        let blind = Scalar::random(&mut StdRng::from_seed(SEED));
+        let other_blind = Scalar::random(&mut StdRng::from_seed([18u8; 32]));
+        prove(CommitmentWitness::new(1u32.into(), blind), &mut tracker).unwrap();
+        prove(
+            CommitmentWitness::new(2u32.into(), other_blind),
+            &mut tracker,
+        )
+        .unwrap();
+        assert_eq!(tracker.len(), 2);
+
+        // A second proof over an encryption with a reused blind is rejected.
+        assert_err!(
+            prove(CommitmentWitness::new(3u32.into(), blind), &mut tracker),
+            ErrorKind::ReusedBlinding
+        );
+        assert_eq!(tracker.len(), 2);
+    }
+}
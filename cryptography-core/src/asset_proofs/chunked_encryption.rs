@@ -0,0 +1,197 @@
+//! The encryption of amounts that are wider than 64 bits.
+//!
+//! The range proofs, and the decryption, only support values of up to 64 bits. A `u128`
+//! value is therefore split into two 64-bit limbs, such that
+//! ```text
+//! value = lo + (hi << 64)
+//! ```
+//! Each limb is encrypted and range-proved separately. The value is also encrypted as a
+//! whole, and a linear-relation proof shows that the limbs reconstruct the whole, i.e.,
+//! that `whole - (lo + 2^64 * hi)` is an encryption of zero. Since Elgamal encryption is
+//! additively homomorphic, this is a correctness proof of the value zero for the
+//! difference of the ciphertexts, whose blinding factor is `r - (r_lo + 2^64 * r_hi)`.
+
+use crate::asset_proofs::{
+    correctness_proof::{
+        CorrectnessProof, CorrectnessProverAwaitingChallenge, CorrectnessVerifier,
+    },
+    encryption_proofs::{single_property_prover, single_property_verifier},
+    errors::Fallible,
+    range_proof::{
+        prove_within_range, verify_within_range_for_commitment, RangeProofFinalResponse,
+    },
+    CipherText, CipherTextWithHint, CommitmentWitness, ElgamalPublicKey, ElgamalSecretKey,
+};
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek::scalar::Scalar;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The number of bits of each limb.
+pub const LIMB_BITS: u32 = 64;
+
+/// A `u128` value encrypted as two 64-bit limbs, see the module documentation.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChunkedCiphertext {
+    /// The encryption of the whole value.
+    pub whole: CipherText,
+    /// The encryption of the low 64 bits of the value.
+    pub lo: CipherTextWithHint,
+    /// The encryption of the high 64 bits of the value.
+    pub hi: CipherTextWithHint,
+    /// The proof that the low limb is in `[0, 2^64)`.
+    pub lo_range_proof: RangeProofFinalResponse,
+    /// The proof that the high limb is in `[0, 2^64)`.
+    pub hi_range_proof: RangeProofFinalResponse,
+    /// The proof that `whole - (lo + 2^64 * hi)` encrypts zero.
+    pub relation_proof: CorrectnessProof,
+}
+
+fn u128_to_scalar(value: u128) -> Scalar {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&value.to_le_bytes());
+    Scalar::from_bytes_mod_order(bytes)
+}
+
+/// `2^64` as a scalar.
+fn limb_shift() -> Scalar {
+    u128_to_scalar(1u128 << LIMB_BITS)
+}
+
+/// Computes the encryption of `lo + 2^64 * hi` from the encryptions of the limbs.
+fn reconstruct(lo: &CipherText, hi: &CipherText) -> CipherText {
+    let shift = limb_shift();
+    CipherText {
+        x: lo.x + shift * hi.x,
+        y: lo.y + shift * hi.y,
+    }
+}
+
+/// Encrypts `value` as two 64-bit limbs, and proves that the limbs are in range and that
+/// they reconstruct the whole value.
+pub fn encrypt_chunked<R: RngCore + CryptoRng>(
+    value: u128,
+    pub_key: &ElgamalPublicKey,
+    rng: &mut R,
+) -> Fallible<ChunkedCiphertext> {
+    let gens = PedersenGens::default();
+    let lo_value = value as u64;
+    let hi_value = (value >> LIMB_BITS) as u64;
+
+    let (whole_witness, whole) = pub_key.encrypt_value(u128_to_scalar(value), rng);
+    let (lo_witness, lo) = pub_key.const_time_encrypt_value(lo_value.into(), rng);
+    let (hi_witness, hi) = pub_key.const_time_encrypt_value(hi_value.into(), rng);
+
+    let lo_range_proof = prove_within_range(lo_value, lo_witness.blinding(), LIMB_BITS, rng)?;
+    let hi_range_proof = prove_within_range(hi_value, hi_witness.blinding(), LIMB_BITS, rng)?;
+
+    let blinding =
+        whole_witness.blinding() - (lo_witness.blinding() + limb_shift() * hi_witness.blinding());
+    let relation_proof = single_property_prover(
+        CorrectnessProverAwaitingChallenge {
+            pub_key: *pub_key,
+            w: CommitmentWitness::new(Scalar::zero(), blinding),
+            pc_gens: &gens,
+        },
+        rng,
+    )?;
+
+    Ok(ChunkedCiphertext {
+        whole,
+        lo,
+        hi,
+        lo_range_proof: lo_range_proof.response,
+        hi_range_proof: hi_range_proof.response,
+        relation_proof,
+    })
+}
+
+/// Verifies the range proofs of the limbs and that the limbs reconstruct the whole value.
+pub fn verify_chunked<R: RngCore + CryptoRng>(
+    pub_key: &ElgamalPublicKey,
+    chunked: &ChunkedCiphertext,
+    rng: &mut R,
+) -> Fallible<()> {
+    let gens = PedersenGens::default();
+    let lo = &chunked.lo.elgamal_cipher;
+    let hi = &chunked.hi.elgamal_cipher;
+
+    verify_within_range_for_commitment(&chunked.lo_range_proof, &lo.y, LIMB_BITS, rng)?;
+    verify_within_range_for_commitment(&chunked.hi_range_proof, &hi.y, LIMB_BITS, rng)?;
+
+    single_property_verifier(
+        &CorrectnessVerifier {
+            value: Scalar::zero(),
+            pub_key: *pub_key,
+            cipher: &chunked.whole - &reconstruct(lo, hi),
+            pc_gens: &gens,
+        },
+        chunked.relation_proof,
+    )
+}
+
+/// Decrypts the limbs and returns `lo + (hi << 64)`, after checking that it is also the
+/// decryption of the whole value.
+pub fn decrypt_chunked(
+    secret_key: &ElgamalSecretKey,
+    chunked: &ChunkedCiphertext,
+) -> Fallible<u128> {
+    let lo = secret_key.const_time_decrypt_u64(&chunked.lo)?;
+    let hi = secret_key.const_time_decrypt_u64(&chunked.hi)?;
+    let value = u128::from(lo) + (u128::from(hi) << LIMB_BITS);
+
+    secret_key.verify(&chunked.whole, &u128_to_scalar(value))?;
+    Ok(value)
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::errors::ErrorKind;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    const SEED_1: [u8; 32] = [29u8; 32];
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_chunked_encryption() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+
+        for value in &[0u128, 42, (1u128 << 64) + 7, u128::max_value()] {
+            let chunked = encrypt_chunked(*value, &elg_pub, &mut rng).unwrap();
+            assert!(verify_chunked(&elg_pub, &chunked, &mut rng).is_ok());
+            assert_eq!(decrypt_chunked(&elg_secret, &chunked).unwrap(), *value);
+        }
+
+        // Negative test: the limbs of another value do not reconstruct the whole value.
+        let chunked = encrypt_chunked((1u128 << 64) + 7, &elg_pub, &mut rng).unwrap();
+        let other = encrypt_chunked((2u128 << 64) + 7, &elg_pub, &mut rng).unwrap();
+        let mut bad_chunked = chunked.clone();
+        bad_chunked.hi = other.hi;
+        bad_chunked.hi_range_proof = other.hi_range_proof;
+        assert_err!(
+            verify_chunked(&elg_pub, &bad_chunked, &mut rng),
+            ErrorKind::CorrectnessFinalResponseVerificationError { check: 1 }
+        );
+        assert_err!(
+            decrypt_chunked(&elg_secret, &bad_chunked),
+            ErrorKind::CipherTextDecryptionError
+        );
+
+        // Negative test: a range proof of another limb does not verify.
+        let mut bad_chunked = chunked;
+        bad_chunked.lo_range_proof = other.hi_range_proof;
+        assert!(verify_chunked(&elg_pub, &bad_chunked, &mut rng).is_err());
+    }
+}
@@ -161,6 +161,22 @@ impl ElgamalSecretKey {
         self.verify(&cipher_text.elgamal_cipher, &decrypted_u32.into())?;
         Ok(decrypted_u32)
     }
+
+    /// Decrypt a cipher text that is known to encrypt a u64. Unlike `decrypt`, this
+    /// does not brute force the discrete log, and therefore works for the whole range.
+    pub fn const_time_decrypt_u64(&self, cipher_text: &CipherTextWithHint) -> Fallible<u64> {
+        // random_2 * h = Y - X / secret_key
+        let random_2_h = cipher_text.y - self.secret.invert() * cipher_text.elgamal_cipher.x;
+
+        use byteorder::{ByteOrder, LittleEndian};
+
+        let decrypted_msg = xor_with_one_time_pad(random_2_h, &cipher_text.z);
+        let decrypted_u64 = LittleEndian::read_u64(&decrypted_msg);
+
+        // Verify that the same value was encrypted using twisted Elgamal encryption.
+        self.verify(&cipher_text.elgamal_cipher, &decrypted_u64.into())?;
+        Ok(decrypted_u64)
+    }
 }
 
 // ------------------------------------------------------------------------
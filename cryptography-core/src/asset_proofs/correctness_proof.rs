@@ -4,8 +4,8 @@
 use crate::{
     asset_proofs::{
         encryption_proofs::{
-            AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofVerifier, ZKPChallenge,
-            ZKProofResponse,
+            AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofVerifier, ProofExt,
+            ZKPChallenge, ZKProofResponse,
         },
         errors::{ErrorKind, Fallible},
         transcript::{TranscriptProtocol, UpdateTranscript},
@@ -45,6 +45,13 @@ impl From<Scalar> for CorrectnessFinalResponse {
     }
 }
 
+impl ProofExt for CorrectnessFinalResponse {
+    /// Compares the scalars after reducing them modulo the group order.
+    fn crypto_eq(&self, other: &Self) -> bool {
+        self.0.reduce() == other.0.reduce()
+    }
+}
+
 impl Encode for CorrectnessFinalResponse {
     fn size_hint(&self) -> usize {
         ScalarEncoder(&self.0).size_hint()
@@ -162,6 +169,11 @@ impl<'a> AssetProofProverAwaitingChallenge for CorrectnessProverAwaitingChalleng
             },
         )
     }
+
+    #[cfg(feature = "blind-tracker")]
+    fn blinding(&self) -> Option<Scalar> {
+        Some(self.w.blinding())
+    }
 }
 
 impl AssetProofProver<CorrectnessFinalResponse> for CorrectnessProver {
@@ -306,4 +318,30 @@ mod tests {
         let recovered_final_response = <CorrectnessFinalResponse>::decode(&mut input).unwrap();
         assert_eq!(recovered_final_response, final_response);
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn crypto_eq_of_proofs() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let w = CommitmentWitness::new(42u32.into(), Scalar::random(&mut rng));
+        let gens = PedersenGens::default();
+        let prover = CorrectnessProverAwaitingChallenge {
+            pub_key: secret_key.get_public_key(),
+            w,
+            pc_gens: &gens,
+        };
+        let (_, final_response) = encryption_proofs::single_property_prover::<
+            StdRng,
+            CorrectnessProverAwaitingChallenge,
+        >(prover, &mut rng)
+        .unwrap();
+
+        // An unreduced scalar and its reduction differ as bytes, but not as scalars.
+        let unreduced = CorrectnessFinalResponse(Scalar::from_bits([0xffu8; 32]));
+        let reduced = CorrectnessFinalResponse(unreduced.0.reduce());
+        assert_ne!(unreduced, reduced);
+        assert!(unreduced.crypto_eq(&reduced));
+        assert!(!final_response.crypto_eq(&reduced));
+    }
 }
@@ -0,0 +1,113 @@
+//! The proof that a ciphertext decrypts to a claimed value. This lets the owner of a
+//! secret key, e.g., a mediator, publicly attest the value that they have decrypted.
+//!
+//! Since `X = r * pub_key = secret_key * r * h` and `Y - value * g = r * h`, the proof is a
+//! Chaum-Pedersen proof that the discrete log of `pub_key` to the base `h` equals the
+//! discrete log of `X` to the base `Y - value * g`. This is the same statement as the
+//! ciphertext refreshment proof between the ciphertext and the trivial encryption of
+//! `value` with a zero blinding factor, which is why that proof is reused here.
+
+use crate::asset_proofs::{
+    ciphertext_refreshment_proof::{
+        CipherEqualSamePubKeyProof, CipherTextRefreshmentProverAwaitingChallenge,
+        CipherTextRefreshmentVerifier,
+    },
+    encryption_proofs::{single_property_prover, single_property_verifier},
+    errors::Fallible,
+    Balance, CipherText, ElgamalPublicKey, ElgamalSecretKey,
+};
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar, traits::Identity};
+use rand_core::{CryptoRng, RngCore};
+
+/// Holds the non-interactive proof that a ciphertext decrypts to a claimed value.
+pub type DecryptionProof = CipherEqualSamePubKeyProof;
+
+/// The encryption of `value` with a zero blinding factor.
+fn trivial_cipher(value: Balance, gens: &PedersenGens) -> CipherText {
+    CipherText {
+        x: RistrettoPoint::identity(),
+        y: Scalar::from(value) * gens.B,
+    }
+}
+
+/// Generates a proof that `cipher` decrypts to `claimed_value` under `secret_key`.
+/// The proof does not check the claim, it just fails to verify if the claim is wrong.
+pub fn prove_correct_decryption<R: RngCore + CryptoRng>(
+    secret_key: &ElgamalSecretKey,
+    cipher: &CipherText,
+    claimed_value: Balance,
+    rng: &mut R,
+) -> Fallible<DecryptionProof> {
+    let gens = PedersenGens::default();
+    let prover = CipherTextRefreshmentProverAwaitingChallenge::new(
+        secret_key.clone(),
+        *cipher,
+        trivial_cipher(claimed_value, &gens),
+        &gens,
+    );
+    single_property_prover(prover, rng)
+}
+
+/// Verifies that `cipher` decrypts to `claimed_value` under the secret key of `pub_key`.
+pub fn verify_correct_decryption(
+    pub_key: &ElgamalPublicKey,
+    cipher: &CipherText,
+    claimed_value: Balance,
+    proof: &DecryptionProof,
+) -> Fallible<()> {
+    let gens = PedersenGens::default();
+    let verifier = CipherTextRefreshmentVerifier::new(
+        *pub_key,
+        *cipher,
+        trivial_cipher(claimed_value, &gens),
+        &gens,
+    );
+    single_property_verifier(&verifier, *proof)
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::errors::ErrorKind;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    const SEED_1: [u8; 32] = [23u8; 32];
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_decryption_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let (_, cipher) = elg_pub.encrypt_value(42u32.into(), &mut rng);
+
+        // Positive test: the claimed value is the decrypted value.
+        let proof = prove_correct_decryption(&elg_secret, &cipher, 42, &mut rng).unwrap();
+        assert!(verify_correct_decryption(&elg_pub, &cipher, 42, &proof).is_ok());
+
+        // Negative test: the proof does not verify for another value.
+        assert_err!(
+            verify_correct_decryption(&elg_pub, &cipher, 43, &proof),
+            ErrorKind::CiphertextRefreshmentFinalResponseVerificationError { check: 1 }
+        );
+
+        // Negative test: a proof of an incorrect claimed value does not verify.
+        let bad_proof = prove_correct_decryption(&elg_secret, &cipher, 43, &mut rng).unwrap();
+        assert_err!(
+            verify_correct_decryption(&elg_pub, &cipher, 43, &bad_proof),
+            ErrorKind::CiphertextRefreshmentFinalResponseVerificationError { check: 1 }
+        );
+
+        // Negative test: the proof is bound to the public key.
+        let other_pub = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        assert!(verify_correct_decryption(&other_pub, &cipher, 42, &proof).is_err());
+    }
+}
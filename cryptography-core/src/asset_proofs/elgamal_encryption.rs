@@ -4,22 +4,34 @@
 //! addition and subtraction API over the cipher texts.
 
 use crate::{
-    asset_proofs::errors::{ErrorKind, Fallible},
-    codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
+    asset_proofs::{
+        errors::{ErrorKind, Fallible},
+        Balance,
+    },
+    codec_wrapper::{
+        RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder,
+        RISTRETTO_POINT_SIZE,
+    },
 };
 
 use bulletproofs::PedersenGens;
 use core::ops::{Add, AddAssign, Sub, SubAssign};
-use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use curve25519_dalek::{
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::IsIdentity,
+};
 use rand::rngs::StdRng;
 use rand_core::{CryptoRng, RngCore};
+use sha3::{digest::FixedOutput, Digest, Sha3_256};
+use subtle::{Choice, ConditionallySelectable};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroize;
 
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
-use sp_std::prelude::*;
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 
 /// Prover's representation of the commitment secret.
 #[derive(Clone, PartialEq, Zeroize, Debug)]
@@ -80,6 +92,12 @@ impl Decode for CommitmentWitness {
     }
 }
 
+/// The size of an encoded `CipherText`, i.e., two compressed Ristretto points.
+pub const CIPHERTEXT_SIZE: usize = 2 * RISTRETTO_POINT_SIZE;
+
+/// The domain separation label of the shared secrets of `ElgamalSecretKey::shared_secret`.
+const SHARED_SECRET_LABEL: &[u8] = b"PolymathElgamalSharedSecret";
+
 /// Prover's representation of the encrypted secret.
 #[derive(PartialEq, Copy, Clone, Default, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -102,10 +120,9 @@ impl Encode for CipherText {
 
 impl Decode for CipherText {
     fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
-        let x = <RistrettoPointDecoder>::decode(input)?.0;
-        let y = <RistrettoPointDecoder>::decode(input)?.0;
-
-        Ok(CipherText { x, y })
+        let bytes = <[u8; CIPHERTEXT_SIZE]>::decode(input)?;
+        CipherText::from_bytes(&bytes)
+            .map_err(|_| CodecError::from("Invalid `CipherText` encoding."))
     }
 }
 
@@ -151,6 +168,18 @@ impl<'b> SubAssign<&'b CipherText> for CipherText {
 }
 define_sub_assign_variants!(LHS = CipherText, RHS = CipherText);
 
+/// Selects one of two cipher texts based on a secret bit without branching, e.g., for
+/// oblivious transfer-like flows. `CipherText::conditional_select(a, b, choice)` returns
+/// `a` if `choice` is 0 and `b` if `choice` is 1.
+impl ConditionallySelectable for CipherText {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        CipherText {
+            x: RistrettoPoint::conditional_select(&a.x, &b.x, choice),
+            y: RistrettoPoint::conditional_select(&a.y, &b.y, choice),
+        }
+    }
+}
+
 // ------------------------------------------------------------------------
 // Elgamal Encryption.
 // ------------------------------------------------------------------------
@@ -229,6 +258,17 @@ impl ElgamalPublicKey {
             self.encrypt_helper(value, blinding),
         )
     }
+
+    /// Encrypts the value using the supplied blinding factor instead of a fresh one.
+    /// This allows linking the ciphertext to a commitment made elsewhere with the same
+    /// blinding, e.g., the commitment of a range proof.
+    ///
+    /// Security: the blinding must be secret and uniformly random. Reusing it for two
+    /// different values under the same key reveals the difference of the values, since
+    /// the `x` terms are equal and the `y` terms differ by `(v1 - v2) * B`.
+    pub fn encrypt_value_with_blind(&self, value: Scalar, blind: Scalar) -> CipherText {
+        self.encrypt_helper(value, blind)
+    }
 }
 
 impl Encode for ElgamalPublicKey {
@@ -294,6 +334,39 @@ impl ElgamalSecretKey {
 
         Err(ErrorKind::CipherTextDecryptionError.into())
     }
+
+    /// Derives a 32-byte symmetric key shared with the owner of `other_pub`, by hashing
+    /// `my_secret * other_pub` together with both public keys. Both parties derive the same
+    /// key, since `a * (b * H) == b * (a * H)` and the public keys are hashed in the order of
+    /// their encodings.
+    ///
+    /// Returns `ErrorKind::IdentityPublicKey` if `other_pub` is the identity point, for which
+    /// the shared point is publicly known.
+    pub fn shared_secret(&self, other_pub: &ElgamalPublicKey) -> Fallible<[u8; 32]> {
+        ensure!(
+            !other_pub.pub_key.is_identity(),
+            ErrorKind::IdentityPublicKey
+        );
+        let shared_point = (self.secret * other_pub.pub_key).compress();
+        let my_pub = self.get_public_key().pub_key.compress();
+        let other_pub = other_pub.pub_key.compress();
+        let (first, second) = if my_pub.as_bytes() <= other_pub.as_bytes() {
+            (my_pub, other_pub)
+        } else {
+            (other_pub, my_pub)
+        };
+
+        let mut key = [0u8; 32];
+        key.copy_from_slice(
+            &Sha3_256::default()
+                .chain(SHARED_SECRET_LABEL)
+                .chain(shared_point.as_bytes())
+                .chain(first.as_bytes())
+                .chain(second.as_bytes())
+                .fixed_result(),
+        );
+        Ok(key)
+    }
 }
 
 pub fn encrypt_using_two_pub_keys(
@@ -311,11 +384,69 @@ pub fn encrypt_using_two_pub_keys(
     (enc1, enc2)
 }
 
+/// Encrypts the same value to a batch of public keys, using a single witness.
+///
+/// Reusing the blinding factor across recipients is safe as long as the public keys are
+/// independently generated, since the `X` component of each cipher text is `r * pub_key`
+/// for a different key. This is the same randomness reuse as in `encrypt_using_two_pub_keys`,
+/// and it allows the `EncryptingSameValue` proofs to be generated from the single returned
+/// witness. As a result, all the cipher texts share the same `Y` component, which reveals
+/// that they encrypt the same value. Use `encrypt_value` per recipient when this
+/// linkability is not acceptable.
+pub fn encrypt_value_to_many<R: RngCore + CryptoRng>(
+    value: Scalar,
+    pub_keys: &[ElgamalPublicKey],
+    rng: &mut R,
+) -> (CommitmentWitness, Vec<CipherText>) {
+    let witness = CommitmentWitness {
+        value,
+        blinding: Scalar::random(rng),
+    };
+    let gens = PedersenGens::default();
+    let y = gens.commit(witness.value, witness.blinding);
+    let ciphers = pub_keys
+        .iter()
+        .map(|pub_key| CipherText {
+            x: witness.blinding * pub_key.pub_key,
+            y,
+        })
+        .collect();
+
+    (witness, ciphers)
+}
+
 // ------------------------------------------------------------------------
 // CipherText Refreshment Method
 // ------------------------------------------------------------------------
 
 impl CipherText {
+    /// Returns the compressed encodings of `x` and `y`.
+    pub fn to_bytes(&self) -> [u8; CIPHERTEXT_SIZE] {
+        let mut bytes = [0u8; CIPHERTEXT_SIZE];
+        bytes[..RISTRETTO_POINT_SIZE].copy_from_slice(self.x.compress().as_bytes());
+        bytes[RISTRETTO_POINT_SIZE..].copy_from_slice(self.y.compress().as_bytes());
+        bytes
+    }
+
+    /// Parses a ciphertext from untrusted bytes. Both points are decompressed, so that
+    /// non-canonical encodings are rejected with `InvalidCiphertextEncoding`.
+    pub fn from_bytes(bytes: &[u8]) -> Fallible<Self> {
+        ensure!(
+            bytes.len() == CIPHERTEXT_SIZE,
+            ErrorKind::InvalidCiphertextEncoding
+        );
+        let decompress = |point: &[u8]| {
+            CompressedRistretto::from_slice(point)
+                .decompress()
+                .ok_or(ErrorKind::InvalidCiphertextEncoding)
+        };
+
+        Ok(CipherText {
+            x: decompress(&bytes[..RISTRETTO_POINT_SIZE])?,
+            y: decompress(&bytes[RISTRETTO_POINT_SIZE..])?,
+        })
+    }
+
     pub fn refresh(&self, secret_key: &ElgamalSecretKey, blinding: Scalar) -> Fallible<CipherText> {
         let value: Scalar = secret_key.decrypt(self)?.into();
         let pub_key = secret_key.get_public_key();
@@ -343,6 +474,154 @@ impl CipherText {
     }
 }
 
+// ------------------------------------------------------------------------
+// Batch decryption with a baby-step giant-step table.
+// ------------------------------------------------------------------------
+
+/// A precomputed baby-step giant-step table for decrypting the values in `[0, range)`.
+/// Building the table costs about `sqrt(range)` point additions and it can be shared by
+/// all the decryptions, after which each decryption costs at most `sqrt(range)` steps.
+#[derive(Clone, Debug)]
+pub struct BsgsTable {
+    /// Maps the compressed `j * B` to `j`, for all `j` in `[0, baby_steps)`.
+    table: BTreeMap<[u8; 32], u32>,
+    baby_steps: u32,
+    giant_steps: u32,
+    /// `baby_steps * B`.
+    giant_step: RistrettoPoint,
+    range: u64,
+}
+
+impl BsgsTable {
+    /// Builds the table for the values in `[0, range)`, where `range` is at most `2^32`.
+    pub fn new(range: u64) -> Fallible<Self> {
+        ensure!(
+            range != 0 && range <= 1u64 << 32,
+            ErrorKind::InvalidDecryptionRange { range }
+        );
+
+        let mut baby_steps = 1u64;
+        while baby_steps * baby_steps < range {
+            baby_steps += 1;
+        }
+        let giant_steps = (range + baby_steps - 1) / baby_steps;
+
+        let gens = PedersenGens::default();
+        let mut table = BTreeMap::new();
+        let mut point = RistrettoPoint::default();
+        for j in 0..baby_steps as u32 {
+            table.insert(point.compress().to_bytes(), j);
+            point += gens.B;
+        }
+
+        Ok(BsgsTable {
+            table,
+            baby_steps: baby_steps as u32,
+            giant_steps: giant_steps as u32,
+            giant_step: point,
+            range,
+        })
+    }
+
+    /// The values in `[0, range())` can be decrypted with this table.
+    pub fn range(&self) -> u64 {
+        self.range
+    }
+
+    /// Finds `value` such that `value * B == value_h`.
+    fn solve(&self, value_h: RistrettoPoint) -> Fallible<Balance> {
+        let mut point = value_h;
+        for i in 0..self.giant_steps {
+            if let Some(j) = self.table.get(point.compress().as_bytes()) {
+                let value = i as u64 * self.baby_steps as u64 + *j as u64;
+                ensure!(value < self.range, ErrorKind::CipherTextDecryptionError);
+                return Ok(value as Balance);
+            }
+            point -= self.giant_step;
+        }
+
+        Err(ErrorKind::CipherTextDecryptionError.into())
+    }
+}
+
+impl ElgamalSecretKey {
+    /// Decrypts a cipher text whose value is in the range of `table`.
+    pub fn decrypt_with_table(
+        &self,
+        cipher_text: &CipherText,
+        table: &BsgsTable,
+    ) -> Fallible<Balance> {
+        table.solve(cipher_text.y - self.secret.invert() * cipher_text.x)
+    }
+}
+
+/// Decrypts all the `ciphers` with the same `secret_key` and `table`. The result of each
+/// decryption is returned separately, so that a single failure, e.g., a value outside of
+/// the range of the table, does not abort the batch. With the `rayon` feature, the
+/// ciphers are decrypted in parallel.
+pub fn decrypt_many(
+    secret_key: &ElgamalSecretKey,
+    ciphers: &[CipherText],
+    table: &BsgsTable,
+) -> Vec<Fallible<Balance>> {
+    let secret_inverse = secret_key.secret.invert();
+    let decrypt = |cipher: &CipherText| table.solve(cipher.y - secret_inverse * cipher.x);
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        ciphers.par_iter().map(decrypt).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        ciphers.iter().map(decrypt).collect()
+    }
+}
+
+// ------------------------------------------------------------------------
+// Signed values.
+// ------------------------------------------------------------------------
+
+/// Maps a signed value to the scalar field, such that a negative value is the additive
+/// inverse of its magnitude. Adding the encryption of a negative value to a cipher text
+/// subtracts its magnitude from the encrypted value.
+pub fn signed_value_to_scalar(value: i64) -> Scalar {
+    let magnitude = Scalar::from((value as i128).abs() as u64);
+    if value < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Generates a blinding factor, and encrypts the signed value. See `signed_value_to_scalar`
+/// for the encoding of the sign, and `range_proof::prove_signed_within_range` for proving
+/// that the value is in a signed range.
+pub fn enc_signed_value<R: RngCore + CryptoRng>(
+    value: i64,
+    pub_key: &ElgamalPublicKey,
+    rng: &mut R,
+) -> (CommitmentWitness, CipherText) {
+    pub_key.encrypt_value(signed_value_to_scalar(value), rng)
+}
+
+impl ElgamalSecretKey {
+    /// Decrypts a cipher text of a signed value in `[-range / 2, range / 2)`, where `range`
+    /// is the range of the `table`. This is the counterpart of `enc_signed_value`.
+    pub fn decrypt_signed_with_table(
+        &self,
+        cipher_text: &CipherText,
+        table: &BsgsTable,
+    ) -> Fallible<i64> {
+        let gens = PedersenGens::default();
+        let offset = table.range() / 2;
+        // Shift the value into `[0, range)`, where the table can find it.
+        let value_h = cipher_text.y - self.secret.invert() * cipher_text.x;
+        let shifted = table.solve(value_h + Scalar::from(offset) * gens.B)?;
+        Ok(shifted as i64 - offset as i64)
+    }
+}
+
 // ------------------------------------------------------------------------
 // Tests
 // ------------------------------------------------------------------------
@@ -351,7 +630,8 @@ impl CipherText {
 mod tests {
     extern crate wasm_bindgen_test;
     use super::*;
-    use crate::asset_proofs::{AssetId, Balance};
+    use crate::asset_proofs::{range_proof::prove_within_range, AssetId, Balance};
+    use curve25519_dalek::traits::Identity;
     use rand::{rngs::StdRng, SeedableRng};
     use wasm_bindgen_test::*;
 
@@ -360,6 +640,77 @@ mod tests {
     const SEED_1: [u8; 32] = [42u8; 32];
     const SEED_2: [u8; 32] = [56u8; 32];
 
+    #[test]
+    #[wasm_bindgen_test]
+    fn signed_enc_dec() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let table = BsgsTable::new(1000).unwrap();
+
+        for value in &[-500i64, -42, -1, 0, 1, 42, 499] {
+            let (_, cipher) = enc_signed_value(*value, &elg_pub, &mut rng);
+            assert_eq!(
+                elg_secret
+                    .decrypt_signed_with_table(&cipher, &table)
+                    .unwrap(),
+                *value
+            );
+        }
+
+        // Adding a negative value subtracts its magnitude.
+        let (_, balance) = elg_pub.encrypt_value(Scalar::from(100u32), &mut rng);
+        let (_, refund) = enc_signed_value(-30, &elg_pub, &mut rng);
+        assert_eq!(elg_secret.decrypt(&(balance + refund)).unwrap(), 70);
+
+        // The values outside of the signed range of the table are not found.
+        let (_, cipher) = enc_signed_value(500, &elg_pub, &mut rng);
+        assert_err!(
+            elg_secret.decrypt_signed_with_table(&cipher, &table),
+            ErrorKind::CipherTextDecryptionError
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn shared_secret_agreement() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let alice_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let bob_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let eve_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+
+        let alice_key = alice_secret
+            .shared_secret(&bob_secret.get_public_key())
+            .unwrap();
+        let bob_key = bob_secret
+            .shared_secret(&alice_secret.get_public_key())
+            .unwrap();
+        assert_eq!(alice_key, bob_key);
+
+        // A third party derives a different key with either of them.
+        assert_ne!(
+            eve_secret
+                .shared_secret(&bob_secret.get_public_key())
+                .unwrap(),
+            bob_key
+        );
+        assert_ne!(
+            eve_secret
+                .shared_secret(&alice_secret.get_public_key())
+                .unwrap(),
+            alice_key
+        );
+
+        // The identity point would give a publicly known key.
+        let identity = ElgamalPublicKey {
+            pub_key: RistrettoPoint::identity(),
+        };
+        assert_err!(
+            alice_secret.shared_secret(&identity),
+            ErrorKind::IdentityPublicKey
+        );
+    }
+
     #[test]
     #[wasm_bindgen_test]
     fn basic_enc_dec() {
@@ -460,4 +811,152 @@ mod tests {
         assert_eq!(value, msg1);
         assert_eq!(value, msg2);
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_encrypt_value_to_many() {
+        let mut rng = StdRng::from_seed([17u8; 32]);
+        let value = 256u32;
+
+        let secret_keys: Vec<ElgamalSecretKey> = (0..3)
+            .map(|_| ElgamalSecretKey::new(Scalar::random(&mut rng)))
+            .collect();
+        let pub_keys: Vec<ElgamalPublicKey> =
+            secret_keys.iter().map(|key| key.get_public_key()).collect();
+
+        let (witness, ciphers) = encrypt_value_to_many(value.into(), &pub_keys, &mut rng);
+        assert_eq!(ciphers.len(), pub_keys.len());
+        for ((secret_key, pub_key), cipher) in secret_keys.iter().zip(&pub_keys).zip(&ciphers) {
+            assert_eq!(secret_key.decrypt(cipher).unwrap(), value);
+            assert_eq!(pub_key.encrypt(&witness), *cipher);
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_encrypt_value_with_blind() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let value = 256u32;
+        let blind = Scalar::random(&mut rng);
+
+        // The same value and blind produce identical ciphertexts.
+        let cipher = elg_pub.encrypt_value_with_blind(value.into(), blind);
+        assert_eq!(
+            cipher,
+            elg_pub.encrypt_value_with_blind(value.into(), blind)
+        );
+        assert_eq!(
+            cipher,
+            elg_pub.encrypt(&CommitmentWitness {
+                value: value.into(),
+                blinding: blind,
+            })
+        );
+        assert_eq!(elg_secret.decrypt(&cipher).unwrap(), value);
+
+        // The commitment of a range proof with the same blind matches the ciphertext.
+        let range_proof = prove_within_range(value.into(), blind, 32, &mut rng).unwrap();
+        assert_eq!(range_proof.init, cipher.y.compress());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_ciphertext_encoding() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_pub = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let (_, cipher) = elg_pub.encrypt_value(42u32.into(), &mut rng);
+
+        // Positive tests: a valid ciphertext round trips.
+        let bytes = cipher.to_bytes();
+        assert_eq!(CipherText::from_bytes(&bytes).unwrap(), cipher);
+        let encoded = cipher.encode();
+        assert_eq!(encoded, bytes.to_vec());
+        assert_eq!(CipherText::decode(&mut &encoded[..]).unwrap(), cipher);
+
+        // Negative tests: a corrupted point is rejected.
+        let mut corrupted = bytes;
+        corrupted[..RISTRETTO_POINT_SIZE].copy_from_slice(&[0xffu8; RISTRETTO_POINT_SIZE]);
+        assert_err!(
+            CipherText::from_bytes(&corrupted),
+            ErrorKind::InvalidCiphertextEncoding
+        );
+        assert!(CipherText::decode(&mut &corrupted[..]).is_err());
+
+        let mut corrupted = bytes;
+        corrupted[CIPHERTEXT_SIZE - 1] ^= 0xff;
+        assert_err!(
+            CipherText::from_bytes(&corrupted),
+            ErrorKind::InvalidCiphertextEncoding
+        );
+
+        assert_err!(
+            CipherText::from_bytes(&bytes[1..]),
+            ErrorKind::InvalidCiphertextEncoding
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_ciphertext_conditional_select() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_pub = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let (_, a) = elg_pub.encrypt_value(1u32.into(), &mut rng);
+        let (_, b) = elg_pub.encrypt_value(2u32.into(), &mut rng);
+
+        assert_eq!(CipherText::conditional_select(&a, &b, Choice::from(0)), a);
+        assert_eq!(CipherText::conditional_select(&a, &b, Choice::from(1)), b);
+
+        // The other operations of `ConditionallySelectable` are derived from the selection.
+        let mut c = a;
+        c.conditional_assign(&b, Choice::from(1));
+        assert_eq!(c, b);
+        let (mut c, mut d) = (a, b);
+        CipherText::conditional_swap(&mut c, &mut d, Choice::from(1));
+        assert_eq!((c, d), (b, a));
+        CipherText::conditional_swap(&mut c, &mut d, Choice::from(0));
+        assert_eq!((c, d), (b, a));
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_decrypt_many() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+
+        assert!(BsgsTable::new(0).is_err());
+        assert!(BsgsTable::new((1u64 << 32) + 1).is_err());
+        let table = BsgsTable::new(1000).unwrap();
+        assert_eq!(table.range(), 1000);
+
+        // The third ciphertext is out of the table's range.
+        let values = [0u32, 999, 1000, 17, 500];
+        let ciphers: Vec<CipherText> = values
+            .iter()
+            .map(|value| elg_pub.encrypt_value((*value).into(), &mut rng).1)
+            .collect();
+        let results = decrypt_many(&elg_secret, &ciphers, &table);
+        assert_eq!(results.len(), values.len());
+        for (index, (result, value)) in results.iter().zip(values.iter()).enumerate() {
+            if index == 2 {
+                assert_eq!(
+                    result.as_ref().expect_err("Error expected").kind(),
+                    &ErrorKind::CipherTextDecryptionError
+                );
+            } else {
+                assert_eq!(result.as_ref().unwrap(), value);
+            }
+        }
+
+        assert_eq!(
+            elg_secret.decrypt_with_table(&ciphers[3], &table).unwrap(),
+            17
+        );
+        assert_eq!(
+            elg_secret.decrypt_with_table(&ciphers[3], &table).unwrap(),
+            elg_secret.decrypt(&ciphers[3]).unwrap()
+        );
+    }
 }
@@ -186,6 +186,11 @@ impl<'a> AssetProofProverAwaitingChallenge for EncryptingSameValueProverAwaiting
             },
         )
     }
+
+    #[cfg(feature = "blind-tracker")]
+    fn blinding(&self) -> Option<Scalar> {
+        Some(self.w.blinding())
+    }
 }
 
 impl AssetProofProver<EncryptingSameValueFinalResponse> for EncryptingSameValueProver {
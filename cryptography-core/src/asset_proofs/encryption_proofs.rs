@@ -1,24 +1,34 @@
 //! Encryption proofs' interface definitions and
 //! Non-Interactive Zero Knowledge Proof API.
 
+use codec::{Decode, Encode};
 use curve25519_dalek::scalar::Scalar;
 use merlin::{Transcript, TranscriptRng};
 use rand_core::{CryptoRng, RngCore};
-use sp_std::convert::TryFrom;
+use sha3::{digest::FixedOutput, Digest, Keccak256};
+use sp_std::{
+    convert::{TryFrom, TryInto},
+    vec::Vec,
+};
 
 use super::errors::{Error, ErrorKind, Fallible};
+#[cfg(feature = "blind-tracker")]
+use crate::asset_proofs::blind_tracker::BlindTracker;
 use crate::asset_proofs::transcript::{TranscriptProtocol, UpdateTranscript};
 
 /// The domain label for the encryption proofs.
 pub const ENCRYPTION_PROOFS_LABEL: &[u8] = b"PolymathEncryptionProofs";
 /// The domain label for the challenge.
 pub const ENCRYPTION_PROOFS_CHALLENGE_LABEL: &[u8] = b"PolymathEncryptionProofsChallenge";
+/// The domain label for the context that a proof is bound to.
+pub const ENCRYPTION_PROOFS_CONTEXT_LABEL: &[u8] = b"PolymathEncryptionProofsContext";
 
 // ------------------------------------------------------------------------
 // Sigma Protocol's Prover and Verifier Interfaces
 // ------------------------------------------------------------------------
 
 /// A scalar challenge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ZKPChallenge {
     x: Scalar,
 }
@@ -82,6 +92,13 @@ pub trait AssetProofProverAwaitingChallenge {
         &self,
         rng: &mut TranscriptRng,
     ) -> (Self::ZKProver, Self::ZKInitialMessage);
+
+    /// The blinding factor of the encryption that the prover proves a property of, if any.
+    /// Fed to the `BlindTracker` by `single_property_prover_with_tracker`.
+    #[cfg(feature = "blind-tracker")]
+    fn blinding(&self) -> Option<Scalar> {
+        None
+    }
 }
 
 pub trait AssetProofProver<ZKFinalResponse> {
@@ -119,6 +136,17 @@ pub trait AssetProofVerifier {
     ) -> Fallible<()>;
 }
 
+/// Compares the proof elements by the group elements and the scalars that they represent,
+/// instead of by their byte representations, e.g., when comparing a stored proof with a
+/// freshly generated one for the same deterministic inputs.
+///
+/// Only the elements whose derived `==` compares the representations implement it: the
+/// unreduced scalars, and the compressed points, which may not be valid encodings.
+pub trait ProofExt {
+    /// Returns true if both values represent the same curve points and scalars.
+    fn crypto_eq(&self, other: &Self) -> bool;
+}
+
 // ------------------------------------------------------------------------
 // Non-Interactive Zero Knowledge Proofs API
 // ------------------------------------------------------------------------
@@ -127,6 +155,125 @@ pub trait AssetProofVerifier {
 /// of the initial message and the final response.
 pub type ZKProofResponse<ZKInitialMessage, ZKFinalResponse> = (ZKInitialMessage, ZKFinalResponse);
 
+/// Deserializes a proof, or any of its parts, from its SCALE encoding. All of `bytes` must
+/// be consumed. Any failure is reported as `ErrorKind::MalformedProof`, which can be told
+/// apart from the verification failures using `Error::is_malformed`.
+pub fn decode_proof<T: Decode>(bytes: &[u8]) -> Fallible<T> {
+    let mut input = bytes;
+    let proof = T::decode(&mut input).map_err(|_| ErrorKind::MalformedProof)?;
+    ensure!(input.is_empty(), ErrorKind::MalformedProof);
+    Ok(proof)
+}
+
+/// The version of the transcript construction that a serialized proof was created with.
+/// The serialized proofs are tagged with their version, so that the proofs that were
+/// stored before a change of the transcript labels or of the proof format still verify
+/// during a migration window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofVersion {
+    /// The Merlin transcript of `single_property_prover`, labelled with
+    /// `ENCRYPTION_PROOFS_LABEL` and `ENCRYPTION_PROOFS_CHALLENGE_LABEL`.
+    V1 = 1,
+}
+
+impl ProofVersion {
+    /// The version that the new proofs are tagged with.
+    pub const LATEST: ProofVersion = ProofVersion::V1;
+}
+
+impl TryFrom<u8> for ProofVersion {
+    type Error = Error;
+
+    fn try_from(tag: u8) -> Result<Self, Self::Error> {
+        match tag {
+            1 => Ok(ProofVersion::V1),
+            version => Err(ErrorKind::UnsupportedProofVersion { version }.into()),
+        }
+    }
+}
+
+/// Serializes `value` prefixed with the one byte tag of `ProofVersion::LATEST`.
+/// This is the layout of the versioned proofs, and of the structures that carry them.
+pub fn encode_versioned<T: Encode>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(1 + value.size_hint());
+    bytes.push(ProofVersion::LATEST as u8);
+    value.encode_to(&mut bytes);
+    bytes
+}
+
+/// Deserializes a value that was serialized with `encode_versioned`, and returns it along
+/// with the version it is tagged with. Returns `ErrorKind::UnsupportedProofVersion` if the
+/// version is unknown, and `ErrorKind::MalformedProof` if the value cannot be deserialized.
+pub fn decode_versioned<T: Decode>(bytes: &[u8]) -> Fallible<(ProofVersion, T)> {
+    let (tag, value) = bytes.split_first().ok_or(ErrorKind::MalformedProof)?;
+    let version = ProofVersion::try_from(*tag)?;
+    Ok((version, decode_proof(value)?))
+}
+
+/// Serializes a proof, created by `single_property_prover`, prefixed with the one byte tag
+/// of `ProofVersion::LATEST`.
+pub fn encode_versioned_proof<ZKInitialMessage: Encode, ZKFinalResponse: Encode>(
+    proof: &ZKProofResponse<ZKInitialMessage, ZKFinalResponse>,
+) -> Vec<u8> {
+    encode_versioned(proof)
+}
+
+/// Deserializes a proof that was serialized with `encode_versioned_proof`, and verifies it
+/// using the transcript construction of the version it is tagged with.
+///
+/// # Inputs
+/// `verifier` Any verifier that implements the `AssetProofVerifier` trait.
+/// `bytes`    The version tag followed by the SCALE encoded proof.
+///
+/// # Outputs
+/// Ok on success, `ErrorKind::UnsupportedProofVersion` if the version is unknown,
+/// or failure on error.
+pub fn single_property_verifier_versioned<Verifier: AssetProofVerifier>(
+    verifier: &Verifier,
+    bytes: &[u8],
+) -> Fallible<()>
+where
+    Verifier::ZKInitialMessage: Decode,
+    Verifier::ZKFinalResponse: Decode,
+{
+    let (version, (initial_message, final_response)): (
+        ProofVersion,
+        ZKProofResponse<Verifier::ZKInitialMessage, Verifier::ZKFinalResponse>,
+    ) = decode_versioned(bytes)?;
+    match version {
+        ProofVersion::V1 => {
+            single_property_verifier_ref(verifier, &initial_message, &final_response)
+        }
+    }
+}
+
+/// Runs the prover on `transcript`, which may already be bound to some context, and returns
+/// the initial message, the final response, and the challenge. This is the common part of
+/// `single_property_prover`, `single_property_prover_with_context` and `debug_trace`.
+fn prove_with_transcript<
+    T: RngCore + CryptoRng,
+    ProverAwaitingChallenge: AssetProofProverAwaitingChallenge,
+>(
+    prover_ac: ProverAwaitingChallenge,
+    mut transcript: Transcript,
+    rng: &mut T,
+) -> Fallible<(
+    ProverAwaitingChallenge::ZKInitialMessage,
+    ProverAwaitingChallenge::ZKFinalResponse,
+    ZKPChallenge,
+)> {
+    let mut transcript_rng = prover_ac.create_transcript_rng(rng, &transcript);
+    let (prover, initial_message) = prover_ac.generate_initial_message(&mut transcript_rng);
+
+    // Update the transcript with Prover's initial message
+    initial_message.update_transcript(&mut transcript)?;
+    let challenge = transcript.scalar_challenge(ENCRYPTION_PROOFS_CHALLENGE_LABEL)?;
+
+    let final_response = prover.apply_challenge(&challenge);
+
+    Ok((initial_message, final_response, challenge))
+}
+
 /// The non-interactive implementation of the protocol for a single
 /// encryption proof's prover role.
 ///
@@ -148,44 +295,395 @@ pub fn single_property_prover<
         ProverAwaitingChallenge::ZKFinalResponse,
     >,
 > {
+    let (initial_message, final_response, _) =
+        prove_with_transcript(prover_ac, Transcript::new(ENCRYPTION_PROOFS_LABEL), rng)?;
+
+    Ok((initial_message, final_response))
+}
+
+/// Same as `single_property_prover`, but first records the blinding factor of the prover's
+/// encryption in `tracker`, and fails with `ErrorKind::ReusedBlinding` if the session
+/// has already used it.
+#[cfg(feature = "blind-tracker")]
+pub fn single_property_prover_with_tracker<
+    T: RngCore + CryptoRng,
+    ProverAwaitingChallenge: AssetProofProverAwaitingChallenge,
+>(
+    prover_ac: ProverAwaitingChallenge,
+    tracker: &mut BlindTracker,
+    rng: &mut T,
+) -> Fallible<
+    ZKProofResponse<
+        ProverAwaitingChallenge::ZKInitialMessage,
+        ProverAwaitingChallenge::ZKFinalResponse,
+    >,
+> {
+    if let Some(blinding) = prover_ac.blinding() {
+        tracker.track(&blinding)?;
+    }
+    single_property_prover(prover_ac, rng)
+}
+
+/// The non-interactive implementation of the protocol for a single
+/// encryption proof's verifier role.
+///
+/// # Inputs
+/// `verifier` Any verifier that implements the `AssetProofVerifier` trait.
+/// `proof`    Prover's initial message and final response.
+///
+/// # Outputs
+/// Ok on success, or failure on error.
+pub fn single_property_verifier<Verifier: AssetProofVerifier>(
+    verifier: &Verifier,
+    proof: ZKProofResponse<Verifier::ZKInitialMessage, Verifier::ZKFinalResponse>,
+) -> Fallible<()> {
+    single_property_verifier_ref(verifier, &proof.0, &proof.1)
+}
+
+/// The same as `single_property_verifier`, but borrows the proof, so that the
+/// caller can keep it after the verification without cloning it.
+///
+/// # Inputs
+/// `verifier`        Any verifier that implements the `AssetProofVerifier` trait.
+/// `initial_message` Prover's initial message.
+/// `final_response`  Prover's final response.
+///
+/// # Outputs
+/// Ok on success, or failure on error.
+pub fn single_property_verifier_ref<Verifier: AssetProofVerifier>(
+    verifier: &Verifier,
+    initial_message: &Verifier::ZKInitialMessage,
+    final_response: &Verifier::ZKFinalResponse,
+) -> Fallible<()> {
     let mut transcript = Transcript::new(ENCRYPTION_PROOFS_LABEL);
 
-    let mut transcript_rng = prover_ac.create_transcript_rng(rng, &transcript);
-    let (prover, initial_message) = prover_ac.generate_initial_message(&mut transcript_rng);
+    // Update the transcript with Prover's initial message
+    initial_message.update_transcript(&mut transcript)?;
+    let challenge = transcript.scalar_challenge(ENCRYPTION_PROOFS_CHALLENGE_LABEL)?;
+
+    verifier.verify(&challenge, initial_message, final_response)?;
+
+    Ok(())
+}
+
+/// The same as `single_property_verifier_ref`, but also returns the challenge that
+/// the verifier derived from the initial message, alongside the verification result.
+/// Meant for debugging proof failures by comparing the prover's and the verifier's
+/// challenges.
+///
+/// # Inputs
+/// `verifier`        Any verifier that implements the `AssetProofVerifier` trait.
+/// `initial_message` Prover's initial message.
+/// `final_response`  Prover's final response.
+///
+/// # Outputs
+/// The challenge and the verification result, or failure if the challenge could not be
+/// derived.
+pub fn single_property_verifier_debug<Verifier: AssetProofVerifier>(
+    verifier: &Verifier,
+    initial_message: &Verifier::ZKInitialMessage,
+    final_response: &Verifier::ZKFinalResponse,
+) -> Fallible<(ZKPChallenge, Fallible<()>)> {
+    let mut transcript = Transcript::new(ENCRYPTION_PROOFS_LABEL);
 
     // Update the transcript with Prover's initial message
     initial_message.update_transcript(&mut transcript)?;
     let challenge = transcript.scalar_challenge(ENCRYPTION_PROOFS_CHALLENGE_LABEL)?;
 
-    let final_response = prover.apply_challenge(&challenge);
+    let result = verifier.verify(&challenge, initial_message, final_response);
+
+    Ok((challenge, result))
+}
+
+/// Creates the transcript of a proof that is bound to `context`.
+fn context_transcript(context: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(ENCRYPTION_PROOFS_LABEL);
+    transcript.append_message(ENCRYPTION_PROOFS_CONTEXT_LABEL, context);
+    transcript
+}
+
+/// The same as `single_property_prover`, but binds the proof to `context`, e.g., the data
+/// that the proof is attached to, by appending it to the transcript before the initial
+/// message. The proof only verifies with `single_property_verifier_with_context` and the
+/// same `context`.
+///
+/// # Inputs
+/// `prover`  Any prover that implements the `AssetProofProver` trait.
+/// `context` The data that the proof is bound to.
+/// `rng`     An RNG.
+///
+/// # Outputs
+/// An initial message and a final response as a tuple on success, or failure on an error.
+pub fn single_property_prover_with_context<
+    T: RngCore + CryptoRng,
+    ProverAwaitingChallenge: AssetProofProverAwaitingChallenge,
+>(
+    prover_ac: ProverAwaitingChallenge,
+    context: &[u8],
+    rng: &mut T,
+) -> Fallible<
+    ZKProofResponse<
+        ProverAwaitingChallenge::ZKInitialMessage,
+        ProverAwaitingChallenge::ZKFinalResponse,
+    >,
+> {
+    let (initial_message, final_response, _) =
+        prove_with_transcript(prover_ac, context_transcript(context), rng)?;
 
     Ok((initial_message, final_response))
 }
 
-/// The non-interactive implementation of the protocol for a single
-/// encryption proof's verifier role.
+/// The same as `single_property_verifier`, but for the proofs that are bound to `context`
+/// by `single_property_prover_with_context`.
 ///
 /// # Inputs
 /// `verifier` Any verifier that implements the `AssetProofVerifier` trait.
 /// `proof`    Prover's initial message and final response.
+/// `context`  The data that the proof is bound to.
 ///
 /// # Outputs
 /// Ok on success, or failure on error.
-pub fn single_property_verifier<Verifier: AssetProofVerifier>(
+pub fn single_property_verifier_with_context<Verifier: AssetProofVerifier>(
     verifier: &Verifier,
     proof: ZKProofResponse<Verifier::ZKInitialMessage, Verifier::ZKFinalResponse>,
+    context: &[u8],
 ) -> Fallible<()> {
-    let initial_message = proof.0;
-    let final_response = proof.1;
-    let mut transcript = Transcript::new(ENCRYPTION_PROOFS_LABEL);
+    let (initial_message, final_response) = proof;
+    let mut transcript = context_transcript(context);
 
     // Update the transcript with Prover's initial message
     initial_message.update_transcript(&mut transcript)?;
     let challenge = transcript.scalar_challenge(ENCRYPTION_PROOFS_CHALLENGE_LABEL)?;
 
-    verifier.verify(&challenge, &initial_message, &final_response)?;
+    verifier.verify(&challenge, &initial_message, &final_response)
+}
 
-    Ok(())
+// ------------------------------------------------------------------------
+// Challenge Schemes
+// ------------------------------------------------------------------------
+
+/// The hash function used to derive the Fiat-Shamir challenge of the
+/// non-interactive proofs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChallengeScheme {
+    /// The challenge is generated by a Merlin transcript. This is the scheme used by
+    /// `single_property_prover` and `single_property_verifier`.
+    Merlin,
+    /// The challenge is `Keccak256(ENCRYPTION_PROOFS_LABEL || ENCRYPTION_PROOFS_CHALLENGE_LABEL
+    /// || SCALE(initial_message))`, where the 32 byte digest is interpreted as a little-endian
+    /// integer and reduced modulo the group order. This allows verifiers, such as EVM
+    /// contracts, that only have access to Keccak256 to recompute the challenge.
+    Keccak256,
+}
+
+impl Default for ChallengeScheme {
+    fn default() -> Self {
+        ChallengeScheme::Merlin
+    }
+}
+
+/// Derives the challenge of a non-interactive proof from the prover's initial message.
+fn scheme_challenge<InitialMessage: UpdateTranscript + Encode>(
+    scheme: ChallengeScheme,
+    initial_message: &InitialMessage,
+) -> Fallible<ZKPChallenge> {
+    match scheme {
+        ChallengeScheme::Merlin => {
+            let mut transcript = Transcript::new(ENCRYPTION_PROOFS_LABEL);
+            initial_message.update_transcript(&mut transcript)?;
+            transcript.scalar_challenge(ENCRYPTION_PROOFS_CHALLENGE_LABEL)
+        }
+        ChallengeScheme::Keccak256 => {
+            let digest = Keccak256::default()
+                .chain(ENCRYPTION_PROOFS_LABEL)
+                .chain(ENCRYPTION_PROOFS_CHALLENGE_LABEL)
+                .chain(initial_message.encode())
+                .fixed_result();
+            let mut bytes = [0u8; 32];
+            bytes.copy_from_slice(&digest);
+            Scalar::from_bytes_mod_order(bytes).try_into()
+        }
+    }
+}
+
+/// The non-interactive implementation of the protocol for a single
+/// encryption proof's prover role, with a configurable challenge scheme.
+///
+/// # Inputs
+/// `prover` Any prover that implements the `AssetProofProver` trait.
+/// `scheme` The scheme used to derive the challenge.
+/// `rng`    An RNG.
+///
+/// # Outputs
+/// An initial message and a final response as a tuple on success, or failure on an error.
+pub fn single_property_prover_with_scheme<
+    T: RngCore + CryptoRng,
+    ProverAwaitingChallenge: AssetProofProverAwaitingChallenge,
+>(
+    prover_ac: ProverAwaitingChallenge,
+    scheme: ChallengeScheme,
+    rng: &mut T,
+) -> Fallible<
+    ZKProofResponse<
+        ProverAwaitingChallenge::ZKInitialMessage,
+        ProverAwaitingChallenge::ZKFinalResponse,
+    >,
+>
+where
+    ProverAwaitingChallenge::ZKInitialMessage: Encode,
+{
+    let transcript = Transcript::new(ENCRYPTION_PROOFS_LABEL);
+
+    let mut transcript_rng = prover_ac.create_transcript_rng(rng, &transcript);
+    let (prover, initial_message) = prover_ac.generate_initial_message(&mut transcript_rng);
+
+    let challenge = scheme_challenge(scheme, &initial_message)?;
+    let final_response = prover.apply_challenge(&challenge);
+
+    Ok((initial_message, final_response))
+}
+
+/// The non-interactive implementation of the protocol for a single
+/// encryption proof's verifier role, with a configurable challenge scheme.
+/// The `scheme` must be the one that the prover used.
+///
+/// # Inputs
+/// `verifier` Any verifier that implements the `AssetProofVerifier` trait.
+/// `proof`    Prover's initial message and final response.
+/// `scheme`   The scheme used to derive the challenge.
+///
+/// # Outputs
+/// Ok on success, or failure on error.
+pub fn single_property_verifier_with_scheme<Verifier: AssetProofVerifier>(
+    verifier: &Verifier,
+    proof: ZKProofResponse<Verifier::ZKInitialMessage, Verifier::ZKFinalResponse>,
+    scheme: ChallengeScheme,
+) -> Fallible<()>
+where
+    Verifier::ZKInitialMessage: Encode,
+{
+    let (initial_message, final_response) = proof;
+
+    let challenge = scheme_challenge(scheme, &initial_message)?;
+    verifier.verify(&challenge, &initial_message, &final_response)
+}
+
+// ------------------------------------------------------------------------
+// Interactive Zero Knowledge Proofs API
+// ------------------------------------------------------------------------
+
+/// The first step of the interactive implementation of the protocol for a single
+/// encryption proof's prover role. The prover sends the initial message to the verifier
+/// and keeps the returned prover until the verifier sends back a challenge.
+///
+/// # Inputs
+/// `prover_ac` Any prover that implements the `AssetProofProverAwaitingChallenge` trait.
+/// `rng`       An RNG.
+///
+/// # Outputs
+/// The prover awaiting the challenge and the initial message.
+pub fn interactive_prove_step1<
+    T: RngCore + CryptoRng,
+    ProverAwaitingChallenge: AssetProofProverAwaitingChallenge,
+>(
+    prover_ac: ProverAwaitingChallenge,
+    rng: &mut T,
+) -> (
+    ProverAwaitingChallenge::ZKProver,
+    ProverAwaitingChallenge::ZKInitialMessage,
+) {
+    let transcript = Transcript::new(ENCRYPTION_PROOFS_LABEL);
+
+    let mut transcript_rng = prover_ac.create_transcript_rng(rng, &transcript);
+    prover_ac.generate_initial_message(&mut transcript_rng)
+}
+
+/// The second step of the interactive implementation of the protocol for a single
+/// encryption proof's prover role. The `challenge` is chosen by the verifier, e.g.,
+/// using `ZKPChallenge::try_from` on a random scalar, after it has received the initial
+/// message.
+///
+/// # Inputs
+/// `prover`    The prover returned by `interactive_prove_step1`.
+/// `challenge` The challenge chosen by the verifier.
+///
+/// # Outputs
+/// The final response.
+pub fn interactive_prove_step2<ZKFinalResponse, Prover: AssetProofProver<ZKFinalResponse>>(
+    prover: &Prover,
+    challenge: &ZKPChallenge,
+) -> ZKFinalResponse {
+    prover.apply_challenge(challenge)
+}
+
+/// The interactive implementation of the protocol for a single encryption proof's
+/// verifier role. Unlike `single_property_verifier`, the challenge is not derived from
+/// the initial message, but is the one that the verifier sent to the prover.
+///
+/// # Inputs
+/// `verifier`        Any verifier that implements the `AssetProofVerifier` trait.
+/// `challenge`       The challenge that the verifier sent to the prover.
+/// `initial_message` Prover's initial message.
+/// `final_response`  Prover's final response.
+///
+/// # Outputs
+/// Ok on success, or failure on error.
+pub fn interactive_verify<Verifier: AssetProofVerifier>(
+    verifier: &Verifier,
+    challenge: &ZKPChallenge,
+    initial_message: &Verifier::ZKInitialMessage,
+    final_response: &Verifier::ZKFinalResponse,
+) -> Fallible<()> {
+    verifier.verify(challenge, initial_message, final_response)
+}
+
+// ------------------------------------------------------------------------
+// Test Vectors
+// ------------------------------------------------------------------------
+
+/// The intermediate values of a non-interactive proof, for producing the test vectors of
+/// compatible verifiers in other languages.
+#[cfg(feature = "test-vectors")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProofTrace {
+    /// The SCALE encoding of the prover's initial message.
+    pub initial_message: Vec<u8>,
+    /// The challenge derived from the initial message.
+    pub challenge: Scalar,
+    /// The SCALE encoding of the prover's final response.
+    pub final_response: Vec<u8>,
+}
+
+/// Runs the same steps as `single_property_prover`, e.g., for the correctness or the
+/// wellformedness proofs, and returns the intermediate values of the proof. Use a seeded
+/// `rng` to reproduce the same trace.
+///
+/// # Inputs
+/// `prover_ac` Any prover that implements the `AssetProofProverAwaitingChallenge` trait.
+/// `rng`       An RNG.
+///
+/// # Outputs
+/// The proof trace on success, or failure on an error.
+#[cfg(feature = "test-vectors")]
+pub fn debug_trace<
+    T: RngCore + CryptoRng,
+    ProverAwaitingChallenge: AssetProofProverAwaitingChallenge,
+>(
+    prover_ac: ProverAwaitingChallenge,
+    rng: &mut T,
+) -> Fallible<ProofTrace>
+where
+    ProverAwaitingChallenge::ZKInitialMessage: Encode,
+    ProverAwaitingChallenge::ZKFinalResponse: Encode,
+{
+    let (initial_message, final_response, challenge) =
+        prove_with_transcript(prover_ac, Transcript::new(ENCRYPTION_PROOFS_LABEL), rng)?;
+
+    Ok(ProofTrace {
+        initial_message: initial_message.encode(),
+        challenge: *challenge.x(),
+        final_response: final_response.encode(),
+    })
 }
 
 // ------------------------------------------------------------------------
@@ -198,7 +696,7 @@ mod tests {
     use super::*;
     use crate::asset_proofs::{
         correctness_proof::{
-            CorrectnessFinalResponse, CorrectnessInitialMessage,
+            CorrectnessFinalResponse, CorrectnessInitialMessage, CorrectnessProof,
             CorrectnessProverAwaitingChallenge, CorrectnessVerifier,
         },
         errors::ErrorKind,
@@ -304,6 +802,95 @@ mod tests {
         );
     }
 
+    #[test]
+    #[wasm_bindgen_test]
+    fn nizkp_proofs_with_context() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let gens = PedersenGens::default();
+
+        let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key = secret_key.get_public_key();
+        let (w, cipher) = pub_key.encrypt_value(42u32.into(), &mut rng);
+
+        let (prover, verifier) = create_correctness_proof_objects_helper(w, pub_key, cipher, &gens);
+        let proof = single_property_prover_with_context(prover, b"context", &mut rng).unwrap();
+
+        assert!(single_property_verifier_with_context(&verifier, proof, b"context").is_ok());
+
+        // The proof does not verify with another context, or without one.
+        assert_err!(
+            single_property_verifier_with_context(&verifier, proof, b"other context"),
+            ErrorKind::CorrectnessFinalResponseVerificationError { check: 1 }
+        );
+        assert_err!(
+            single_property_verifier(&verifier, proof),
+            ErrorKind::CorrectnessFinalResponseVerificationError { check: 1 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn nizkp_proofs_by_reference() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let gens = PedersenGens::default();
+
+        let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key = secret_key.get_public_key();
+        let (w, cipher) = pub_key.encrypt_value(42u32.into(), &mut rng);
+
+        let (prover, verifier) = create_correctness_proof_objects_helper(w, pub_key, cipher, &gens);
+        let (initial_message, final_response) = single_property_prover(prover, &mut rng).unwrap();
+        let bad_initial_message = CorrectnessInitialMessage::default();
+
+        assert!(single_property_verifier_ref(&verifier, &initial_message, &final_response).is_ok());
+        assert!(single_property_verifier(&verifier, (initial_message, final_response)).is_ok());
+
+        let by_ref_error =
+            single_property_verifier_ref(&verifier, &bad_initial_message, &final_response)
+                .expect_err("Error expected");
+        let by_value_error =
+            single_property_verifier(&verifier, (bad_initial_message, final_response))
+                .expect_err("Error expected");
+        assert_eq!(by_ref_error.kind(), by_value_error.kind());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn nizkp_proofs_debug() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let gens = PedersenGens::default();
+
+        let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key = secret_key.get_public_key();
+        let (w, cipher) = pub_key.encrypt_value(42u32.into(), &mut rng);
+
+        let (prover, verifier) = create_correctness_proof_objects_helper(w, pub_key, cipher, &gens);
+        let (initial_message, final_response) = single_property_prover(prover, &mut rng).unwrap();
+
+        // Recompute the challenge that the prover derived.
+        let mut transcript = Transcript::new(ENCRYPTION_PROOFS_LABEL);
+        initial_message.update_transcript(&mut transcript).unwrap();
+        let prover_challenge = transcript
+            .scalar_challenge(ENCRYPTION_PROOFS_CHALLENGE_LABEL)
+            .unwrap();
+
+        let (challenge, result) =
+            single_property_verifier_debug(&verifier, &initial_message, &final_response).unwrap();
+        assert_eq!(challenge, prover_challenge);
+        assert!(result.is_ok());
+
+        // The challenge is returned even if the verification fails.
+        let bad_final_response = CorrectnessFinalResponse::from(Scalar::one());
+        let (challenge, result) =
+            single_property_verifier_debug(&verifier, &initial_message, &bad_final_response)
+                .unwrap();
+        assert_eq!(challenge, prover_challenge);
+        assert_err!(
+            result,
+            ErrorKind::CorrectnessFinalResponseVerificationError { check: 1 }
+        );
+    }
+
     #[test]
     #[wasm_bindgen_test]
     fn batched_proofs() {
@@ -354,4 +941,186 @@ mod tests {
             .verify(&bad_challenge, &initial_message1, &final_response1)
             .is_err());
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn interactive_proofs() {
+        let mut rng = StdRng::from_seed(SEED_2);
+        let gens = PedersenGens::default();
+
+        let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key = secret_key.get_public_key();
+        let (w, cipher) = pub_key.encrypt_value(42u32.into(), &mut rng);
+        let (prover_ac, verifier) =
+            create_correctness_proof_objects_helper(w, pub_key, cipher, &gens);
+
+        // Prover sends the initial message.
+        let (prover, initial_message) = interactive_prove_step1(prover_ac, &mut rng);
+        // Verifier chooses the challenge.
+        let challenge = ZKPChallenge::try_from(Scalar::random(&mut rng)).unwrap();
+        // Prover answers the challenge.
+        let final_response = interactive_prove_step2(&prover, &challenge);
+
+        assert!(
+            interactive_verify(&verifier, &challenge, &initial_message, &final_response).is_ok()
+        );
+
+        // The response only holds for the challenge that the verifier chose.
+        let other_challenge = ZKPChallenge::try_from(Scalar::random(&mut rng)).unwrap();
+        assert!(interactive_verify(
+            &verifier,
+            &other_challenge,
+            &initial_message,
+            &final_response
+        )
+        .is_err());
+        // Nor does it hold for the challenge of the non-interactive protocol.
+        assert!(single_property_verifier(&verifier, (initial_message, final_response)).is_err());
+    }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    #[wasm_bindgen_test]
+    fn proof_traces() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let gens = PedersenGens::default();
+
+        let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key = secret_key.get_public_key();
+        let (w, cipher) = pub_key.encrypt_value(42u32.into(), &mut rng);
+
+        // The correctness proof.
+        let (prover, verifier) =
+            create_correctness_proof_objects_helper(w.clone(), pub_key, cipher, &gens);
+        let trace = debug_trace(prover, &mut StdRng::from_seed(SEED_2)).unwrap();
+        let initial_message: CorrectnessInitialMessage =
+            decode_proof(&trace.initial_message).unwrap();
+        let final_response: CorrectnessFinalResponse = decode_proof(&trace.final_response).unwrap();
+        let challenge = ZKPChallenge::try_from(trace.challenge).unwrap();
+        assert!(verifier
+            .verify(&challenge, &initial_message, &final_response)
+            .is_ok());
+        // The trace matches the proof of the same randomness.
+        let (prover, _) =
+            create_correctness_proof_objects_helper(w.clone(), pub_key, cipher, &gens);
+        let proof = single_property_prover(prover, &mut StdRng::from_seed(SEED_2)).unwrap();
+        assert_eq!(proof, (initial_message, final_response));
+
+        // The wellformedness proof.
+        let (prover, verifier) =
+            create_wellformedness_proof_objects_helper(w, pub_key, cipher, &gens);
+        let trace = debug_trace(prover, &mut rng).unwrap();
+        let challenge = ZKPChallenge::try_from(trace.challenge).unwrap();
+        assert!(verifier
+            .verify(
+                &challenge,
+                &decode_proof(&trace.initial_message).unwrap(),
+                &decode_proof(&trace.final_response).unwrap(),
+            )
+            .is_ok());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn challenge_schemes() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let gens = PedersenGens::default();
+
+        let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key = secret_key.get_public_key();
+        let (w, cipher) = pub_key.encrypt_value(42u32.into(), &mut rng);
+
+        for scheme in &[ChallengeScheme::Merlin, ChallengeScheme::Keccak256] {
+            let (prover, verifier) =
+                create_correctness_proof_objects_helper(w.clone(), pub_key, cipher, &gens);
+            let proof = single_property_prover_with_scheme(prover, *scheme, &mut rng).unwrap();
+
+            // Positive test: prover and verifier use the same scheme.
+            assert!(single_property_verifier_with_scheme(&verifier, proof, *scheme).is_ok());
+
+            // Negative test: prover and verifier use different schemes.
+            let other_scheme = match scheme {
+                ChallengeScheme::Merlin => ChallengeScheme::Keccak256,
+                ChallengeScheme::Keccak256 => ChallengeScheme::Merlin,
+            };
+            assert_err!(
+                single_property_verifier_with_scheme(&verifier, proof, other_scheme),
+                ErrorKind::CorrectnessFinalResponseVerificationError { check: 1 }
+            );
+        }
+
+        // The Merlin scheme is compatible with the default API.
+        let (prover, verifier) = create_correctness_proof_objects_helper(w, pub_key, cipher, &gens);
+        let proof =
+            single_property_prover_with_scheme(prover, ChallengeScheme::Merlin, &mut rng).unwrap();
+        assert!(single_property_verifier(&verifier, proof).is_ok());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn malformed_vs_invalid_proofs() {
+        let gens = PedersenGens::default();
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key = elg_secret.get_public_key();
+        let (w, cipher) = pub_key.encrypt_value(42u32.into(), &mut rng);
+
+        let (prover, verifier) = create_correctness_proof_objects_helper(w, pub_key, cipher, &gens);
+        let proof = single_property_prover(prover, &mut rng).unwrap();
+        let bytes = proof.encode();
+
+        // Positive test.
+        let decoded: CorrectnessProof = decode_proof(&bytes).unwrap();
+        assert!(single_property_verifier(&verifier, decoded).is_ok());
+
+        // A truncated proof is malformed.
+        let error = decode_proof::<CorrectnessProof>(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(error.is_malformed());
+        assert!(!error.is_invalid_proof());
+
+        // So is a proof with trailing bytes.
+        let mut long_bytes = bytes.clone();
+        long_bytes.push(0);
+        assert!(decode_proof::<CorrectnessProof>(&long_bytes)
+            .unwrap_err()
+            .is_malformed());
+
+        // A tampered, but well-formed, proof is invalid.
+        let tampered = (proof.0, CorrectnessFinalResponse::from(Scalar::one()));
+        let tampered: CorrectnessProof = decode_proof(&tampered.encode()).unwrap();
+        let error = single_property_verifier(&verifier, tampered).unwrap_err();
+        assert!(error.is_invalid_proof());
+        assert!(!error.is_malformed());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn versioned_proofs() {
+        let gens = PedersenGens::default();
+        let mut rng = StdRng::from_seed(SEED_1);
+        let pub_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let (w, cipher) = pub_key.encrypt_value(42u32.into(), &mut rng);
+
+        let (prover, verifier) = create_correctness_proof_objects_helper(w, pub_key, cipher, &gens);
+        let proof = single_property_prover(prover, &mut rng).unwrap();
+        let bytes = encode_versioned_proof(&proof);
+        assert_eq!(bytes[0], ProofVersion::V1 as u8);
+        assert_eq!(&bytes[1..], &proof.encode()[..]);
+
+        // A v1 proof verifies with the v1 transcript.
+        assert!(single_property_verifier_versioned(&verifier, &bytes).is_ok());
+
+        // An unknown version is rejected.
+        let mut unknown_version = bytes.clone();
+        unknown_version[0] = 99;
+        assert_err!(
+            single_property_verifier_versioned(&verifier, &unknown_version),
+            ErrorKind::UnsupportedProofVersion { version: 99 }
+        );
+
+        // A proof without a version tag is malformed.
+        assert!(single_property_verifier_versioned(&verifier, &[])
+            .unwrap_err()
+            .is_malformed());
+    }
 }
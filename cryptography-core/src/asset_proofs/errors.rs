@@ -15,6 +15,37 @@ impl Error {
     pub fn kind(&self) -> &ErrorKind {
         self.inner.get_context()
     }
+
+    /// The category of the error. See `ErrorCategory`.
+    #[inline]
+    pub fn category(&self) -> ErrorCategory {
+        self.kind().category()
+    }
+
+    /// Returns true if the error was caused by a proof that could not be parsed.
+    #[inline]
+    pub fn is_malformed(&self) -> bool {
+        self.category() == ErrorCategory::MalformedProof
+    }
+
+    /// Returns true if the error was caused by a well-formed proof that failed verification.
+    #[inline]
+    pub fn is_invalid_proof(&self) -> bool {
+        self.category() == ErrorCategory::InvalidProof
+    }
+}
+
+/// Distinguishes the proofs that cannot be parsed from the proofs that are well-formed,
+/// but do not pass the cryptographic checks. The former indicates a misbehaving submitter,
+/// while the latter only means that the transaction must be rejected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorCategory {
+    /// The proof could not be deserialized, e.g., it is truncated or has trailing bytes.
+    MalformedProof,
+    /// The proof is well-formed, but at least one of its checks failed.
+    InvalidProof,
+    /// Any other error.
+    Other,
 }
 
 impl From<ErrorKind> for Error {
@@ -125,6 +156,10 @@ pub enum ErrorKind {
     #[fail(display = "Invalid asset type ")]
     MembershipProofInvalidAssetError,
 
+    /// The window of elements is not under the Merkle root.
+    #[fail(display = "The Merkle path does not lead to the committed root.")]
+    MerklePathVerificationError,
+
     /// Elements set is empty.
     #[fail(display = "The elements set passed to the membership proof cannot be empty.")]
     EmptyElementsSet,
@@ -159,6 +194,11 @@ pub enum ErrorKind {
     #[fail(display = "The account does not match the account on the transaction")]
     AccountIdMismatch,
 
+    /// The public key of the mediator's key pair is not the public key of its secret key,
+    /// e.g., because the keys of two accounts were mixed up.
+    #[fail(display = "The mediator's public key does not match its secret key.")]
+    MisconfiguredMediatorKeys,
+
     /// The mercat transaction id does not match the one supplied previously.
     #[fail(display = "The mercat transaction id does not match the one supplied previously.")]
     TransactionIdMismatch,
@@ -181,6 +221,188 @@ pub enum ErrorKind {
     /// The auditors' payload does not match the compliance rules.
     #[fail(display = "The auditors' payload does not match the compliance rules.")]
     AuditorPayloadError,
+
+    /// The asset id of the account is not among the registered asset ids.
+    #[fail(display = "The asset id is not in the list of valid asset ids.")]
+    UnknownAssetType,
+
+    /// The balance is not enough to cover the debited amount.
+    #[fail(display = "The balance is not enough to cover the debited amount.")]
+    InsufficientBalance,
+
+    /// The amount is larger than the maximum supported balance.
+    #[fail(
+        display = "The amount {} is larger than the maximum supported balance {}.",
+        amount, max_balance
+    )]
+    BalanceOverflow { amount: u64, max_balance: u64 },
+
+    /// The value is not a multiple of the public modulus, or the modulus is zero.
+    #[fail(display = "The value is not a multiple of the modulus {}.", modulus)]
+    NotAMultipleOfModulus { modulus: u64 },
+
+    /// The serialized proof could not be deserialized.
+    #[fail(display = "The proof is malformed and cannot be deserialized.")]
+    MalformedProof,
+
+    /// The keystore could not be decrypted, either because the password is wrong or
+    /// because the keystore is corrupted.
+    #[fail(display = "Failed to decrypt the keystore.")]
+    KeystoreDecryptFailed,
+
+    /// The serialized proof is tagged with a version that this verifier does not support.
+    #[fail(display = "Unsupported proof version {}.", version)]
+    UnsupportedProofVersion { version: u8 },
+
+    /// The range of a decryption table must be non-zero and at most `2^32`.
+    #[fail(display = "Invalid decryption range {}.", range)]
+    InvalidDecryptionRange { range: u64 },
+
+    /// The bytes of a ciphertext are not two canonical encodings of Ristretto points.
+    #[fail(display = "The ciphertext encoding is invalid.")]
+    InvalidCiphertextEncoding,
+
+    /// A blinding factor was used more than once in the same session.
+    #[fail(display = "A blinding factor was reused.")]
+    ReusedBlinding,
+
+    /// The commitment of a range proof is not the `y` term of the ciphertext it is about.
+    #[fail(display = "The range proof commitment does not match the ciphertext.")]
+    RangeCommitmentMismatch,
+
+    /// The metadata of an asset issuance is longer than the maximum length.
+    #[fail(
+        display = "The metadata is {} bytes long, but at most {} bytes are allowed.",
+        length, max_length
+    )]
+    MetadataTooLarge { length: usize, max_length: usize },
+
+    /// The portable account blob could not be deserialized.
+    #[fail(display = "The portable account is malformed and cannot be deserialized.")]
+    MalformedPortableAccount,
+
+    /// The issuance would take the total issued in the current window over its cap.
+    #[fail(
+        display = "Issuing {} would take the total issued in this window to {}, over its cap of {}.",
+        amount, total, cap
+    )]
+    IssuanceCapExceeded { amount: u64, total: u64, cap: u64 },
+
+    /// The first deposit to an account is below the asset's minimum balance.
+    #[fail(
+        display = "The first deposit of {} is below the minimum balance of {}.",
+        amount, min_balance
+    )]
+    BelowMinimumBalance { amount: u32, min_balance: u32 },
+
+    /// The signed value does not fit in the signed range of the given bitsize.
+    #[fail(
+        display = "The value {} is not in the signed range of {} bits.",
+        value, range
+    )]
+    SignedValueOutOfRange { value: i64, range: u32 },
+
+    /// The serialized transaction could not be deserialized, or its fields are implausible.
+    #[fail(display = "The transaction is malformed.")]
+    MalformedTransaction,
+
+    /// The set of the valid asset ids is empty, so no account can prove its membership.
+    #[fail(display = "There are no valid asset ids.")]
+    NoValidAssetIds,
+
+    /// The sum of the outputs of a transfer does not equal the sender's debit.
+    #[fail(display = "The outputs of the transfer do not add up to the debited amount.")]
+    ValueNotConserved,
+
+    /// The proofs of a split transaction do not match the hash in its metadata.
+    #[fail(display = "The proofs do not match the transaction metadata.")]
+    ProofsHashMismatch,
+
+    /// The batch exceeds the configured `BatchLimits`.
+    #[fail(
+        display = "The batch has {} entries, but at most {} are allowed.",
+        size, max
+    )]
+    BatchTooLarge { size: usize, max: usize },
+
+    /// The window of a Merkle membership proof exceeds the maximum window size.
+    #[fail(
+        display = "The membership window has {} elements, but at most {} are allowed.",
+        size, max
+    )]
+    MembershipWindowTooLarge { size: usize, max: usize },
+
+    /// The public key of the other party of a key agreement is the identity point.
+    #[fail(display = "The public key is the identity point.")]
+    IdentityPublicKey,
+
+    /// A refund that is created by the owner of the account can only lower its balance.
+    #[fail(
+        display = "A refund created by the account owner cannot credit {} to the account.",
+        delta
+    )]
+    PositiveRefund { delta: i64 },
+}
+
+impl ErrorKind {
+    /// The category of the error. See `ErrorCategory`.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ErrorKind::MalformedProof
+            | ErrorKind::MalformedTransaction
+            | ErrorKind::MalformedPortableAccount
+            | ErrorKind::InvalidCiphertextEncoding
+            | ErrorKind::UnsupportedProofVersion { .. }
+            | ErrorKind::ProvingError {
+                source: ProofError::FormatError,
+            } => ErrorCategory::MalformedProof,
+            ErrorKind::VerificationError
+            | ErrorKind::CorrectnessFinalResponseVerificationError { .. }
+            | ErrorKind::R1FinalResponseVerificationError { .. }
+            | ErrorKind::OOONFinalResponseVerificationError { .. }
+            | ErrorKind::WellformednessFinalResponseVerificationError { .. }
+            | ErrorKind::CiphertextRefreshmentFinalResponseVerificationError { .. }
+            | ErrorKind::EncryptingSameValueFinalResponseVerificationError { .. }
+            | ErrorKind::MembershipProofVerificationError { .. }
+            | ErrorKind::MembershipProofInvalidAssetError
+            | ErrorKind::MerklePathVerificationError
+            | ErrorKind::RangeCommitmentMismatch
+            | ErrorKind::ValueNotConserved
+            | ErrorKind::ProofsHashMismatch => ErrorCategory::InvalidProof,
+            ErrorKind::PlainTextRangeError
+            | ErrorKind::CipherTextDecryptionError
+            | ErrorKind::OOONProofIndexOutofRange { .. }
+            | ErrorKind::OOONProofWrongSize
+            | ErrorKind::EmptyElementsSet
+            | ErrorKind::InvalidExponentParameter
+            | ErrorKind::TransactionAmountMismatch { .. }
+            | ErrorKind::InputPubKeyMismatch
+            | ErrorKind::NotEnoughFund { .. }
+            | ErrorKind::AccountIdMismatch
+            | ErrorKind::MisconfiguredMediatorKeys
+            | ErrorKind::TransactionIdMismatch
+            | ErrorKind::SerializationError
+            | ErrorKind::ProvingError { .. }
+            | ErrorKind::TickerIdLengthError { .. }
+            | ErrorKind::AuditorPayloadError
+            | ErrorKind::UnknownAssetType
+            | ErrorKind::InsufficientBalance
+            | ErrorKind::BalanceOverflow { .. }
+            | ErrorKind::NotAMultipleOfModulus { .. }
+            | ErrorKind::KeystoreDecryptFailed
+            | ErrorKind::InvalidDecryptionRange { .. }
+            | ErrorKind::ReusedBlinding
+            | ErrorKind::MetadataTooLarge { .. }
+            | ErrorKind::IssuanceCapExceeded { .. }
+            | ErrorKind::BelowMinimumBalance { .. }
+            | ErrorKind::SignedValueOutOfRange { .. }
+            | ErrorKind::NoValidAssetIds
+            | ErrorKind::BatchTooLarge { .. }
+            | ErrorKind::MembershipWindowTooLarge { .. }
+            | ErrorKind::IdentityPublicKey
+            | ErrorKind::PositiveRefund { .. } => ErrorCategory::Other,
+        }
+    }
 }
 
 pub type Fallible<T, E = Error> = Result<T, E>;
@@ -394,6 +394,111 @@ impl<'a> AssetProofVerifier for MembershipProofVerifier<'a> {
     }
 }
 
+// ------------------------------------------------------------------------
+// Precomputed Generators
+// ------------------------------------------------------------------------
+
+/// The base of the one-out-of-many proofs used by `MembershipGenerators`.
+pub const MEMBERSHIP_GENERATORS_BASE: u32 = 4;
+
+/// Returns the smallest `exp` such that `MEMBERSHIP_GENERATORS_BASE^exp >= set_size`.
+fn membership_exponent(set_size: usize) -> u32 {
+    let base = MEMBERSHIP_GENERATORS_BASE as usize;
+    let mut exp = 1u32;
+    while base.pow(exp) < set_size {
+        exp += 1;
+    }
+    exp
+}
+
+/// Returns the number of Pedersen generators that a membership proof over a set of
+/// `set_size` elements requires, which can be used to pre-size the generators.
+///
+/// The set is padded to the next power of the base `n = MEMBERSHIP_GENERATORS_BASE`,
+/// i.e., to `n^m` elements with `m = max(1, ceil(log_n(set_size)))`. The underlying
+/// one-out-of-many proof commits to an `m x n` bit matrix, therefore it needs
+/// `n * m` generators. An empty set needs no generators.
+pub fn membership_generators_needed(set_size: usize) -> usize {
+    if set_size == 0 {
+        return 0;
+    }
+    MEMBERSHIP_GENERATORS_BASE as usize * membership_exponent(set_size) as usize
+}
+
+/// Precomputed generators for the membership proofs over sets of up to a given size.
+/// Since building the generators is costly, a single instance can be shared by all
+/// the provers and verifiers that use the same elements set.
+///
+/// The generators depend only on the elements set size. They must be rebuilt by calling
+/// `new` when the set grows beyond `capacity()`. Note that proofs generated with one
+/// instance can only be verified with an instance of the same `exp`.
+#[derive(Clone)]
+pub struct MembershipGenerators {
+    generators: OooNProofGenerators,
+    base: u32,
+    exp: u32,
+}
+
+impl MembershipGenerators {
+    /// Builds the generators for sets of up to `set_size` elements.
+    pub fn new(set_size: usize) -> Fallible<Self> {
+        ensure!(set_size != 0, ErrorKind::EmptyElementsSet);
+
+        let base = MEMBERSHIP_GENERATORS_BASE;
+        let exp = membership_exponent(set_size);
+
+        Ok(MembershipGenerators {
+            generators: OooNProofGenerators::new(base, exp),
+            base,
+            exp,
+        })
+    }
+
+    /// The maximum elements set size that these generators support.
+    pub fn capacity(&self) -> usize {
+        (self.base as usize).pow(self.exp)
+    }
+
+    /// Returns true if these generators can be used for an elements set of size `set_size`.
+    pub fn supports(&self, set_size: usize) -> bool {
+        set_size != 0 && set_size <= self.capacity()
+    }
+
+    /// Creates a membership prover that uses these generators.
+    pub fn prover<'a>(
+        &'a self,
+        secret_element: Scalar,
+        random: Scalar,
+        elements_set: &'a [Scalar],
+    ) -> Fallible<MembershipProverAwaitingChallenge<'a>> {
+        ensure!(
+            self.supports(elements_set.len()),
+            ErrorKind::InvalidExponentParameter
+        );
+        MembershipProverAwaitingChallenge::new(
+            secret_element,
+            random,
+            &self.generators,
+            elements_set,
+            self.base,
+            self.exp,
+        )
+    }
+
+    /// Creates a membership verifier that uses these generators.
+    pub fn verifier<'a>(
+        &'a self,
+        secret_element_com: RistrettoPoint,
+        elements_set: &'a [Scalar],
+    ) -> MembershipProofVerifier<'a> {
+        MembershipProofVerifier {
+            secret_element_com,
+            elements_set,
+            generators: &self.generators,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -613,4 +718,60 @@ mod tests {
         let recovered_final_response = <MembershipProofFinalResponse>::decode(&mut input).unwrap();
         assert_eq!(recovered_final_response, final_response0);
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_membership_generators() {
+        let mut rng = StdRng::from_seed(SEED_1);
+
+        assert!(MembershipGenerators::new(0).is_err());
+        assert_eq!(MembershipGenerators::new(1).unwrap().capacity(), 4);
+        assert_eq!(MembershipGenerators::new(16).unwrap().capacity(), 16);
+        assert_eq!(MembershipGenerators::new(17).unwrap().capacity(), 64);
+
+        let elements_set: Vec<Scalar> = (0..50u32).map(Scalar::from).collect();
+        let generators = MembershipGenerators::new(elements_set.len()).unwrap();
+        assert!(generators.supports(elements_set.len()));
+        assert!(!generators.supports(65));
+
+        // The same generators are shared by multiple provers and verifiers.
+        for secret in &[3u32, 42] {
+            let secret_element = Scalar::from(*secret);
+            let blinding = Scalar::random(&mut rng);
+            let secret_element_com = generators
+                .generators
+                .com_gens
+                .commit(secret_element, blinding);
+
+            let prover = generators
+                .prover(secret_element, blinding, &elements_set)
+                .unwrap();
+            let proof = single_property_prover(prover, &mut rng).unwrap();
+
+            let verifier = generators.verifier(secret_element_com, &elements_set);
+            assert!(single_property_verifier(&verifier, proof).is_ok());
+        }
+
+        // The generators must be rebuilt for larger sets.
+        let large_set: Vec<Scalar> = (0..65u32).map(Scalar::from).collect();
+        assert!(generators
+            .prover(Scalar::from(3u32), Scalar::random(&mut rng), &large_set)
+            .is_err());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_membership_generators_needed() {
+        assert_eq!(membership_generators_needed(0), 0);
+
+        for set_size in &[1usize, 4, 5, 16, 17, 64, 65, 300] {
+            let generators = MembershipGenerators::new(*set_size).unwrap();
+            assert_eq!(
+                membership_generators_needed(*set_size),
+                generators.generators.h_vec.len()
+            );
+        }
+        assert_eq!(membership_generators_needed(4), 4);
+        assert_eq!(membership_generators_needed(17), 12);
+    }
 }
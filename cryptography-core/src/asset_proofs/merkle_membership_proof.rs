@@ -0,0 +1,424 @@
+//! Windowed membership proofs for large sets of elements, e.g., a registry of tens of
+//! thousands of asset ids, for which a one-out-of-many membership proof over the whole
+//! set needs too much memory.
+//!
+//! The set is split into windows of `window_size` elements, and the windows are the leaves
+//! of a Merkle tree. A proof reveals the window of the secret element and its Merkle path to
+//! the committed root, and proves in zero-knowledge that the committed secret is one of the
+//! elements of that window. Therefore, the prover and the verifier only need the generators
+//! for a single window and the root of the tree, instead of the whole set.
+//!
+//! Note that this trades some privacy for scalability: the proof hides the secret element
+//! only among the `window_size` elements of its window.
+
+use crate::{
+    asset_proofs::{
+        encryption_proofs::{single_property_prover, single_property_verifier},
+        errors::{ErrorKind, Fallible},
+        membership_proof::{MembershipGenerators, MembershipProof},
+    },
+    codec_wrapper::{ScalarDecoder, ScalarEncoder},
+};
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha3::{digest::FixedOutput, Digest, Sha3_256};
+
+use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use sp_std::prelude::*;
+
+/// The domain separators of the leaves and the inner nodes of the tree.
+const MERKLE_LEAF_PREFIX: &[u8] = b"PolymathMerkleMembershipLeaf";
+const MERKLE_NODE_PREFIX: &[u8] = b"PolymathMerkleMembershipNode";
+
+/// The default maximum number of elements in the window of a proof that the verifier
+/// accepts.
+pub const MAX_MEMBERSHIP_WINDOW: usize = 1024;
+
+/// The hash of a leaf or an inner node of the Merkle tree.
+pub type MerkleHash = [u8; 32];
+
+/// Hashes a window of elements into a leaf of the Merkle tree.
+pub fn merkle_leaf_hash(window: &[Scalar]) -> MerkleHash {
+    let mut hasher = Sha3_256::default().chain(MERKLE_LEAF_PREFIX);
+    for element in window {
+        hasher = hasher.chain(element.as_bytes());
+    }
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&hasher.fixed_result());
+    hash
+}
+
+fn merkle_node_hash(left: &MerkleHash, right: &MerkleHash) -> MerkleHash {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(
+        &Sha3_256::default()
+            .chain(MERKLE_NODE_PREFIX)
+            .chain(left)
+            .chain(right)
+            .fixed_result(),
+    );
+    hash
+}
+
+/// The Merkle path of a leaf, i.e., its position and the hashes of its siblings from the
+/// leaf level up to the root.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MerklePath {
+    pub leaf_index: u32,
+    pub siblings: Vec<MerkleHash>,
+}
+
+impl MerklePath {
+    /// Computes the root of the tree from the hash of the leaf.
+    pub fn root(&self, leaf_hash: MerkleHash) -> MerkleHash {
+        let mut index = self.leaf_index;
+        let mut hash = leaf_hash;
+        for sibling in &self.siblings {
+            hash = if index % 2 == 0 {
+                merkle_node_hash(&hash, sibling)
+            } else {
+                merkle_node_hash(sibling, &hash)
+            };
+            index /= 2;
+        }
+        hash
+    }
+
+    /// Returns true if `window` is under `root` at the position of this path.
+    pub fn verify(&self, window: &[Scalar], root: &MerkleHash) -> bool {
+        let index_fits = self.siblings.len() >= 32 || self.leaf_index >> self.siblings.len() == 0;
+        index_fits && self.root(merkle_leaf_hash(window)) == *root
+    }
+}
+
+/// A Merkle tree over the windows of a set of elements. The number of leaves is padded to
+/// a power of two with the hash of an empty window.
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    windows: Vec<Vec<Scalar>>,
+    /// All the levels of the tree, from the leaves up to the root.
+    levels: Vec<Vec<MerkleHash>>,
+}
+
+impl MerkleTree {
+    /// Splits `elements` into windows of `window_size` elements, where the last window can
+    /// be smaller, and builds the tree over them.
+    pub fn new(elements: &[Scalar], window_size: usize) -> Fallible<Self> {
+        ensure!(!elements.is_empty(), ErrorKind::EmptyElementsSet);
+        ensure!(window_size != 0, ErrorKind::InvalidExponentParameter);
+
+        let windows: Vec<Vec<Scalar>> = elements
+            .chunks(window_size)
+            .map(|window| window.to_vec())
+            .collect();
+        let mut leaves: Vec<MerkleHash> = windows
+            .iter()
+            .map(|window| merkle_leaf_hash(window))
+            .collect();
+        leaves.resize(windows.len().next_power_of_two(), merkle_leaf_hash(&[]));
+
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| merkle_node_hash(&pair[0], &pair[1]))
+                .collect();
+            levels.push(next);
+        }
+
+        Ok(MerkleTree { windows, levels })
+    }
+
+    /// The root of the tree, which commits to the whole set.
+    pub fn root(&self) -> MerkleHash {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// The number of windows, i.e., the number of non-padding leaves.
+    pub fn windows_len(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Returns the window that contains `element`, along with its Merkle path.
+    pub fn window_of(&self, element: &Scalar) -> Fallible<(&[Scalar], MerklePath)> {
+        let index = self
+            .windows
+            .iter()
+            .position(|window| window.contains(element))
+            .ok_or(ErrorKind::MembershipProofInvalidAssetError)?;
+        Ok((&self.windows[index], self.path(index)))
+    }
+
+    /// Returns the Merkle path of the leaf at `leaf_index`.
+    fn path(&self, leaf_index: usize) -> MerklePath {
+        let mut index = leaf_index;
+        let siblings = self.levels[..self.levels.len() - 1]
+            .iter()
+            .map(|level| {
+                let sibling = level[index ^ 1];
+                index /= 2;
+                sibling
+            })
+            .collect();
+        MerklePath {
+            leaf_index: leaf_index as u32,
+            siblings,
+        }
+    }
+}
+
+/// Proves that a committed secret element belongs to a window of elements that is under a
+/// committed Merkle root.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MerkleMembershipProof {
+    /// The window of elements that contains the secret element.
+    pub window: Vec<Scalar>,
+    /// The path of the window to the root of the tree.
+    pub path: MerklePath,
+    /// Proves that the secret element is one of the elements of the window.
+    pub membership_proof: MembershipProof,
+}
+
+impl Encode for MerkleMembershipProof {
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        let window: Vec<ScalarEncoder> = self.window.iter().map(ScalarEncoder).collect();
+        window.encode_to(dest);
+        self.path.encode_to(dest);
+        self.membership_proof.encode_to(dest);
+    }
+}
+
+impl Decode for MerkleMembershipProof {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let window = <Vec<ScalarDecoder>>::decode(input)?
+            .into_iter()
+            .map(|element| element.0)
+            .collect();
+        let path = <MerklePath>::decode(input)?;
+        let membership_proof = <MembershipProof>::decode(input)?;
+
+        Ok(MerkleMembershipProof {
+            window,
+            path,
+            membership_proof,
+        })
+    }
+}
+
+/// Creates a proof that the secret element, committed with `random` as the blinding factor,
+/// is in `window` (the leaf), and that `window` is under `root` with the given `path`.
+pub fn prove_membership_merkle<R: RngCore + CryptoRng>(
+    secret_element: Scalar,
+    random: Scalar,
+    window: &[Scalar],
+    path: &MerklePath,
+    root: &MerkleHash,
+    rng: &mut R,
+) -> Fallible<MerkleMembershipProof> {
+    ensure!(
+        path.verify(window, root),
+        ErrorKind::MerklePathVerificationError
+    );
+
+    let generators = MembershipGenerators::new(window.len())?;
+    let membership_proof =
+        single_property_prover(generators.prover(secret_element, random, window)?, rng)?;
+
+    Ok(MerkleMembershipProof {
+        window: window.to_vec(),
+        path: path.clone(),
+        membership_proof,
+    })
+}
+
+/// Verifies that the committed secret element, `secret_element_com`, is in a window that is
+/// under the committed `root`.
+pub fn verify_membership_merkle(
+    secret_element_com: RistrettoPoint,
+    proof: &MerkleMembershipProof,
+    root: &MerkleHash,
+) -> Fallible<()> {
+    verify_membership_merkle_with_max_window(secret_element_com, proof, root, MAX_MEMBERSHIP_WINDOW)
+}
+
+/// Same as `verify_membership_merkle`, but a proof whose window has more than `max_window`
+/// elements is rejected with `ErrorKind::MembershipWindowTooLarge` before its generators are
+/// allocated.
+pub fn verify_membership_merkle_with_max_window(
+    secret_element_com: RistrettoPoint,
+    proof: &MerkleMembershipProof,
+    root: &MerkleHash,
+    max_window: usize,
+) -> Fallible<()> {
+    ensure!(
+        proof.window.len() <= max_window,
+        ErrorKind::MembershipWindowTooLarge {
+            size: proof.window.len(),
+            max: max_window
+        }
+    );
+    ensure!(
+        proof.path.verify(&proof.window, root),
+        ErrorKind::MerklePathVerificationError
+    );
+
+    let generators = MembershipGenerators::new(proof.window.len())?;
+    single_property_verifier(
+        &generators.verifier(secret_element_com, &proof.window),
+        proof.membership_proof.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use bulletproofs::PedersenGens;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    const SEED_1: [u8; 32] = [42u8; 32];
+    const WINDOW_SIZE: usize = 4;
+
+    /// Builds a tree with 16 leaves over 64 elements.
+    fn tree_with_16_leaves() -> (Vec<Scalar>, MerkleTree) {
+        let elements: Vec<Scalar> = (0..64u32).map(|m| Scalar::from(2 * m)).collect();
+        let tree = MerkleTree::new(&elements, WINDOW_SIZE).unwrap();
+        assert_eq!(tree.windows_len(), 16);
+        (elements, tree)
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_merkle_membership_valid_leaf() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let (_, tree) = tree_with_16_leaves();
+        let root = tree.root();
+
+        let secret_element = Scalar::from(42u32);
+        let blinding = Scalar::random(&mut rng);
+        let secret_element_com = PedersenGens::default().commit(secret_element, blinding);
+
+        let (window, path) = tree.window_of(&secret_element).unwrap();
+        assert_eq!(path.siblings.len(), 4);
+        let proof =
+            prove_membership_merkle(secret_element, blinding, window, &path, &root, &mut rng)
+                .unwrap();
+        assert!(verify_membership_merkle(secret_element_com, &proof, &root).is_ok());
+
+        // The proof survives an encode and decode round trip.
+        let decoded = MerkleMembershipProof::decode(&mut &proof.encode()[..]).unwrap();
+        assert!(verify_membership_merkle(secret_element_com, &decoded, &root).is_ok());
+
+        // The proof does not verify for another committed element or another root.
+        let other_com = PedersenGens::default().commit(Scalar::from(40u32), blinding);
+        assert!(verify_membership_merkle(other_com, &proof, &root).is_err());
+        let other_tree = MerkleTree::new(&[Scalar::from(42u32)], WINDOW_SIZE).unwrap();
+        assert_err!(
+            verify_membership_merkle(secret_element_com, &proof, &other_tree.root()),
+            ErrorKind::MerklePathVerificationError
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_merkle_membership_invalid_leaf() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let (_, tree) = tree_with_16_leaves();
+        let root = tree.root();
+        let blinding = Scalar::random(&mut rng);
+
+        // An element that is not in the set has no window.
+        let secret_element = Scalar::from(43u32);
+        assert_err!(
+            tree.window_of(&secret_element),
+            ErrorKind::MembershipProofInvalidAssetError
+        );
+
+        // A forged leaf that contains the element is not under the root.
+        let (window, path) = tree.window_of(&Scalar::from(42u32)).unwrap();
+        let mut forged_window = window.to_vec();
+        forged_window[0] = secret_element;
+        assert_err!(
+            prove_membership_merkle(
+                secret_element,
+                blinding,
+                &forged_window,
+                &path,
+                &root,
+                &mut rng
+            ),
+            ErrorKind::MerklePathVerificationError
+        );
+
+        // A valid leaf that does not contain the element cannot be used in a proof.
+        assert_err!(
+            prove_membership_merkle(secret_element, blinding, window, &path, &root, &mut rng),
+            ErrorKind::MembershipProofInvalidAssetError
+        );
+
+        // A proof whose window was replaced after proving does not verify.
+        let secret_element = Scalar::from(42u32);
+        let secret_element_com = PedersenGens::default().commit(secret_element, blinding);
+        let mut proof =
+            prove_membership_merkle(secret_element, blinding, window, &path, &root, &mut rng)
+                .unwrap();
+        proof.window[1] = Scalar::from(43u32);
+        assert_err!(
+            verify_membership_merkle(secret_element_com, &proof, &root),
+            ErrorKind::MerklePathVerificationError
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_merkle_membership_window_limit() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let (_, tree) = tree_with_16_leaves();
+        let root = tree.root();
+
+        let secret_element = Scalar::from(42u32);
+        let blinding = Scalar::random(&mut rng);
+        let secret_element_com = PedersenGens::default().commit(secret_element, blinding);
+        let (window, path) = tree.window_of(&secret_element).unwrap();
+        let proof =
+            prove_membership_merkle(secret_element, blinding, window, &path, &root, &mut rng)
+                .unwrap();
+
+        // A window within the limit verifies, one above it is rejected.
+        assert!(verify_membership_merkle_with_max_window(
+            secret_element_com,
+            &proof,
+            &root,
+            WINDOW_SIZE
+        )
+        .is_ok());
+        assert_err!(
+            verify_membership_merkle_with_max_window(
+                secret_element_com,
+                &proof,
+                &root,
+                WINDOW_SIZE - 1
+            ),
+            ErrorKind::MembershipWindowTooLarge {
+                size: WINDOW_SIZE,
+                max: WINDOW_SIZE - 1
+            }
+        );
+
+        // An oversized window is rejected by the default limit before any hashing.
+        let mut oversized = proof.clone();
+        oversized.window = vec![Scalar::from(42u32); MAX_MEMBERSHIP_WINDOW + 1];
+        assert_err!(
+            verify_membership_merkle(secret_element_com, &oversized, &root),
+            ErrorKind::MembershipWindowTooLarge {
+                size: MAX_MEMBERSHIP_WINDOW + 1,
+                max: MAX_MEMBERSHIP_WINDOW
+            }
+        );
+    }
+}
@@ -213,12 +213,28 @@ pub use const_time_elgamal_encryption::CipherTextWithHint;
 
 pub mod encryption_proofs;
 
+pub mod batch_limits;
+pub use batch_limits::BatchLimits;
+
+#[cfg(feature = "bench")]
+pub mod bench;
+
+#[cfg(feature = "blind-tracker")]
+pub mod blind_tracker;
+#[cfg(feature = "mercat")]
+pub mod chunked_encryption;
 pub mod ciphertext_refreshment_proof;
 pub mod correctness_proof;
+pub mod decryption_proof;
 pub mod encrypting_same_value_proof;
+#[cfg(feature = "mercat")]
 pub mod membership_proof;
+#[cfg(feature = "mercat")]
+pub mod merkle_membership_proof;
+#[cfg(feature = "mercat")]
 pub mod one_out_of_many_proof;
 pub mod range_proof;
+pub mod re_encryption;
 pub mod transcript;
 pub mod wellformedness_proof;
 pub use bulletproofs;
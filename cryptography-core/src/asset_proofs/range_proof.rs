@@ -4,7 +4,11 @@
 //! is within a range.
 
 use crate::{
-    asset_proofs::errors::{ErrorKind, Fallible},
+    asset_proofs::{
+        encryption_proofs::ProofExt,
+        errors::{ErrorKind, Fallible},
+        CipherText,
+    },
     codec_wrapper::{
         CompressedRistrettoDecoder, CompressedRistrettoEncoder, RangeProofDencoder,
         RangeProofEncoder,
@@ -13,11 +17,15 @@ use crate::{
 
 use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
-use curve25519_dalek::{ristretto::CompressedRistretto, scalar::Scalar};
+use curve25519_dalek::{
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+};
 use merlin::Transcript;
 use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use sp_std::prelude::*;
 
 const RANGE_PROOF_LABEL: &[u8] = b"PolymathRangeProof";
 
@@ -27,8 +35,32 @@ const RANGE_PROOF_LABEL: &[u8] = b"PolymathRangeProof";
 
 pub type RangeProofInitialMessage = CompressedRistretto;
 
+impl ProofExt for RangeProofInitialMessage {
+    /// Compares the decompressed commitments. An encoding that is not a valid point is not
+    /// equal to anything, including itself.
+    fn crypto_eq(&self, other: &Self) -> bool {
+        match (self.decompress(), other.decompress()) {
+            (Some(point), Some(other_point)) => point == other_point,
+            _ => false,
+        }
+    }
+}
+
+/// The bulletproofs range proof. Use `range_proof_to_bytes` and `range_proof_from_bytes`
+/// to access its canonical compact form, e.g., for an external aggregation layer.
 pub type RangeProofFinalResponse = RangeProof;
 
+/// Returns the canonical compact byte representation of the range proof, as defined by
+/// bulletproofs. For a single proof over `n` bits, this is `(2 * log2(n) + 9) * 32` bytes.
+pub fn range_proof_to_bytes(response: &RangeProofFinalResponse) -> Vec<u8> {
+    response.to_bytes()
+}
+
+/// Parses a range proof from its canonical compact byte representation.
+pub fn range_proof_from_bytes(bytes: &[u8]) -> Fallible<RangeProofFinalResponse> {
+    Ok(RangeProof::from_bytes(bytes).map_err(|source| ErrorKind::ProvingError { source })?)
+}
+
 /// Holds the non-interactive range proofs, equivalent of L_range of MERCAT paper.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -74,6 +106,24 @@ impl InRangeProof {
     }
 }
 
+/// The generators of the range proofs, built once and shared by the range proof entry
+/// points that take pre-built generators.
+pub struct RangeProofGens {
+    pub bp_gens: BulletproofGens,
+    pub pc_gens: PedersenGens,
+}
+
+impl Default for RangeProofGens {
+    /// The generators that `prove_within_range` uses, valid for proofs up to bitsize 64 and
+    /// aggregation size up to 1.
+    fn default() -> Self {
+        RangeProofGens {
+            bp_gens: BulletproofGens::new(64, 1),
+            pc_gens: PedersenGens::default(),
+        }
+    }
+}
+
 /// Generate a range proof for a commitment to a secret value.
 /// Range proof commitments are equevalant to the second term (Y)
 /// of the Elgamal encryption.
@@ -92,13 +142,25 @@ pub fn prove_within_range<Rng: RngCore + CryptoRng>(
     // from a single party into an aggretated proof yet.
     let bp_gens = BulletproofGens::new(64, 1);
 
+    prove_within_range_with_gens(&bp_gens, &pc_gens, secret_value, rand_blind, range, rng)
+}
+
+/// The same as `prove_within_range`, but with caller-built generators.
+pub(crate) fn prove_within_range_with_gens<Rng: RngCore + CryptoRng>(
+    bp_gens: &BulletproofGens,
+    pc_gens: &PedersenGens,
+    secret_value: u64,
+    rand_blind: Scalar,
+    range: u32,
+    rng: &mut Rng,
+) -> Fallible<InRangeProof> {
     // Transcripts eliminate the need for a dealer by employing
     // the Fiat-Shamir huristic.
     let mut prover_transcript = Transcript::new(RANGE_PROOF_LABEL);
 
     let (proof, commitment) = RangeProof::prove_single_with_rng(
-        &bp_gens,
-        &pc_gens,
+        bp_gens,
+        pc_gens,
         &mut prover_transcript,
         secret_value,
         &rand_blind,
@@ -114,30 +176,238 @@ pub fn prove_within_range<Rng: RngCore + CryptoRng>(
     })
 }
 
+/// The bitsizes supported by `prove_within_min_range`, in increasing order.
+pub const SUPPORTED_RANGES: [u32; 4] = [8, 16, 32, 64];
+
+/// Returns the smallest supported bitsize `n` such that `secret_value` is in `[0, 2^n)`.
+pub fn min_range(secret_value: u64) -> u32 {
+    let required_bits = 64 - secret_value.leading_zeros();
+    SUPPORTED_RANGES
+        .iter()
+        .copied()
+        .find(|range| *range >= required_bits)
+        .unwrap_or(64)
+}
+
+/// Generate a range proof for a commitment to a secret value, using the smallest
+/// supported range that fits the value. The chosen range is stored in the `range`
+/// field of the returned proof, which is what the verifier uses.
+///
+/// Note that the chosen range is public, and therefore it leaks an upper bound
+/// (and for ranges above 8 bits, a lower bound as well) on the secret value.
+pub fn prove_within_min_range<Rng: RngCore + CryptoRng>(
+    secret_value: u64,
+    rand_blind: Scalar,
+    rng: &mut Rng,
+) -> Fallible<InRangeProof> {
+    prove_within_range(secret_value, rand_blind, min_range(secret_value), rng)
+}
+
+/// Returns the offset that maps the signed range of `range` bits, `[-2^(range - 1),
+/// 2^(range - 1))`, onto the unsigned range `[0, 2^range)`, if `value` is in the signed range.
+fn signed_range_offset(value: i64, range: u32) -> Fallible<i128> {
+    ensure!(
+        range != 0 && range <= 64,
+        ErrorKind::SignedValueOutOfRange { value, range }
+    );
+    let offset = 1i128 << (range - 1);
+    ensure!(
+        -offset <= value as i128 && (value as i128) < offset,
+        ErrorKind::SignedValueOutOfRange { value, range }
+    );
+    Ok(offset)
+}
+
+/// Generate a range proof that a commitment, e.g., the `y` term of the cipher text from
+/// `enc_signed_value`, commits to a signed value in `[-2^(range - 1), 2^(range - 1))`.
+///
+/// The signed value is shifted by `2^(range - 1)` into `[0, 2^range)`, and the proof is a
+/// range proof for the shifted value with the same blinding factor. The verifier computes the
+/// shifted commitment from the original one, so the shift does not reveal anything.
+pub fn prove_signed_within_range<Rng: RngCore + CryptoRng>(
+    value: i64,
+    rand_blind: Scalar,
+    range: u32,
+    rng: &mut Rng,
+) -> Fallible<InRangeProof> {
+    let offset = signed_range_offset(value, range)?;
+    prove_within_range((value as i128 + offset) as u64, rand_blind, range, rng)
+}
+
+/// Verify a proof that `commitment` commits to a signed value in
+/// `[-2^(range - 1), 2^(range - 1))`. See `prove_signed_within_range`.
+pub fn verify_signed_within_range<Rng: RngCore + CryptoRng>(
+    commitment: &RistrettoPoint,
+    range: u32,
+    proof: &InRangeProof,
+    rng: &mut Rng,
+) -> Fallible<()> {
+    let offset = signed_range_offset(0, range)?;
+    ensure!(proof.range == range, ErrorKind::VerificationError);
+
+    let pc_gens = PedersenGens::default();
+    let shifted_commitment = commitment + Scalar::from(offset as u64) * pc_gens.B;
+    ensure!(
+        proof.init == shifted_commitment.compress(),
+        ErrorKind::RangeCommitmentMismatch
+    );
+
+    verify_within_range(proof, rng)
+}
+
 /// Verify that a range proof is valid given a commitment to a secret value.
 pub fn verify_within_range<Rng: RngCore + CryptoRng>(
     proof: &InRangeProof,
     rng: &mut Rng,
 ) -> Fallible<()> {
-    // Generators for Pedersen commitments.
-    let pc_gens = PedersenGens::default();
+    verify_range_proof(&proof.response, &proof.init, proof.range, rng)
+}
 
-    // Generators for Bulletproofs, valid for proofs up to bitsize 64
-    // and aggregation size up to 1.
-    let bp_gens = BulletproofGens::new(64, 1);
+/// Verify that a range proof is valid given the commitment to the secret value in its
+/// decompressed form, e.g., the `y` term of an Elgamal cipher text. The commitment is
+/// compressed exactly once, instead of requiring the caller to build an `InRangeProof`.
+pub fn verify_within_range_for_commitment<Rng: RngCore + CryptoRng>(
+    response: &RangeProofFinalResponse,
+    commitment: &RistrettoPoint,
+    range: u32,
+    rng: &mut Rng,
+) -> Fallible<()> {
+    verify_range_proof(response, &commitment.compress(), range, rng)
+}
+
+/// Checks that the commitment of a range proof is the `y` term of `cipher`, i.e., that the
+/// range proof is about the value encrypted in `cipher`, and not about an unrelated
+/// commitment. This does not verify the range proof itself.
+pub fn verify_range_matches_ciphertext(
+    range_msg: &RangeProofInitialMessage,
+    cipher: &CipherText,
+) -> Fallible<()> {
+    ensure!(
+        *range_msg == cipher.y.compress(),
+        ErrorKind::RangeCommitmentMismatch
+    );
+    Ok(())
+}
 
+/// Returns a transcript seeded with the standard label of the range proofs, to be reused
+/// with `verify_within_range_with_transcript`.
+pub fn range_proof_transcript() -> Transcript {
+    Transcript::new(RANGE_PROOF_LABEL)
+}
+
+/// The same as `verify_within_range_for_commitment`, but with caller-built generators and
+/// a caller-owned transcript, e.g., in a validator's hot loop, so that neither is built
+/// again for every proof.
+///
+/// The transcript must be created with `range_proof_transcript` and must not be appended
+/// to by the caller. The verification runs on a copy of the seeded transcript, which is a
+/// fixed-size copy without any allocation, so the same transcript is reused for every call.
+pub fn verify_within_range_with_transcript<Rng: RngCore + CryptoRng>(
+    gens: &RangeProofGens,
+    commitment: &RistrettoPoint,
+    response: &RangeProofFinalResponse,
+    range: u32,
+    seeded_transcript: &Transcript,
+    rng: &mut Rng,
+) -> Fallible<()> {
+    verify_range_proof_with_gens(
+        &gens.bp_gens,
+        &gens.pc_gens,
+        response,
+        &commitment.compress(),
+        range,
+        &mut seeded_transcript.clone(),
+        rng,
+    )
+}
+
+/// Generate a proof that the Pedersen commitment `value * B + rand_blind * B_blinding` commits
+/// to a multiple of the public modulus `m`, i.e., `value = q * m` where `q` is in `[0, 2^range)`.
+///
+/// The proof is a range proof for the commitment to the quotient `q`, with the blinding
+/// factor `rand_blind / m`, which makes the original commitment exactly `m` times the
+/// quotient's commitment. The verifier checks this linear relation and the range proof.
+/// Since the quotient's commitment can be computed from the original commitment, it
+/// does not reveal anything beyond the statement.
+pub fn prove_multiple_of<Rng: RngCore + CryptoRng>(
+    value: u64,
+    rand_blind: Scalar,
+    m: u64,
+    range: u32,
+    rng: &mut Rng,
+) -> Fallible<InRangeProof> {
+    ensure!(
+        m != 0 && value % m == 0,
+        ErrorKind::NotAMultipleOfModulus { modulus: m }
+    );
+
+    let quotient_blind = rand_blind * Scalar::from(m).invert();
+    prove_within_range(value / m, quotient_blind, range, rng)
+}
+
+/// Verify a proof that `commitment` commits to a multiple of the public modulus `m`, where
+/// the quotient is in `[0, 2^range)`. See `prove_multiple_of`.
+pub fn verify_multiple_of<Rng: RngCore + CryptoRng>(
+    commitment: &RistrettoPoint,
+    m: u64,
+    range: u32,
+    proof: &InRangeProof,
+    rng: &mut Rng,
+) -> Fallible<()> {
+    ensure!(m != 0, ErrorKind::NotAMultipleOfModulus { modulus: m });
+    ensure!(proof.range == range, ErrorKind::VerificationError);
+
+    let quotient_commitment = proof
+        .init
+        .decompress()
+        .ok_or(ErrorKind::VerificationError)?;
+    ensure!(
+        Scalar::from(m) * quotient_commitment == *commitment,
+        ErrorKind::VerificationError
+    );
+
+    verify_within_range(proof, rng)
+}
+
+fn verify_range_proof<Rng: RngCore + CryptoRng>(
+    response: &RangeProofFinalResponse,
+    init: &RangeProofInitialMessage,
+    range: u32,
+    rng: &mut Rng,
+) -> Fallible<()> {
     // Transcripts eliminate the need for a dealer by employing
     // the Fiat-Shamir huristic.
-    let mut verifier_transcript = Transcript::new(RANGE_PROOF_LABEL);
+    let mut verifier_transcript = range_proof_transcript();
+    let gens = RangeProofGens::default();
+
+    verify_range_proof_with_gens(
+        &gens.bp_gens,
+        &gens.pc_gens,
+        response,
+        init,
+        range,
+        &mut verifier_transcript,
+        rng,
+    )
+}
 
-    proof
-        .response
+/// The same as `verify_range_proof`, but with caller-built generators and transcript.
+pub(crate) fn verify_range_proof_with_gens<Rng: RngCore + CryptoRng>(
+    bp_gens: &BulletproofGens,
+    pc_gens: &PedersenGens,
+    response: &RangeProofFinalResponse,
+    init: &RangeProofInitialMessage,
+    range: u32,
+    verifier_transcript: &mut Transcript,
+    rng: &mut Rng,
+) -> Fallible<()> {
+    response
         .verify_single_with_rng(
-            &bp_gens,
-            &pc_gens,
-            &mut verifier_transcript,
-            &proof.init,
-            proof.range as usize,
+            bp_gens,
+            pc_gens,
+            verifier_transcript,
+            init,
+            range as usize,
             rng,
         )
         .map_err(|_| ErrorKind::VerificationError.into())
@@ -151,12 +421,81 @@ pub fn verify_within_range<Rng: RngCore + CryptoRng>(
 mod tests {
     extern crate wasm_bindgen_test;
     use super::*;
-    use crate::asset_proofs::*;
+    use crate::asset_proofs::{elgamal_encryption::enc_signed_value, *};
     use rand::{rngs::StdRng, SeedableRng};
     use wasm_bindgen_test::*;
 
     const SEED_1: [u8; 32] = [42u8; 32];
 
+    #[test]
+    #[wasm_bindgen_test]
+    fn signed_range_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+
+        let (witness, cipher) = enc_signed_value(-100, &elg_pub, &mut rng);
+        let proof = prove_signed_within_range(-100, witness.blinding(), 8, &mut rng).unwrap();
+        assert!(verify_signed_within_range(&cipher.y, 8, &proof, &mut rng).is_ok());
+
+        // The proof does not verify for another commitment, or another range.
+        let (_, other_cipher) = enc_signed_value(-100, &elg_pub, &mut rng);
+        assert_err!(
+            verify_signed_within_range(&other_cipher.y, 8, &proof, &mut rng),
+            ErrorKind::RangeCommitmentMismatch
+        );
+        assert_err!(
+            verify_signed_within_range(&cipher.y, 16, &proof, &mut rng),
+            ErrorKind::VerificationError
+        );
+
+        // The bounds of the signed range.
+        assert!(prove_signed_within_range(-128, witness.blinding(), 8, &mut rng).is_ok());
+        assert!(prove_signed_within_range(127, witness.blinding(), 8, &mut rng).is_ok());
+        assert_err!(
+            prove_signed_within_range(128, witness.blinding(), 8, &mut rng),
+            ErrorKind::SignedValueOutOfRange {
+                value: 128,
+                range: 8
+            }
+        );
+        assert_err!(
+            prove_signed_within_range(-129, witness.blinding(), 8, &mut rng),
+            ErrorKind::SignedValueOutOfRange {
+                value: -129,
+                range: 8
+            }
+        );
+        assert!(
+            prove_signed_within_range(i64::min_value(), witness.blinding(), 64, &mut rng).is_ok()
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn crypto_eq_of_commitments() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let gens = PedersenGens::default();
+        let blinding = Scalar::random(&mut rng);
+
+        // The same commitment, computed and encoded in two different ways.
+        let proof = prove_within_range(42, blinding, 32, &mut rng).unwrap();
+        let commitment = gens.commit(Scalar::from(42u64), blinding).compress();
+        let bytes = CompressedRistrettoEncoder(&commitment).encode();
+        let decoded = <CompressedRistrettoDecoder>::decode(&mut bytes.as_slice())
+            .unwrap()
+            .0;
+        assert!(proof.init.crypto_eq(&commitment));
+        assert!(decoded.crypto_eq(&proof.init));
+
+        // A different commitment and an invalid encoding are not equal.
+        let other = gens.commit(Scalar::from(43u64), blinding).compress();
+        assert!(!proof.init.crypto_eq(&other));
+        let invalid = CompressedRistretto([0xffu8; 32]);
+        assert_eq!(invalid, invalid);
+        assert!(!invalid.crypto_eq(&invalid));
+    }
+
     #[test]
     #[wasm_bindgen_test]
     fn basic_range_proof() {
@@ -182,4 +521,189 @@ mod tests {
             prove_within_range(large_secret_value, witness.blinding(), 32, &mut rng).unwrap();
         assert!(!verify_within_range(&bad_proof, &mut rng).is_ok());
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn min_range_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+
+        assert_eq!(min_range(0), 8);
+        assert_eq!(min_range(200), 8);
+        assert_eq!(min_range(256), 16);
+        assert_eq!(min_range(70000), 32);
+        assert_eq!(min_range(u64::from(u32::max_value())), 32);
+        assert_eq!(min_range(u64::from(u32::max_value()) + 1), 64);
+        assert_eq!(min_range(u64::max_value()), 64);
+
+        for (secret_value, expected_range) in &[(200u64, 8u32), (300, 16), (70000, 32)] {
+            let blinding = Scalar::random(&mut rng);
+            let proof = prove_within_min_range(*secret_value, blinding, &mut rng)
+                .expect("This shouldn't happen.");
+            assert_eq!(proof.range, *expected_range);
+            assert!(verify_within_range(&proof, &mut rng).is_ok());
+
+            // Negative test: the verifier must use the range chosen by the prover.
+            let mut bad_proof = proof.clone();
+            bad_proof.range = expected_range / 2;
+            assert!(verify_within_range(&bad_proof, &mut rng).is_err());
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn range_proof_bytes() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let blinding = Scalar::random(&mut rng);
+
+        for (range, expected_len) in &[(32u32, 608usize), (64, 672)] {
+            let proof = prove_within_range(42, blinding, *range, &mut rng).unwrap();
+            let bytes = range_proof_to_bytes(&proof.response);
+            assert_eq!(bytes.len(), *expected_len);
+
+            let response = range_proof_from_bytes(&bytes).unwrap();
+            assert_eq!(range_proof_to_bytes(&response), bytes);
+
+            let decoded_proof = InRangeProof {
+                init: proof.init,
+                response,
+                range: proof.range,
+            };
+            assert!(verify_within_range(&decoded_proof, &mut rng).is_ok());
+        }
+
+        // Negative test: truncated bytes.
+        let proof = prove_within_range(42, blinding, 64, &mut rng).unwrap();
+        let bytes = range_proof_to_bytes(&proof.response);
+        assert!(range_proof_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn range_proof_for_commitment() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret_value = 42u32;
+
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let (witness, cipher) = elg_pub.encrypt_value(secret_value.into(), &mut rng);
+
+        let proof = prove_within_range(secret_value as u64, witness.blinding(), 32, &mut rng)
+            .expect("This shouldn't happen.");
+
+        // Verifying from the decompressed commitment matches the compressed path.
+        assert!(verify_within_range(&proof, &mut rng).is_ok());
+        assert!(verify_within_range_for_commitment(
+            &proof.response,
+            &cipher.y,
+            proof.range,
+            &mut rng
+        )
+        .is_ok());
+
+        // Negative tests: wrong commitment or wrong range.
+        assert!(verify_within_range_for_commitment(
+            &proof.response,
+            &cipher.x,
+            proof.range,
+            &mut rng
+        )
+        .is_err());
+        assert!(
+            verify_within_range_for_commitment(&proof.response, &cipher.y, 64, &mut rng).is_err()
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn multiple_of_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let gens = PedersenGens::default();
+        let blinding = Scalar::random(&mut rng);
+
+        // Positive test: 500 is a multiple of 100.
+        let commitment = gens.commit(Scalar::from(500u64), blinding);
+        let proof = prove_multiple_of(500, blinding, 100, 32, &mut rng).unwrap();
+        assert!(verify_multiple_of(&commitment, 100, 32, &proof, &mut rng).is_ok());
+
+        // Negative tests: wrong modulus, or wrong range.
+        assert!(verify_multiple_of(&commitment, 50, 32, &proof, &mut rng).is_err());
+        assert!(verify_multiple_of(&commitment, 100, 64, &proof, &mut rng).is_err());
+
+        // Negative test: the prover cannot prove a non-multiple.
+        assert_err!(
+            prove_multiple_of(501, blinding, 100, 32, &mut rng),
+            ErrorKind::NotAMultipleOfModulus { modulus: 100 }
+        );
+
+        // Negative test: a proof for a multiple does not verify against a non-multiple.
+        let bad_commitment = gens.commit(Scalar::from(501u64), blinding);
+        assert!(verify_multiple_of(&bad_commitment, 100, 32, &proof, &mut rng).is_err());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn range_proof_with_reused_transcript() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let gens = PedersenGens::default();
+
+        let mut proofs = Vec::new();
+        for secret_value in &[0u64, 42, 70000, u64::from(u32::max_value()) + 3] {
+            let blinding = Scalar::random(&mut rng);
+            let proof = prove_within_range(*secret_value, blinding, 32, &mut rng).unwrap();
+            let commitment = gens.commit(Scalar::from(*secret_value), blinding);
+            proofs.push((commitment, proof));
+        }
+
+        let range_gens = RangeProofGens::default();
+        let transcript = range_proof_transcript();
+        for (commitment, proof) in &proofs {
+            let with_fresh =
+                verify_within_range_for_commitment(&proof.response, commitment, 32, &mut rng);
+            let with_reused = verify_within_range_with_transcript(
+                &range_gens,
+                commitment,
+                &proof.response,
+                32,
+                &transcript,
+                &mut rng,
+            );
+            assert_eq!(with_fresh.is_ok(), with_reused.is_ok());
+        }
+
+        // The last value is out of range, the others verify.
+        let results: Vec<bool> = proofs
+            .iter()
+            .map(|(commitment, proof)| {
+                verify_within_range_with_transcript(
+                    &range_gens,
+                    commitment,
+                    &proof.response,
+                    32,
+                    &transcript,
+                    &mut rng,
+                )
+                .is_ok()
+            })
+            .collect();
+        assert_eq!(results, vec![true, true, true, false]);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn range_proof_matches_ciphertext() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_pub = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let (witness, cipher) = elg_pub.encrypt_value(42u32.into(), &mut rng);
+        let (_, other_cipher) = elg_pub.encrypt_value(42u32.into(), &mut rng);
+
+        let proof = prove_within_range(42, witness.blinding(), 32, &mut rng).unwrap();
+        assert!(verify_range_matches_ciphertext(&proof.init, &cipher).is_ok());
+
+        // A valid range proof about an unrelated commitment to the same value.
+        assert!(verify_within_range(&proof, &mut rng).is_ok());
+        assert_err!(
+            verify_range_matches_ciphertext(&proof.init, &other_cipher),
+            ErrorKind::RangeCommitmentMismatch
+        );
+    }
 }
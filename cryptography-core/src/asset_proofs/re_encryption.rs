@@ -0,0 +1,160 @@
+//! The re-encryption of a ciphertext to a new public key, e.g., when an account is handed
+//! off to a new owner.
+//!
+//! The owner of the old secret key cannot recover the blinding factor of an existing
+//! ciphertext, which the proof of encrypting the same value under different keys needs as
+//! its witness. The ciphertext is therefore first refreshed under the old key with a fresh
+//! blinding factor, and the refreshment is proven with the ciphertext refreshment proof.
+//! The refreshed ciphertext and the new ciphertext then share their commitment witness,
+//! and are proven to encrypt the same value under the old and the new keys.
+
+use crate::asset_proofs::{
+    ciphertext_refreshment_proof::{
+        CipherEqualSamePubKeyProof, CipherTextRefreshmentProverAwaitingChallenge,
+        CipherTextRefreshmentVerifier,
+    },
+    encrypting_same_value_proof::{
+        CipherEqualDifferentPubKeyProof, EncryptingSameValueProverAwaitingChallenge,
+        EncryptingSameValueVerifier,
+    },
+    encryption_proofs::{single_property_prover, single_property_verifier},
+    errors::Fallible,
+    CipherText, CommitmentWitness, ElgamalPublicKey, ElgamalSecretKey,
+};
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek::scalar::Scalar;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// The proof that a re-encrypted ciphertext encrypts the same value as the original one,
+/// see the module documentation.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReEncryptionProof {
+    /// The original ciphertext, refreshed under the old key.
+    pub refreshed: CipherText,
+    /// The proof that the original and the refreshed ciphertexts encrypt the same value.
+    pub refreshment_proof: CipherEqualSamePubKeyProof,
+    /// The proof that the refreshed and the re-encrypted ciphertexts encrypt the same value.
+    pub equality_proof: CipherEqualDifferentPubKeyProof,
+}
+
+impl ElgamalSecretKey {
+    /// Decrypts `cipher` and encrypts its value to `new_pub`. Returns the new ciphertext,
+    /// along with the proof that it encrypts the same value as `cipher`.
+    pub fn re_encrypt<R: RngCore + CryptoRng>(
+        &self,
+        cipher: &CipherText,
+        new_pub: &ElgamalPublicKey,
+        rng: &mut R,
+    ) -> Fallible<(CipherText, ReEncryptionProof)> {
+        let gens = PedersenGens::default();
+        let old_pub = self.get_public_key();
+
+        let value: Scalar = self.decrypt(cipher)?.into();
+        let witness = CommitmentWitness::new(value, Scalar::random(rng));
+        let refreshed = old_pub.encrypt(&witness);
+        let re_encrypted = new_pub.encrypt(&witness);
+
+        let refreshment_proof = single_property_prover(
+            CipherTextRefreshmentProverAwaitingChallenge::new(
+                self.clone(),
+                *cipher,
+                refreshed,
+                &gens,
+            ),
+            rng,
+        )?;
+        let equality_proof = single_property_prover(
+            EncryptingSameValueProverAwaitingChallenge {
+                pub_key1: old_pub,
+                pub_key2: *new_pub,
+                w: Zeroizing::new(witness),
+                pc_gens: &gens,
+            },
+            rng,
+        )?;
+
+        Ok((
+            re_encrypted,
+            ReEncryptionProof {
+                refreshed,
+                refreshment_proof,
+                equality_proof,
+            },
+        ))
+    }
+}
+
+impl ReEncryptionProof {
+    /// Verifies that `new_cipher` under `new_pub` encrypts the same value as `old_cipher`
+    /// under `old_pub`.
+    pub fn verify(
+        &self,
+        old_pub: &ElgamalPublicKey,
+        old_cipher: &CipherText,
+        new_pub: &ElgamalPublicKey,
+        new_cipher: &CipherText,
+    ) -> Fallible<()> {
+        let gens = PedersenGens::default();
+
+        single_property_verifier(
+            &CipherTextRefreshmentVerifier::new(*old_pub, *old_cipher, self.refreshed, &gens),
+            self.refreshment_proof,
+        )?;
+        single_property_verifier(
+            &EncryptingSameValueVerifier {
+                pub_key1: *old_pub,
+                pub_key2: *new_pub,
+                cipher1: self.refreshed,
+                cipher2: *new_cipher,
+                pc_gens: &gens,
+            },
+            self.equality_proof,
+        )
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    const SEED_1: [u8; 32] = [31u8; 32];
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_re_encrypt() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let old_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let old_pub = old_secret.get_public_key();
+        let new_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let new_pub = new_secret.get_public_key();
+
+        let (_, cipher) = old_pub.encrypt_value(Scalar::from(42u32), &mut rng);
+        let (new_cipher, proof) = old_secret.re_encrypt(&cipher, &new_pub, &mut rng).unwrap();
+        assert_eq!(new_secret.decrypt(&new_cipher).unwrap(), 42);
+        assert!(proof
+            .verify(&old_pub, &cipher, &new_pub, &new_cipher)
+            .is_ok());
+
+        // The proof is bound to both the original and the re-encrypted ciphertexts.
+        let (_, other_cipher) = new_pub.encrypt_value(Scalar::from(43u32), &mut rng);
+        assert!(proof
+            .verify(&old_pub, &cipher, &new_pub, &other_cipher)
+            .is_err());
+        let (_, other_cipher) = old_pub.encrypt_value(Scalar::from(42u32), &mut rng);
+        assert!(proof
+            .verify(&old_pub, &other_cipher, &new_pub, &new_cipher)
+            .is_err());
+    }
+}
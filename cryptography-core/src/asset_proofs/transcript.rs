@@ -48,6 +48,25 @@ pub trait TranscriptProtocol {
     /// A scalar challenge.
     fn scalar_challenge(&mut self, label: &'static [u8]) -> Fallible<ZKPChallenge>;
 
+    /// Get the protocol's challenge, using a caller-supplied buffer for the
+    /// challenge bytes. Produces the same challenge as `scalar_challenge`.
+    /// Useful on embedded targets that reuse the same buffer for all the challenges.
+    /// The default implementation leaves `buf` untouched and calls `scalar_challenge`.
+    ///
+    /// # Inputs
+    /// * `label` a domain label.
+    /// * `buf`   a buffer that is overwritten with the wide challenge bytes.
+    ///
+    /// # Output
+    /// A scalar challenge.
+    fn scalar_challenge_into(
+        &mut self,
+        label: &'static [u8],
+        _buf: &mut [u8; 64],
+    ) -> Fallible<ZKPChallenge> {
+        self.scalar_challenge(label)
+    }
+
     /// Create an RNG seeded from the transcript's cloned state and
     /// randomness from an external `rng`.
     ///
@@ -83,9 +102,17 @@ impl TranscriptProtocol for Transcript {
 
     fn scalar_challenge(&mut self, label: &'static [u8]) -> Fallible<ZKPChallenge> {
         let mut buf = [0u8; 64];
-        self.challenge_bytes(label, &mut buf);
+        self.scalar_challenge_into(label, &mut buf)
+    }
 
-        Scalar::from_bytes_mod_order_wide(&buf).try_into()
+    fn scalar_challenge_into(
+        &mut self,
+        label: &'static [u8],
+        buf: &mut [u8; 64],
+    ) -> Fallible<ZKPChallenge> {
+        self.challenge_bytes(label, buf);
+
+        Scalar::from_bytes_mod_order_wide(buf).try_into()
     }
 
     fn create_transcript_rng_from_witness<T: RngCore + CryptoRng>(
@@ -119,4 +146,30 @@ mod tests {
             ErrorKind::VerificationError
         );
     }
+
+    #[test]
+    fn scalar_challenge_into_buffer() {
+        let mut transcript = Transcript::new(b"unit test");
+        transcript.append_domain_separator(b"challenge");
+        let mut other_transcript = transcript.clone();
+
+        let mut buf = [0u8; 64];
+        let challenge = transcript.scalar_challenge(b"label").unwrap();
+        let other_challenge = other_transcript
+            .scalar_challenge_into(b"label", &mut buf)
+            .unwrap();
+        assert_eq!(challenge, other_challenge);
+        assert_eq!(
+            *other_challenge.x(),
+            Scalar::from_bytes_mod_order_wide(&buf)
+        );
+
+        // The buffer can be reused for the next challenge.
+        assert_eq!(
+            transcript.scalar_challenge(b"next label").unwrap(),
+            other_transcript
+                .scalar_challenge_into(b"next label", &mut buf)
+                .unwrap()
+        );
+    }
 }
@@ -162,6 +162,11 @@ impl<'a> AssetProofProverAwaitingChallenge for WellformednessProverAwaitingChall
             },
         )
     }
+
+    #[cfg(feature = "blind-tracker")]
+    fn blinding(&self) -> Option<Scalar> {
+        Some(self.w.blinding())
+    }
 }
 
 impl AssetProofProver<WellformednessFinalResponse> for WellformednessProver {
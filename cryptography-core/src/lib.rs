@@ -10,8 +10,55 @@ pub use curve25519_dalek::{
     scalar::Scalar,
 };
 
+#[cfg(feature = "identity")]
 pub mod cdd_claim;
 
 pub mod codec_wrapper;
 
 pub mod asset_proofs;
+
+// ------------------------------------------------------------------------------------------------
+// -                                            Tests                                             -
+// ------------------------------------------------------------------------------------------------
+
+/// These tests also compile with a single component enabled, e.g.,
+/// `cargo test --no-default-features --features std,u64_backend,identity`.
+#[cfg(test)]
+mod tests {
+    use crate::asset_proofs::{CipherText, ElgamalSecretKey};
+    use crate::Scalar;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn shared_asset_proofs_are_always_available() {
+        let mut rng = StdRng::from_seed([7u8; 32]);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let (_, cipher): (_, CipherText) = elg_pub.encrypt_value(42u32.into(), &mut rng);
+        assert!(elg_secret.verify(&cipher, &42u32.into()).is_ok());
+    }
+
+    #[cfg(feature = "identity")]
+    #[test]
+    #[wasm_bindgen_test]
+    fn identity_component_is_available() {
+        use crate::cdd_claim::{compute_cdd_id, CddClaimData};
+
+        let cdd_claim = CddClaimData::new(&[1u8; 32], &[2u8; 32]);
+        assert_eq!(compute_cdd_id(&cdd_claim), compute_cdd_id(&cdd_claim));
+    }
+
+    #[cfg(feature = "mercat")]
+    #[test]
+    #[wasm_bindgen_test]
+    fn mercat_component_is_available() {
+        use crate::asset_proofs::{
+            chunked_encryption::ChunkedCiphertext, membership_proof::MembershipProofVerifier,
+        };
+
+        let _ = core::mem::size_of::<ChunkedCiphertext>();
+        let _ = core::mem::size_of::<MembershipProofVerifier>();
+    }
+}
@@ -1,6 +1,9 @@
 mod utility;
 use criterion::{criterion_group, criterion_main, Criterion};
-use cryptography_core::asset_proofs::{AssetId, Balance};
+use cryptography_core::{
+    asset_proofs::{AssetId, Balance},
+    curve25519_dalek::scalar::Scalar,
+};
 use mercat::{
     account::convert_asset_ids,
     asset::{AssetIssuer, AssetValidator},
@@ -23,11 +26,13 @@ const ASSET_ID: u32 = 1;
 fn bench_transaction_issuer(
     c: &mut Criterion,
     issuer_account: Account,
+    valid_asset_ids: Vec<Scalar>,
     amounts: Vec<Balance>,
 ) -> Vec<InitializedAssetTx> {
     let label = "MERCAT Transaction: Issuer".to_string();
     let mut rng = thread_rng();
     let issuer_account_cloned = issuer_account.clone();
+    let valid_asset_ids_cloned = valid_asset_ids.clone();
 
     c.bench_function_over_inputs(
         &label,
@@ -37,6 +42,7 @@ fn bench_transaction_issuer(
                 issuer
                     .initialize_asset_transaction(
                         &issuer_account_cloned.clone(),
+                        &valid_asset_ids_cloned,
                         &[],
                         amount,
                         &mut rng,
@@ -52,7 +58,13 @@ fn bench_transaction_issuer(
         .map(|&amount| {
             let issuer = AssetIssuer;
             issuer
-                .initialize_asset_transaction(&issuer_account.clone(), &[], amount, &mut rng)
+                .initialize_asset_transaction(
+                    &issuer_account.clone(),
+                    &valid_asset_ids,
+                    &[],
+                    amount,
+                    &mut rng,
+                )
                 .unwrap()
         })
         .collect()
@@ -109,7 +121,8 @@ fn bench_asset_transaction(c: &mut Criterion) {
         .collect();
 
     // Initialization
-    let transactions = bench_transaction_issuer(c, issuer_account.clone(), issued_amounts);
+    let transactions =
+        bench_transaction_issuer(c, issuer_account.clone(), valid_asset_ids, issued_amounts);
 
     // Validation
     bench_transaction_validator(c, transactions, issuer_account.public, issuer_init_balance);
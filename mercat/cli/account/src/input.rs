@@ -92,6 +92,53 @@ pub struct DecryptAccountInfo {
     pub ticker: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, StructOpt)]
+pub struct CloseAccountInfo {
+    /// The name of the user. The name can be any valid string that can be used as a file name.
+    #[structopt(short, long, help = "The name of the user. This name must be unique.")]
+    pub user: String,
+
+    /// The directory that will serve as the database of the on/off-chain data and will be used
+    /// to save and load the data that in a real execution would be written to the on/off the
+    /// blockchain. Defaults to the current directory. This directory will have two main
+    /// sub-directories: `on-chain` and `off-chain`.
+    #[structopt(
+        parse(from_os_str),
+        help = "The directory to load and save the input and output files. Defaults to current directory.",
+        short,
+        long
+    )]
+    pub db_dir: Option<PathBuf>,
+
+    /// An asset ticker name which is a string of at most 12 characters.
+    /// In these test CLIs, the unique account id is created from the pair of username and ticker.
+    #[structopt(
+        short,
+        long,
+        help = "The asset ticker name. String of at most 12 characters."
+    )]
+    pub ticker: String,
+
+    /// An optional seed, to feed to the RNG, that can be passed to reproduce a previous run of this CLI.
+    /// The seed can be found inside the logs.
+    #[structopt(
+        long,
+        help = "Base64 encoding of an initial seed for the RNG. If not provided, the seed will be chosen at random."
+    )]
+    pub seed: Option<String>,
+
+    /// Transaction id.
+    #[structopt(long, help = "Transaction id.")]
+    pub tx_id: u32,
+
+    /// Instructs the CLI to print the transaction data in stdout.
+    #[structopt(
+        long,
+        help = "Instructs the CLI to print the transaction data in stdout."
+    )]
+    pub stdout: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, StructOpt)]
 pub struct IssueAssetInfo {
     /// Account ID of the issuer will be generated from the username and ticker name pair.
@@ -326,6 +373,9 @@ pub enum CLI {
 
     /// Decrypt the account balance.
     Decrypt(DecryptAccountInfo),
+
+    /// Close a MERCAT account whose balance is zero.
+    Close(CloseAccountInfo),
 }
 
 pub fn parse_input() -> CLI {
@@ -391,6 +441,30 @@ pub fn parse_input() -> CLI {
             CLI::Decrypt(cfg)
         }
 
+        CLI::Close(cfg) => {
+            let db_dir = cfg.db_dir.clone().or_else(|| std::env::current_dir().ok());
+
+            let seed: Option<String> = cfg.seed.clone().or_else(|| Some(gen_seed()));
+            info!("Seed: {:?}", seed.clone().unwrap()); // unwrap won't panic
+
+            let cfg = CloseAccountInfo {
+                seed,
+                ticker: cfg.ticker,
+                db_dir,
+                user: cfg.user,
+                tx_id: cfg.tx_id,
+                stdout: cfg.stdout,
+            };
+
+            info!(
+                "Parsed the following config from the command line:
+{:#?}",
+                cfg
+            );
+
+            CLI::Close(cfg)
+        }
+
         CLI::Issue(cfg) => {
             let db_dir = cfg.db_dir.clone().or_else(|| std::env::current_dir().ok());
 
@@ -6,6 +6,7 @@ mod input;
 use input::{parse_input, CLI};
 use log::info;
 use mercat_common::{
+    account_close::process_close_account,
     account_create::process_create_account,
     account_issue::process_issue_asset,
     account_transfer::{process_create_tx, process_finalize_tx},
@@ -43,6 +44,15 @@ fn main() {
             )
             .unwrap()
         ),
+        CLI::Close(cfg) => process_close_account(
+            cfg.seed.ok_or(Error::EmptySeed).unwrap(),
+            cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
+            cfg.ticker,
+            cfg.user,
+            cfg.stdout,
+            cfg.tx_id,
+        )
+        .unwrap(),
         CLI::Issue(cfg) => process_issue_asset(
             cfg.seed.ok_or(Error::EmptySeed).unwrap(),
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
@@ -0,0 +1,73 @@
+use crate::{
+    account_close_transaction_file, create_rng_from_seed, errors::Error, load_object, save_object,
+    user_public_account_balance_file, user_public_account_file, user_secret_account_file,
+    AccountClosureTx, OrderedPubAccount, COMMON_OBJECTS_DIR, OFF_CHAIN_DIR, ON_CHAIN_DIR,
+};
+use codec::Encode;
+use log::info;
+use mercat::{account::prove_balance_zero, EncryptedAmount, SecAccount};
+use metrics::timing;
+use std::{path::PathBuf, time::Instant};
+
+/// Requests the closure of the user's account, by proving that its validated balance is zero.
+/// The validator closes the account once it has verified the proof.
+pub fn process_close_account(
+    seed: String,
+    db_dir: PathBuf,
+    ticker: String,
+    user: String,
+    stdout: bool,
+    tx_id: u32,
+) -> Result<(), Error> {
+    let mut rng = create_rng_from_seed(Some(seed))?;
+
+    let load_from_file_timer = Instant::now();
+    let ordered_pub_account: OrderedPubAccount = load_object(
+        db_dir.clone(),
+        ON_CHAIN_DIR,
+        &user,
+        &user_public_account_file(&ticker),
+    )?;
+    let balance: EncryptedAmount = load_object(
+        db_dir.clone(),
+        ON_CHAIN_DIR,
+        &user,
+        &user_public_account_balance_file(&ticker),
+    )?;
+    let secret_account: SecAccount = load_object(
+        db_dir.clone(),
+        OFF_CHAIN_DIR,
+        &user,
+        &user_secret_account_file(&ticker),
+    )?;
+    timing!("account.close.load_from_file", load_from_file_timer, Instant::now(), "tx_id" => tx_id.to_string());
+
+    let close_account_timer = Instant::now();
+    let zero_balance_proof = prove_balance_zero(&secret_account, &balance, &mut rng)
+        .map_err(|error| Error::LibraryError { error })?;
+    timing!("account.close.call_library", close_account_timer, Instant::now(), "tx_id" => tx_id.to_string());
+
+    let save_to_file_timer = Instant::now();
+    let instruction = AccountClosureTx {
+        account_id: ordered_pub_account.pub_account.enc_asset_id,
+        zero_balance_proof,
+    };
+    save_object(
+        db_dir,
+        ON_CHAIN_DIR,
+        COMMON_OBJECTS_DIR,
+        &account_close_transaction_file(tx_id, &user, &ticker),
+        &instruction,
+    )?;
+
+    if stdout {
+        info!(
+            "CLI log: tx-{}: Transaction as base64:\n{}\n",
+            tx_id,
+            base64::encode(instruction.encode())
+        );
+    }
+    timing!("account.close.save_output", save_to_file_timer, Instant::now(), "tx_id" => tx_id.to_string());
+
+    Ok(())
+}
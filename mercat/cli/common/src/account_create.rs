@@ -101,7 +101,7 @@ pub fn process_create_account(
     Ok(())
 }
 
-fn create_secret_account<R: RngCore + CryptoRng>(
+pub(crate) fn create_secret_account<R: RngCore + CryptoRng>(
     rng: &mut R,
     ticker_id: String,
 ) -> Result<SecAccount, Error> {
@@ -1,8 +1,9 @@
 use crate::{
-    asset_transaction_file, create_rng_from_seed, errors::Error, last_ordering_state, load_object,
-    retrieve_auditors_by_names, save_issue_transaction_name, save_object, user_public_account_file,
-    user_secret_account_file, OrderedAssetInstruction, OrderedPubAccount, OrderingState,
-    COMMON_OBJECTS_DIR, OFF_CHAIN_DIR, ON_CHAIN_DIR,
+    asset_transaction_file, create_rng_from_seed, errors::Error, get_asset_ids,
+    last_ordering_state, load_object, retrieve_auditors_by_names, save_issue_transaction_name,
+    save_object, user_public_account_file, user_secret_account_file, OrderedAssetInstruction,
+    OrderedPubAccount, OrderingState, COMMON_OBJECTS_DIR, GENESIS_STATE_HASH, OFF_CHAIN_DIR,
+    ON_CHAIN_DIR,
 };
 use codec::Encode;
 use cryptography_core::asset_proofs::{asset_id_from_ticker, CommitmentWitness};
@@ -63,6 +64,7 @@ pub fn process_issue_asset(
         )?,
     };
     let auditors_accounts = retrieve_auditors_by_names(auditors, db_dir.clone())?;
+    let valid_asset_ids = get_asset_ids(db_dir.clone())?;
 
     timing!(
         "account.issue_asset.load_from_file",
@@ -111,7 +113,13 @@ pub fn process_issue_asset(
     let issuance_init_timer = Instant::now();
     let ctx_issuer = AssetIssuer;
     let mut asset_tx = ctx_issuer
-        .initialize_asset_transaction(&issuer_account, &auditors_accounts, amount, &mut rng)
+        .initialize_asset_transaction(
+            &issuer_account,
+            &valid_asset_ids,
+            &auditors_accounts,
+            amount,
+            &mut rng,
+        )
         .map_err(|error| Error::LibraryError { error })?;
 
     let ordering_state = OrderingState {
@@ -150,6 +158,7 @@ pub fn process_issue_asset(
         data: asset_tx.encode().to_vec(),
         amount,
         auditors: auditors.to_vec(),
+        prev_state_hash: GENESIS_STATE_HASH,
     };
 
     save_object(
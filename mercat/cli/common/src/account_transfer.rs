@@ -1,10 +1,11 @@
 use crate::{
     compute_enc_pending_balance, confidential_transaction_file, construct_path,
     create_rng_from_seed, debug_decrypt, errors::Error, last_ordering_state, load_object,
-    non_empty_account_id, retrieve_auditors_by_names, save_object, save_transfer_transaction_name,
-    user_public_account_balance_file, user_public_account_file, user_secret_account_file,
-    OrderedPubAccount, OrderedTransferInstruction, OrderingState, PrintableAccountId,
-    COMMON_OBJECTS_DIR, MEDIATOR_PUBLIC_ACCOUNT_FILE, OFF_CHAIN_DIR, ON_CHAIN_DIR,
+    load_transfer_initialization, non_empty_account_id, retrieve_auditors_by_names, save_object,
+    save_transfer_transaction_name, state_hash, user_public_account_balance_file,
+    user_public_account_file, user_secret_account_file, OrderedPubAccount,
+    OrderedTransferInstruction, OrderingState, PrintableAccountId, COMMON_OBJECTS_DIR,
+    GENESIS_STATE_HASH, MEDIATOR_PUBLIC_ACCOUNT_FILE, OFF_CHAIN_DIR, ON_CHAIN_DIR,
 };
 use codec::{Decode, Encode};
 use log::{debug, info};
@@ -204,6 +205,7 @@ pub fn process_create_tx(
         ordering_state,
         data: asset_tx.encode().to_vec(),
         auditors: auditors.to_vec(),
+        prev_state_hash: GENESIS_STATE_HASH,
     };
 
     save_object(
@@ -262,13 +264,10 @@ pub fn process_finalize_tx(
         public: receiver_ordered_pub_account.pub_account,
     };
 
-    let instruction: OrderedTransferInstruction = load_object(
-        db_dir.clone(),
-        ON_CHAIN_DIR,
-        COMMON_OBJECTS_DIR,
-        &confidential_transaction_file(tx_id, &sender, state),
-    )?;
+    // Make sure that the sender has initialized the transaction.
+    let instruction = load_transfer_initialization(db_dir.clone(), tx_id, &sender)?;
 
+    let prev_state_hash = state_hash(&instruction);
     let tx = InitializedTransferTx::decode(&mut &instruction.data[..]).map_err(|error| {
         Error::ObjectLoadError {
             error,
@@ -356,6 +355,7 @@ pub fn process_finalize_tx(
         ordering_state,
         data: asset_tx.encode().to_vec(),
         auditors: vec![], // TODO
+        prev_state_hash,
     };
 
     save_object(
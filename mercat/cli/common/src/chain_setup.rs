@@ -4,7 +4,7 @@ use crate::{
 use cryptography_core::asset_proofs::{asset_id_from_ticker, AssetId};
 use mercat::account::convert_asset_ids;
 use metrics::timing;
-use std::{path::PathBuf, time::Instant};
+use std::{collections::BTreeMap, path::PathBuf, time::Instant};
 
 pub fn process_asset_id_creation(db_dir: PathBuf, ticker_names: Vec<String>) -> Result<(), Error> {
     let start = Instant::now();
@@ -34,3 +34,42 @@ pub fn process_asset_id_creation(db_dir: PathBuf, ticker_names: Vec<String>) ->
 
     Ok(())
 }
+
+/// Merges two asset id registries. The result contains each asset id once, sorted by the
+/// bytes of the asset id, so that merging the same registries always produces the same list.
+///
+/// An asset is defined by its id alone, and the scalar that the membership proofs use is a
+/// hash of the id, so two registries cannot hold conflicting definitions of the same asset.
+/// The merge does not fail at the moment; the `Result` leaves room for the registries to
+/// carry more than the ids.
+pub fn merge_asset_registries(a: &[AssetId], b: &[AssetId]) -> Result<Vec<AssetId>, Error> {
+    let merged: BTreeMap<_, _> = a
+        .iter()
+        .chain(b.iter())
+        .map(|asset_id| (asset_id.id, asset_id.clone()))
+        .collect();
+
+    Ok(merged.into_iter().map(|(_, asset_id)| asset_id).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_merge_asset_registries() {
+        let to_asset_ids =
+            |ids: &[u32]| ids.iter().map(|id| AssetId::from(*id)).collect::<Vec<_>>();
+        let a = to_asset_ids(&[3, 1, 2, 1]);
+        let b = to_asset_ids(&[2, 5, 4]);
+
+        let merged = merge_asset_registries(&a, &b).unwrap();
+        assert_eq!(merged, to_asset_ids(&[1, 2, 3, 4, 5]));
+
+        // Merging is deterministic and symmetric.
+        assert_eq!(merge_asset_registries(&b, &a).unwrap(), merged);
+        assert_eq!(merge_asset_registries(&merged, &[]).unwrap(), merged);
+    }
+}
@@ -1,5 +1,5 @@
 use failure::Fail;
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 /// Common errors for all mercat clis
 #[derive(Fail, Debug)]
@@ -48,6 +48,10 @@ pub enum Error {
         path: PathBuf,
     },
 
+    /// An error occurred while indexing a tar archive of the database directory.
+    #[fail(display = "Failed to read the archive: {:?}", error)]
+    ArchiveReadError { error: std::io::Error },
+
     /// An error occurred while deserializing an object from a file.
     #[fail(
         display = "Failed to deserialize an object, read from file {:?}: {:?}",
@@ -72,6 +76,27 @@ pub enum Error {
     )]
     ObjectLoadError { error: codec::Error, path: PathBuf },
 
+    /// The checksum of an object, read from file, does not match its content.
+    #[fail(
+        display = "The checksum of the object in file {:?} does not match",
+        path
+    )]
+    ObjectChecksumMismatch { path: PathBuf },
+
+    /// The file does not start with the header of the on-disk object format.
+    #[fail(
+        display = "The file {:?} does not hold an object in the on-disk object format",
+        path
+    )]
+    MissingObjectHeader { path: PathBuf },
+
+    /// The object was saved in a newer, or unknown, format.
+    #[fail(
+        display = "The object in file {:?} has the unsupported format version {}",
+        path, version
+    )]
+    UnsupportedObjectFormat { version: u8, path: PathBuf },
+
     /// An error occurred while writing to a file.
     #[fail(display = "Failed to save data to file {:?}: {:?}", path, error)]
     ObjectSaveError {
@@ -161,4 +186,37 @@ pub enum Error {
 
     #[fail(display = "Not implemented, story: {}", story)]
     NotImplemented { story: String },
+
+    /// The previous state hash of a transaction does not match its previous state.
+    #[fail(
+        display = "The state chain of transaction {} is broken at state {}.",
+        tx_id, state
+    )]
+    StateChainBroken { tx_id: u32, state: String },
+
+    /// The account of a user has not been validated yet.
+    #[fail(
+        display = "The account of user {} has not been validated yet. Validate it first.",
+        user
+    )]
+    AccountNotValidated { user: String },
+
+    /// A transfer transaction is being finalized, but it has not been initialized.
+    #[fail(
+        display = "Transaction {} of sender {} has not been initialized.",
+        tx_id, sender
+    )]
+    MissingInitialization { tx_id: u32, sender: String },
+
+    /// A verification did not finish within its time limit.
+    #[fail(display = "The verification did not finish within {:?}.", timeout)]
+    VerificationTimeout { timeout: Duration },
+
+    /// A verification was aborted before returning a result.
+    #[fail(display = "The verification was aborted.")]
+    VerificationAborted,
+
+    /// A different object is already saved at the path of a write-once object.
+    #[fail(display = "A different object already exists at {:?}", path)]
+    ObjectAlreadyExists { path: PathBuf },
 }
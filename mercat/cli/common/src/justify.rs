@@ -1,7 +1,7 @@
 use crate::{
     compute_enc_pending_balance, confidential_transaction_file, construct_path,
     create_rng_from_seed, errors::Error, last_ordering_state, load_object, non_empty_account_id,
-    retrieve_auditors_by_names, save_object, user_public_account_balance_file,
+    retrieve_auditors_by_names, save_object, state_hash, user_public_account_balance_file,
     user_public_account_file, OrderedPubAccount, OrderedTransferInstruction, TransferInstruction,
     COMMON_OBJECTS_DIR, MEDIATOR_PUBLIC_ACCOUNT_FILE, OFF_CHAIN_DIR, ON_CHAIN_DIR,
     SECRET_ACCOUNT_FILE,
@@ -215,6 +215,7 @@ pub fn justify_asset_transfer_transaction(
             data: asset_tx.encode().to_vec(),
             state: rejected_state,
             auditors: auditors.to_vec(),
+            prev_state_hash: state_hash(&instruction),
         };
 
         save_object(
@@ -238,6 +239,7 @@ pub fn justify_asset_transfer_transaction(
             data: justified_tx.encode().to_vec(),
             state: new_state,
             auditors: auditors.to_vec(),
+            prev_state_hash: state_hash(&instruction),
         };
 
         save_object(
@@ -1,5 +1,6 @@
 //! A common library for utility functions.
 
+pub mod account_close;
 pub mod account_create;
 pub mod account_issue;
 pub mod account_transfer;
@@ -8,10 +9,13 @@ pub mod chain_setup;
 pub mod errors;
 mod harness;
 pub mod justify;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+pub mod store;
 pub mod validate;
 
 use codec::{Decode, Encode};
-use cryptography_core::asset_proofs::CipherText;
+use cryptography_core::asset_proofs::{decryption_proof::DecryptionProof, CipherText};
 use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT, scalar::Scalar};
 use errors::Error;
 use log::{debug, error, info};
@@ -26,15 +30,23 @@ use rand::{rngs::StdRng, Rng, SeedableRng};
 use rand::{CryptoRng, RngCore};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha3::{digest::FixedOutput, Digest, Sha3_256};
 use std::{
     collections::HashMap,
     convert::{TryFrom, TryInto},
     fmt,
     fs::{create_dir_all, File},
     hash::Hash,
-    io::BufReader,
+    io::{self, BufReader},
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant},
 };
+use store::{FileStore, ObjectStore};
 
 pub const ON_CHAIN_DIR: &str = "on-chain";
 pub const OFF_CHAIN_DIR: &str = "off-chain";
@@ -49,6 +61,22 @@ pub const USER_ACCOUNT_MAP: &str = "user_ticker_to_account_id.json";
 pub const TRANSACTION_NAME_ID_MAP: &str = "transaction_name_to_id.json";
 pub const LAST_VALIDATED_TX_ID_FILE: &str = "last_validated_tx_id_file.json";
 
+/// The previous state hash of the first state of a transaction.
+pub const GENESIS_STATE_HASH: [u8; 32] = [0u8; 32];
+
+/// The wall-clock limit of a single verification by the validator.
+pub const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The maximum number of verification threads that run at the same time, including the
+/// ones that have timed out and still run in the background.
+pub const MAX_VERIFICATION_THREADS: usize = 4;
+
+/// How often a verification that waits for a free thread checks again.
+const VERIFICATION_SLOT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The number of running verification threads.
+static VERIFICATION_THREADS: AtomicUsize = AtomicUsize::new(0);
+
 /// A wrapper around MERCAT api which holds the transaction data, the transaction id,
 /// and the user who initiated the transaction. Some transactions also hold the
 /// ordering state.
@@ -85,6 +113,10 @@ pub enum CoreTransaction {
         tx_id: u32,
         auditors: Vec<String>,
     },
+    AccountClose {
+        closure_tx: AccountClosureTx,
+        tx_id: u32,
+    },
     Invalid,
 }
 
@@ -94,7 +126,8 @@ impl CoreTransaction {
         match self {
             CoreTransaction::Account { .. }
             | CoreTransaction::IssueInit { .. }
-            | CoreTransaction::TransferJustify { .. } => true,
+            | CoreTransaction::TransferJustify { .. }
+            | CoreTransaction::AccountClose { .. } => true,
             _ => false,
         }
     }
@@ -142,23 +175,26 @@ pub enum Direction {
     Outgoing,
 }
 
-/// A wrapper that hides the validation error and only keeps the result of the validation.
+/// A wrapper that keeps the result of the validation, and the description of the
+/// validation error, if any.
 #[derive(Clone, Debug)]
 pub struct ValidationResult {
     user: String,
     ticker: String,
     direction: Direction,
     amount: Option<EncryptedAmount>,
+    error: Option<String>,
 }
 
 impl ValidationResult {
     /// Creates the error value. An amount of None, indicates that an error has occurred.
-    fn error(user: &str, ticker: &str) -> Self {
+    fn error(user: &str, ticker: &str, error: &Error) -> Self {
         Self {
             user: user.to_string(),
             ticker: ticker.to_string(),
             direction: Direction::Incoming,
             amount: None,
+            error: Some(error.to_string()),
         }
     }
 }
@@ -198,6 +234,14 @@ pub struct OrderedPubAccountTx {
     pub account_tx: PubAccountTx,
 }
 
+/// The request of a user to close their account, see `account_close::process_close_account`.
+#[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
+pub struct AccountClosureTx {
+    pub account_id: EncryptedAssetId,
+    /// The proof that the validated balance of the account is zero.
+    pub zero_balance_proof: DecryptionProof,
+}
+
 /// Used for issue asset transaction.
 #[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
 pub struct OrderedAssetInstruction {
@@ -205,6 +249,8 @@ pub struct OrderedAssetInstruction {
     pub amount: u32,
     pub ordering_state: OrderingState,
     pub auditors: Vec<String>,
+    /// The hash of the previous state of this transaction, or `GENESIS_STATE_HASH`.
+    pub prev_state_hash: [u8; 32],
     #[serde(with = "serde_bytes")]
     pub data: Vec<u8>,
 }
@@ -213,6 +259,8 @@ pub struct OrderedAssetInstruction {
 #[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
 pub struct AssetInstruction {
     pub state: AssetTxState,
+    /// The hash of the previous state of this transaction, or `GENESIS_STATE_HASH`.
+    pub prev_state_hash: [u8; 32],
     #[serde(with = "serde_bytes")]
     pub data: Vec<u8>,
 }
@@ -223,6 +271,8 @@ pub struct OrderedTransferInstruction {
     pub state: TransferTxState,
     pub ordering_state: OrderingState,
     pub auditors: Vec<String>,
+    /// The hash of the previous state of this transaction, or `GENESIS_STATE_HASH`.
+    pub prev_state_hash: [u8; 32],
     #[serde(with = "serde_bytes")]
     pub data: Vec<u8>,
 }
@@ -232,10 +282,33 @@ pub struct OrderedTransferInstruction {
 pub struct TransferInstruction {
     pub state: TransferTxState,
     pub auditors: Vec<String>,
+    /// The hash of the previous state of this transaction, or `GENESIS_STATE_HASH`.
+    pub prev_state_hash: [u8; 32],
     #[serde(with = "serde_bytes")]
     pub data: Vec<u8>,
 }
 
+/// Returns the hash of a transaction state object. Each state of a transaction stores the
+/// hash of its previous state in `prev_state_hash` to detect skipped or tampered steps.
+pub fn state_hash<T: Encode>(state: &T) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&Sha3_256::default().chain(state.encode()).fixed_result());
+    hash
+}
+
+/// Checks that `prev_state_hash` is the hash of the `prev_state` object.
+pub fn ensure_state_chain<T: Encode>(
+    prev_state_hash: &[u8; 32],
+    prev_state: &T,
+    tx_id: u32,
+    state: String,
+) -> Result<(), Error> {
+    if *prev_state_hash != state_hash(prev_state) {
+        return Err(Error::StateChainBroken { tx_id, state });
+    }
+    Ok(())
+}
+
 #[derive(PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PrintableAccountId(pub Vec<u8>);
 
@@ -311,6 +384,11 @@ pub fn account_create_transaction_file(tx_id: u32, user: &str, ticker: &str) ->
     format!("tx_{}_{}_ticker#{}.json", tx_id, user, ticker)
 }
 
+#[inline]
+pub fn account_close_transaction_file(tx_id: u32, user: &str, ticker: &str) -> String {
+    format!("tx_{}_{}_close#{}.json", tx_id, user, ticker)
+}
+
 #[inline]
 pub fn user_public_account_file(ticker: &str) -> String {
     format!("{}_{}", ticker, VALIDATED_PUBLIC_ACCOUNT_FILE)
@@ -392,17 +470,24 @@ impl Recorder for PrintRecorder {
     }
 }
 
-#[cfg(feature = "std")]
+#[cfg(all(feature = "std", not(feature = "prometheus")))]
 pub fn init_print_logger() {
     let recorder = PrintRecorder::default();
     metrics::set_boxed_recorder(Box::new(recorder)).unwrap()
 }
 
-#[cfg(not(feature = "std"))]
+#[cfg(not(any(feature = "std", feature = "prometheus")))]
 pub fn init_print_logger() {
     metrics::set_recorder(&RECORDER).unwrap()
 }
 
+/// With the `prometheus` feature, the metrics are collected for the Prometheus export
+/// instead of being printed.
+#[cfg(feature = "prometheus")]
+pub fn init_print_logger() {
+    prometheus::init_prometheus_recorder()
+}
+
 // -------------------------------------- Metric recording ------------------------------------------------
 
 /// Utility function to construct the path based user name, file name, and whether the file
@@ -498,12 +583,13 @@ pub struct AssetIdList(pub Vec<Scalar>);
 /// Utility function to read the asset ids from the database directory.
 #[inline]
 pub fn get_asset_ids(db_dir: PathBuf) -> Result<Vec<Scalar>, Error> {
-    let file_path = construct_path(db_dir, ON_CHAIN_DIR, COMMON_OBJECTS_DIR, ASSET_ID_LIST_FILE);
-    let file = File::open(file_path).map_err(|error| Error::FileReadError {
-        error,
-        path: ASSET_ID_LIST_FILE.into(),
-    })?;
-    let mut de = serde_json::Deserializer::from_reader(file);
+    get_asset_ids_in(&FileStore::new(db_dir))
+}
+
+/// Same as `get_asset_ids`, but reads the asset ids from `store`.
+pub fn get_asset_ids_in(store: &dyn ObjectStore) -> Result<Vec<Scalar>, Error> {
+    let data = store.load(ON_CHAIN_DIR, COMMON_OBJECTS_DIR, ASSET_ID_LIST_FILE)?;
+    let mut de = serde_json::Deserializer::from_slice(&data);
 
     let valid_asset_ids =
         AssetIdList::deserialize(&mut de).map_err(|_| Error::AssetIdListDeserializeError {
@@ -521,24 +607,88 @@ pub fn save_object<T: Encode>(
     file_name: &str,
     data: &T,
 ) -> Result<(), Error> {
-    let mut file_path = db_dir;
-    file_path.push(on_off_chain);
-    file_path.push(user);
+    save_object_in(&FileStore::new(db_dir), on_off_chain, user, file_name, data)
+}
 
-    // The file_path is now the path to the user directory. Create it if it does not exist.
-    create_dir_all(file_path.clone()).map_err(|error| Error::FileCreationError {
-        error,
-        path: file_path.clone(),
-    })?;
+/// Same as `save_object`, but saves the object to `store`.
+pub fn save_object_in<T: Encode>(
+    store: &dyn ObjectStore,
+    on_off_chain: &str,
+    user: &str,
+    file_name: &str,
+    data: &T,
+) -> Result<(), Error> {
+    let bytes = encode_object(data);
+    store.save(on_off_chain, user, file_name, &bytes)
+}
 
-    file_path.push(file_name);
+/// Same as `save_object`, but never replaces a different object. Saving an object that is
+/// identical to the one already at the path is a no-op, while saving a different one returns
+/// `Error::ObjectAlreadyExists`.
+pub fn save_object_if_absent<T: Encode>(
+    db_dir: PathBuf,
+    on_off_chain: &str,
+    user: &str,
+    file_name: &str,
+    data: &T,
+) -> Result<(), Error> {
+    save_object_if_absent_in(&FileStore::new(db_dir), on_off_chain, user, file_name, data)
+}
 
-    std::fs::write(file_path.clone(), data.encode()).map_err(|error| Error::ObjectSaveError {
-        error,
-        path: file_path,
-    })?;
+/// Same as `save_object_if_absent`, but saves the object to `store`.
+pub fn save_object_if_absent_in<T: Encode>(
+    store: &dyn ObjectStore,
+    on_off_chain: &str,
+    user: &str,
+    file_name: &str,
+    data: &T,
+) -> Result<(), Error> {
+    let bytes = encode_object(data);
 
-    Ok(())
+    match store.load(on_off_chain, user, file_name) {
+        Ok(existing) if existing == bytes => Ok(()),
+        Ok(_) => Err(Error::ObjectAlreadyExists {
+            path: construct_path(PathBuf::new(), on_off_chain, user, file_name),
+        }),
+        Err(Error::FileReadError { error, .. }) if error.kind() == io::ErrorKind::NotFound => {
+            store.save(on_off_chain, user, file_name, &bytes)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// The magic bytes that start the objects saved by `save_object`, followed by the
+/// `OBJECT_FORMAT_VERSION`. Files that do not start with them are rejected.
+pub const OBJECT_MAGIC: [u8; 4] = *b"MRCT";
+
+/// The version of the on-disk object format, see `save_object`.
+pub const OBJECT_FORMAT_VERSION: u8 = 1;
+
+/// The size of the header prepended to the objects saved by `save_object`.
+pub const OBJECT_HEADER_SIZE: usize = OBJECT_MAGIC.len() + 1;
+
+/// The size of the checksum footer appended to the objects saved by `save_object`.
+pub const OBJECT_CHECKSUM_SIZE: usize = 32;
+
+/// Computes the checksum footer of an encoded object. This is a SHA3-256 digest rather than
+/// a CRC32 or a SHA-256 one, since SHA3-256 is the hash function that this crate already
+/// uses for the chain state hash, and it detects corruption just as well.
+fn object_checksum(data: &[u8]) -> [u8; OBJECT_CHECKSUM_SIZE] {
+    let mut checksum = [0u8; OBJECT_CHECKSUM_SIZE];
+    checksum.copy_from_slice(&Sha3_256::default().chain(data).fixed_result());
+    checksum
+}
+
+/// Encodes an object in the on-disk format:
+/// `OBJECT_MAGIC || OBJECT_FORMAT_VERSION || encoded object || checksum`,
+/// where the checksum footer covers everything before it.
+fn encode_object<T: Encode>(data: &T) -> Vec<u8> {
+    let mut bytes = OBJECT_MAGIC.to_vec();
+    bytes.push(OBJECT_FORMAT_VERSION);
+    data.encode_to(&mut bytes);
+    let checksum = object_checksum(&bytes);
+    bytes.extend_from_slice(&checksum);
+    bytes
 }
 
 /// Utility function to read an object that implements the Encode trait from file.
@@ -549,17 +699,57 @@ pub fn load_object<T: Decode>(
     user: &str,
     file_name: &str,
 ) -> Result<T, Error> {
-    let file_path = construct_path(db_dir, on_off_chain, user, file_name);
-    load_object_from(file_path)
+    load_object_in(&FileStore::new(db_dir), on_off_chain, user, file_name)
+}
+
+/// Same as `load_object`, but reads the object from `store`.
+pub fn load_object_in<T: Decode>(
+    store: &dyn ObjectStore,
+    on_off_chain: &str,
+    user: &str,
+    file_name: &str,
+) -> Result<T, Error> {
+    let data = store.load(on_off_chain, user, file_name)?;
+    decode_object(
+        &data,
+        construct_path(PathBuf::new(), on_off_chain, user, file_name),
+    )
 }
 
 /// Utility function to read an object that implements the Encode trait from file.
+/// The header and the checksum footer are verified before decoding, and
+/// `Error::ObjectChecksumMismatch` is returned if the file is corrupted.
 #[inline]
 pub fn load_object_from<T: Decode>(file_path: PathBuf) -> Result<T, Error> {
     let data = std::fs::read(file_path.clone()).map_err(|error| Error::FileReadError {
         error,
         path: file_path.clone(),
     })?;
+    decode_object(&data, file_path)
+}
+
+/// Verifies the header and the checksum footer of an encoded object and decodes it. Returns
+/// `Error::MissingObjectHeader` if the data does not start with `OBJECT_MAGIC`. The
+/// `file_path` is only used in the errors.
+fn decode_object<T: Decode>(data: &[u8], file_path: PathBuf) -> Result<T, Error> {
+    if !data.starts_with(&OBJECT_MAGIC) {
+        return Err(Error::MissingObjectHeader { path: file_path });
+    }
+    let version = data.get(OBJECT_MAGIC.len()).copied().unwrap_or_default();
+    if version != OBJECT_FORMAT_VERSION {
+        return Err(Error::UnsupportedObjectFormat {
+            version,
+            path: file_path,
+        });
+    }
+    if data.len() < OBJECT_HEADER_SIZE + OBJECT_CHECKSUM_SIZE {
+        return Err(Error::ObjectChecksumMismatch { path: file_path });
+    }
+    let (data, checksum) = data.split_at(data.len() - OBJECT_CHECKSUM_SIZE);
+    if object_checksum(data)[..] != *checksum {
+        return Err(Error::ObjectChecksumMismatch { path: file_path });
+    }
+    let data = &data[OBJECT_HEADER_SIZE..];
 
     T::decode(&mut &data[..]).map_err(|error| Error::ObjectLoadError {
         error,
@@ -567,6 +757,127 @@ pub fn load_object_from<T: Decode>(file_path: PathBuf) -> Result<T, Error> {
     })
 }
 
+/// Loads the validated public account of a user. Returns `Error::AccountNotValidated` if the
+/// account has not been validated yet, instead of a low-level file read error.
+#[inline]
+pub fn load_validated_account(
+    db_dir: PathBuf,
+    user: &str,
+    ticker: &str,
+) -> Result<OrderedPubAccount, Error> {
+    load_validated_account_in(&FileStore::new(db_dir), user, ticker)
+}
+
+/// Same as `load_validated_account`, but loads the account from `store`.
+pub fn load_validated_account_in(
+    store: &dyn ObjectStore,
+    user: &str,
+    ticker: &str,
+) -> Result<OrderedPubAccount, Error> {
+    load_object_in(store, ON_CHAIN_DIR, user, &user_public_account_file(ticker)).map_err(|error| {
+        match error {
+            Error::FileReadError { error, .. } if error.kind() == io::ErrorKind::NotFound => {
+                Error::AccountNotValidated {
+                    user: user.to_string(),
+                }
+            }
+            error => error,
+        }
+    })
+}
+
+/// Loads the initialization instruction of a transfer transaction. Returns
+/// `Error::MissingInitialization` if the sender has not initialized the transaction, or if the
+/// instruction is not in the `Initialization(Started)` state.
+pub fn load_transfer_initialization(
+    db_dir: PathBuf,
+    tx_id: u32,
+    sender: &str,
+) -> Result<OrderedTransferInstruction, Error> {
+    let state = TransferTxState::Initialization(TxSubstate::Started);
+    let missing_initialization = || Error::MissingInitialization {
+        tx_id,
+        sender: sender.to_string(),
+    };
+
+    let file_path = construct_path(
+        db_dir,
+        ON_CHAIN_DIR,
+        COMMON_OBJECTS_DIR,
+        &confidential_transaction_file(tx_id, sender, state),
+    );
+    if !file_path.exists() {
+        return Err(missing_initialization());
+    }
+    let instruction: OrderedTransferInstruction = load_object_from(file_path)?;
+    if instruction.state != state {
+        return Err(missing_initialization());
+    }
+    Ok(instruction)
+}
+
+/// A slot of the bounded pool of verification threads. The slot is released when the
+/// verification thread finishes, even if the verification panics.
+struct VerificationSlot;
+
+impl VerificationSlot {
+    /// Waits until fewer than `MAX_VERIFICATION_THREADS` verifications are running, and takes
+    /// a slot. Returns `None` if no slot is freed before `deadline`.
+    fn acquire(deadline: Instant) -> Option<Self> {
+        loop {
+            let threads = VERIFICATION_THREADS.load(Ordering::SeqCst);
+            if threads < MAX_VERIFICATION_THREADS
+                && VERIFICATION_THREADS
+                    .compare_exchange(threads, threads + 1, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_ok()
+            {
+                return Some(VerificationSlot);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            thread::sleep(VERIFICATION_SLOT_POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for VerificationSlot {
+    fn drop(&mut self) {
+        VERIFICATION_THREADS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Runs the verification `f` on a separate thread, and returns `Error::VerificationTimeout`
+/// if it does not finish within `timeout`. This protects the validator from pathological
+/// inputs that take too long to verify.
+///
+/// Note that a thread cannot be cancelled safely. Therefore, on timeout the verification
+/// thread is detached and runs to completion in the background, but its result is dropped.
+/// To keep such threads from piling up, at most `MAX_VERIFICATION_THREADS` verifications
+/// run at the same time. A verification that finds all of them busy waits for one to
+/// finish, and the wait counts towards its `timeout`.
+pub fn verify_with_timeout<T, F>(f: F, timeout: Duration) -> Result<T, Error>
+where
+    F: FnOnce() -> cryptography_core::asset_proofs::errors::Fallible<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let deadline = Instant::now() + timeout;
+    let slot = VerificationSlot::acquire(deadline).ok_or(Error::VerificationTimeout { timeout })?;
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _slot = slot;
+        // The receiver is gone if the verification has timed out.
+        let _ = sender.send(f());
+    });
+
+    match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        Ok(result) => result.map_err(|error| Error::LibraryError { error }),
+        Err(RecvTimeoutError::Timeout) => Err(Error::VerificationTimeout { timeout }),
+        Err(RecvTimeoutError::Disconnected) => Err(Error::VerificationAborted),
+    }
+}
+
 /// Helper function to save a config file to `cfg_path`.
 pub fn save_config<T>(cfg_path: Option<PathBuf>, cfg: &T)
 where
@@ -615,9 +926,16 @@ pub fn create_rng_from_seed(seed: Option<String>) -> Result<StdRng, Error> {
 /// Reads the account mapping from disk. Returns a map of account id to (user_name, ticker, tx_id).
 #[inline]
 pub fn load_account_map(db_dir: PathBuf) -> HashMap<String, (String, String, u32)> {
-    let mapping: Result<HashMap<String, (String, String, u32)>, Error> =
-        load_from_file(db_dir, OFF_CHAIN_DIR, COMMON_OBJECTS_DIR, USER_ACCOUNT_MAP);
-    mapping.unwrap_or_default()
+    load_account_map_in(&FileStore::new(db_dir))
+}
+
+/// Same as `load_account_map`, but reads the account mapping from `store`.
+pub fn load_account_map_in(store: &dyn ObjectStore) -> HashMap<String, (String, String, u32)> {
+    store
+        .load(OFF_CHAIN_DIR, COMMON_OBJECTS_DIR, USER_ACCOUNT_MAP)
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
 }
 
 /// Updates the account mapping file with a new record.
@@ -649,7 +967,15 @@ pub fn get_user_ticker_from(
     account_id: EncryptedAssetId,
     db_dir: PathBuf,
 ) -> Result<(String, String, u32), Error> {
-    let mapping = load_account_map(db_dir);
+    get_user_ticker_in(&FileStore::new(db_dir), account_id)
+}
+
+/// Same as `get_user_ticker_from`, but reads the account mapping from `store`.
+pub fn get_user_ticker_in(
+    store: &dyn ObjectStore,
+    account_id: EncryptedAssetId,
+) -> Result<(String, String, u32), Error> {
+    let mapping = load_account_map_in(store);
     let (user, ticker, tx_id) = mapping
         .get(&PrintableAccountId(account_id.encode()).to_string())
         .ok_or(Error::AccountIdNotFound {
@@ -1056,6 +1382,11 @@ pub fn load_tx_file(
             tx_id,
             ordering_state: ordered_account_tx.ordering_state,
         }
+    } else if state.starts_with("close#") {
+        CoreTransaction::AccountClose {
+            closure_tx: load_object_from(PathBuf::from(tx_file_path))?,
+            tx_id,
+        }
     } else {
         return Err(Error::InvalidTransactionFile { path: tx_file_path });
     };
@@ -1065,12 +1396,20 @@ pub fn load_tx_file(
 pub fn retrieve_auditors_by_names(
     auditors: &[String],
     db_dir: PathBuf,
+) -> Result<Vec<AuditorPubAccount>, Error> {
+    retrieve_auditors_by_names_in(auditors, &FileStore::new(db_dir))
+}
+
+/// Same as `retrieve_auditors_by_names`, but loads the auditors' accounts from `store`.
+pub fn retrieve_auditors_by_names_in(
+    auditors: &[String],
+    store: &dyn ObjectStore,
 ) -> Result<Vec<AuditorPubAccount>, Error> {
     auditors
         .iter()
         .map(|auditor| {
-            load_object::<AuditorPubAccount>(
-                db_dir.clone(),
+            load_object_in::<AuditorPubAccount>(
+                store,
                 ON_CHAIN_DIR,
                 auditor,
                 AUDITOR_PUBLIC_ACCOUNT_FILE,
@@ -1157,3 +1496,263 @@ pub fn debug_decrypt_base64_account_balance(
         .decrypt(&enc_balance)
         .map_err(|error| Error::LibraryError { error })
 }
+
+// ------------------------------------------------------------------------------------------------
+// -                                            Tests                                             -
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_state_chain() {
+        let tx_id = 1;
+        let init_instruction = OrderedTransferInstruction {
+            state: TransferTxState::Initialization(TxSubstate::Started),
+            ordering_state: OrderingState::new(tx_id),
+            auditors: vec![],
+            prev_state_hash: GENESIS_STATE_HASH,
+            data: vec![1, 2, 3],
+        };
+        let mut finalized_instruction = OrderedTransferInstruction {
+            state: TransferTxState::Finalization(TxSubstate::Started),
+            ordering_state: OrderingState::new(tx_id),
+            auditors: vec![],
+            prev_state_hash: state_hash(&init_instruction),
+            data: vec![4, 5, 6],
+        };
+        let justified_instruction = TransferInstruction {
+            state: TransferTxState::Justification(TxSubstate::Started),
+            auditors: vec![],
+            prev_state_hash: state_hash(&finalized_instruction),
+            data: vec![7, 8, 9],
+        };
+
+        // Positive test.
+        assert!(ensure_state_chain(
+            &finalized_instruction.prev_state_hash,
+            &init_instruction,
+            tx_id,
+            finalized_instruction.state.to_string(),
+        )
+        .is_ok());
+        assert!(ensure_state_chain(
+            &justified_instruction.prev_state_hash,
+            &finalized_instruction,
+            tx_id,
+            justified_instruction.state.to_string(),
+        )
+        .is_ok());
+
+        // Negative test: tamper with the intermediate state.
+        finalized_instruction.data = vec![4, 5, 7];
+        assert!(matches!(
+            ensure_state_chain(
+                &justified_instruction.prev_state_hash,
+                &finalized_instruction,
+                tx_id,
+                justified_instruction.state.to_string(),
+            ),
+            Err(Error::StateChainBroken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_validated_account() {
+        let db_dir = PathBuf::from("chain_dir/unittest/load_validated_account");
+        let ticker = "ACME";
+
+        // Simulate a receiver whose account was created, but never validated.
+        save_to_file(
+            db_dir.clone(),
+            ON_CHAIN_DIR,
+            "bob",
+            &account_create_transaction_file(1, "bob", ticker),
+            &Vec::<u8>::new(),
+        )
+        .unwrap();
+
+        let result = load_validated_account(db_dir.clone(), "bob", ticker);
+        let _ = std::fs::remove_dir_all(&db_dir);
+        assert!(matches!(
+            result,
+            Err(Error::AccountNotValidated { user }) if user == "bob"
+        ));
+    }
+
+    #[test]
+    fn test_load_transfer_initialization() {
+        let db_dir = PathBuf::from("chain_dir/unittest/load_transfer_initialization");
+        let tx_id = 1;
+
+        // Finalizing a transaction that was never initialized.
+        assert!(matches!(
+            load_transfer_initialization(db_dir.clone(), tx_id, "alice"),
+            Err(Error::MissingInitialization { tx_id: 1, sender }) if sender == "alice"
+        ));
+
+        // An initialization instruction that was saved under a mismatching state.
+        let state = TransferTxState::Initialization(TxSubstate::Started);
+        let mut instruction = OrderedTransferInstruction {
+            state: TransferTxState::Finalization(TxSubstate::Started),
+            ordering_state: OrderingState::new(tx_id),
+            auditors: vec![],
+            prev_state_hash: GENESIS_STATE_HASH,
+            data: vec![1, 2, 3],
+        };
+        let file_name = confidential_transaction_file(tx_id, "alice", state);
+        save_object(
+            db_dir.clone(),
+            ON_CHAIN_DIR,
+            COMMON_OBJECTS_DIR,
+            &file_name,
+            &instruction,
+        )
+        .unwrap();
+        let result = load_transfer_initialization(db_dir.clone(), tx_id, "alice");
+        assert!(matches!(result, Err(Error::MissingInitialization { .. })));
+
+        // Positive test.
+        instruction.state = state;
+        save_object(
+            db_dir.clone(),
+            ON_CHAIN_DIR,
+            COMMON_OBJECTS_DIR,
+            &file_name,
+            &instruction,
+        )
+        .unwrap();
+        let result = load_transfer_initialization(db_dir.clone(), tx_id, "alice");
+        let _ = std::fs::remove_dir_all(&db_dir);
+        assert_eq!(result.unwrap().data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_verify_with_timeout() {
+        let timeout = Duration::from_millis(50);
+
+        assert_eq!(verify_with_timeout(|| Ok(42), timeout).unwrap(), 42);
+
+        let result = verify_with_timeout(
+            || {
+                thread::sleep(Duration::from_millis(500));
+                Ok(())
+            },
+            timeout,
+        );
+        assert!(matches!(result, Err(Error::VerificationTimeout { .. })));
+
+        let result: Result<(), Error> = verify_with_timeout(|| panic!("aborted"), timeout);
+        assert!(matches!(result, Err(Error::VerificationAborted)));
+    }
+
+    #[test]
+    fn test_verify_with_timeout_bounds_the_threads() {
+        let timeout = Duration::from_millis(50);
+        let runtime = Duration::from_millis(500);
+
+        // The verifications that time out keep their threads until they finish.
+        for _ in 0..MAX_VERIFICATION_THREADS {
+            let result = verify_with_timeout(
+                move || {
+                    thread::sleep(runtime);
+                    Ok(())
+                },
+                timeout,
+            );
+            assert!(matches!(result, Err(Error::VerificationTimeout { .. })));
+        }
+
+        // While they run, no new verification thread is started.
+        let started = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = started.clone();
+        let result = verify_with_timeout(
+            move || {
+                flag.store(true, Ordering::SeqCst);
+                Ok(())
+            },
+            timeout,
+        );
+        assert!(matches!(result, Err(Error::VerificationTimeout { .. })));
+        assert!(!started.load(Ordering::SeqCst));
+
+        // A verification that waits long enough for a free thread succeeds.
+        assert_eq!(verify_with_timeout(|| Ok(42), runtime * 2).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_object_checksum() {
+        let db_dir = PathBuf::from("chain_dir/unittest/checksum");
+        let _ = std::fs::remove_dir_all(&db_dir);
+
+        let object: Vec<u32> = vec![1, 2, 3];
+        save_object(db_dir.clone(), ON_CHAIN_DIR, "alice", "object", &object).unwrap();
+        let loaded: Vec<u32> =
+            load_object(db_dir.clone(), ON_CHAIN_DIR, "alice", "object").unwrap();
+        assert_eq!(loaded, object);
+
+        // Flip a bit of the encoded object.
+        let file_path = construct_path(db_dir.clone(), ON_CHAIN_DIR, "alice", "object");
+        let mut data = std::fs::read(&file_path).unwrap();
+        data[OBJECT_HEADER_SIZE + 1] ^= 1;
+        std::fs::write(&file_path, &data).unwrap();
+        let result: Result<Vec<u32>, Error> =
+            load_object(db_dir.clone(), ON_CHAIN_DIR, "alice", "object");
+        assert!(matches!(result, Err(Error::ObjectChecksumMismatch { .. })));
+
+        // A truncated file is also detected.
+        std::fs::write(&file_path, &data[..OBJECT_HEADER_SIZE + 2]).unwrap();
+        let result: Result<Vec<u32>, Error> =
+            load_object(db_dir.clone(), ON_CHAIN_DIR, "alice", "object");
+        assert!(matches!(result, Err(Error::ObjectChecksumMismatch { .. })));
+
+        // An unknown format version is rejected.
+        let mut data = encode_object(&object);
+        data[OBJECT_MAGIC.len()] = OBJECT_FORMAT_VERSION + 1;
+        std::fs::write(&file_path, data).unwrap();
+        let result: Result<Vec<u32>, Error> =
+            load_object(db_dir.clone(), ON_CHAIN_DIR, "alice", "object");
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedObjectFormat { version: 2, .. })
+        ));
+
+        // So is an object saved without the header and the footer.
+        std::fs::write(&file_path, object.encode()).unwrap();
+        let result: Result<Vec<u32>, Error> =
+            load_object(db_dir.clone(), ON_CHAIN_DIR, "alice", "object");
+
+        let _ = std::fs::remove_dir_all(&db_dir);
+        assert!(matches!(result, Err(Error::MissingObjectHeader { .. })));
+    }
+
+    #[test]
+    fn test_save_object_if_absent() {
+        let store = store::MemStore::new();
+        let object: Vec<u32> = vec![1, 2, 3];
+        save_object_if_absent_in(&store, ON_CHAIN_DIR, "alice", "object", &object).unwrap();
+
+        // Re-saving the same object is a no-op.
+        save_object_if_absent_in(&store, ON_CHAIN_DIR, "alice", "object", &object).unwrap();
+
+        // A different object is not saved over it.
+        let other: Vec<u32> = vec![4, 5, 6];
+        let result = save_object_if_absent_in(&store, ON_CHAIN_DIR, "alice", "object", &other);
+        assert!(matches!(result, Err(Error::ObjectAlreadyExists { .. })));
+        let loaded: Vec<u32> = load_object_in(&store, ON_CHAIN_DIR, "alice", "object").unwrap();
+        assert_eq!(loaded, object);
+
+        // The same holds for the objects on disk.
+        let db_dir = PathBuf::from("chain_dir/unittest/save_if_absent");
+        let _ = std::fs::remove_dir_all(&db_dir);
+        save_object_if_absent(db_dir.clone(), ON_CHAIN_DIR, "alice", "object", &object).unwrap();
+        save_object_if_absent(db_dir.clone(), ON_CHAIN_DIR, "alice", "object", &object).unwrap();
+        let result = save_object_if_absent(db_dir.clone(), ON_CHAIN_DIR, "alice", "object", &other);
+
+        let _ = std::fs::remove_dir_all(&db_dir);
+        assert!(matches!(result, Err(Error::ObjectAlreadyExists { .. })));
+    }
+}
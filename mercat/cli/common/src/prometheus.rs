@@ -0,0 +1,202 @@
+//! Collects the metrics that are recorded with the `metrics` macros, e.g., `timing!`, and
+//! exports them in the Prometheus text exposition format.
+
+use crate::errors::Error;
+use lazy_static::lazy_static;
+use log::info;
+use metrics::Recorder;
+use metrics_core::Key;
+use std::{collections::BTreeMap, fs, path::PathBuf, sync::Mutex, sync::Once};
+
+/// The value of a single metric series.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Metric {
+    Counter(u64),
+    Gauge(i64),
+    /// Histograms, e.g., the timings, are exported as summaries of their count and sum.
+    Histogram {
+        count: u64,
+        sum: u64,
+    },
+}
+
+lazy_static! {
+    /// All the recorded series, keyed by the metric name and its rendered labels.
+    static ref REGISTRY: Mutex<BTreeMap<(String, String), Metric>> = Mutex::new(BTreeMap::new());
+}
+
+static RECORDER: PrometheusRecorder = PrometheusRecorder;
+static INIT: Once = Once::new();
+
+/// Records the metrics in the global registry, from which `render_metrics` exports them.
+#[derive(Default)]
+pub struct PrometheusRecorder;
+
+impl PrometheusRecorder {
+    fn update<F: FnOnce(Option<Metric>) -> Metric>(&self, key: Key, f: F) {
+        let (name, labels) = key.into_parts();
+        let name = sanitize_name(&name);
+        let labels = labels
+            .iter()
+            .map(|label| {
+                format!(
+                    "{}=\"{}\"",
+                    sanitize_name(label.key()),
+                    escape_label_value(label.value())
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+
+        let mut registry = REGISTRY.lock().expect("The metrics registry is poisoned!");
+        let entry = (name, labels);
+        let metric = f(registry.get(&entry).copied());
+        registry.insert(entry, metric);
+    }
+}
+
+impl Recorder for PrometheusRecorder {
+    fn increment_counter(&self, key: Key, value: u64) {
+        self.update(key, |metric| match metric {
+            Some(Metric::Counter(total)) => Metric::Counter(total + value),
+            _ => Metric::Counter(value),
+        });
+    }
+
+    fn update_gauge(&self, key: Key, value: i64) {
+        self.update(key, |_| Metric::Gauge(value));
+    }
+
+    fn record_histogram(&self, key: Key, value: u64) {
+        self.update(key, |metric| match metric {
+            Some(Metric::Histogram { count, sum }) => Metric::Histogram {
+                count: count + 1,
+                sum: sum + value,
+            },
+            _ => Metric::Histogram {
+                count: 1,
+                sum: value,
+            },
+        });
+    }
+}
+
+/// Installs the `PrometheusRecorder` as the global metrics recorder. It is safe to call this
+/// more than once.
+pub fn init_prometheus_recorder() {
+    INIT.call_once(|| {
+        metrics::set_recorder(&RECORDER).expect("Another metrics recorder is already installed!")
+    });
+}
+
+/// Renders all the recorded metrics in the Prometheus text exposition format.
+pub fn render_metrics() -> String {
+    let registry = REGISTRY.lock().expect("The metrics registry is poisoned!");
+    let mut output = String::new();
+    let mut last_name: Option<&str> = None;
+    for ((name, labels), metric) in registry.iter() {
+        if last_name != Some(name) {
+            let metric_type = match metric {
+                Metric::Counter(_) => "counter",
+                Metric::Gauge(_) => "gauge",
+                Metric::Histogram { .. } => "summary",
+            };
+            output.push_str(&format!("# TYPE {} {}\n", name, metric_type));
+            last_name = Some(name);
+        }
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", labels)
+        };
+        match metric {
+            Metric::Counter(value) => output.push_str(&format!("{}{} {}\n", name, labels, value)),
+            Metric::Gauge(value) => output.push_str(&format!("{}{} {}\n", name, labels, value)),
+            Metric::Histogram { count, sum } => {
+                output.push_str(&format!("{}_count{} {}\n", name, labels, count));
+                output.push_str(&format!("{}_sum{} {}\n", name, labels, sum));
+            }
+        }
+    }
+    output
+}
+
+/// Writes all the recorded metrics to `path`, e.g., for the textfile collector of the
+/// Prometheus node exporter.
+pub fn write_metrics(path: PathBuf) -> Result<(), Error> {
+    info!("Writing the metrics to {:?}", path);
+    fs::write(&path, render_metrics()).map_err(|error| Error::FileCreationError { error, path })
+}
+
+/// Replaces the characters that are not allowed in Prometheus names, e.g., the dots in
+/// `validator.account`, with underscores.
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+// ------------------------------------------------------------------------------------------------
+// -                                            Tests                                             -
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        account_create::process_create_account, chain_setup::process_asset_id_creation, gen_seed,
+        init_print_logger, validate::validate_all_pending,
+    };
+
+    #[test]
+    fn test_prometheus_recorder_captures_timings() {
+        let db_dir = PathBuf::from("chain_dir/unittest/prometheus");
+        let _ = std::fs::remove_dir_all(&db_dir);
+        init_print_logger();
+
+        process_asset_id_creation(db_dir.clone(), vec![String::from("ACME")]).unwrap();
+        process_create_account(
+            Some(gen_seed()),
+            db_dir.clone(),
+            String::from("ACME"),
+            String::from("alice"),
+            false,
+            1,
+            false,
+        )
+        .unwrap();
+        validate_all_pending(db_dir.clone()).unwrap();
+
+        let mut metrics_file = db_dir.clone();
+        metrics_file.push("metrics.prom");
+        write_metrics(metrics_file.clone()).unwrap();
+        let written = fs::read_to_string(&metrics_file);
+        let _ = std::fs::remove_dir_all(&db_dir);
+
+        let metrics = render_metrics();
+        assert!(metrics.contains("# TYPE validator_account summary\n"));
+        assert!(metrics.contains("validator_account_count{tx_id=\"1\"} "));
+        assert!(metrics.contains("validator_account_sum{tx_id=\"1\"} "));
+        assert!(written.unwrap().contains("validator_account_count"));
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("validator.account"), "validator_account");
+        assert_eq!(sanitize_name("tx_id"), "tx_id");
+        assert_eq!(escape_label_value("a\"b\\c"), "a\\\"b\\\\c");
+    }
+}
@@ -0,0 +1,204 @@
+//! The storage backends of the on-chain and off-chain objects.
+//!
+//! The CLIs save every object under `<db_dir>/<on_off_chain>/<user>/<file_name>`. The
+//! `ObjectStore` trait abstracts that layout, so that the validation logic can run against
+//! an alternate directory layout, from a tar archive of the database directory, or entirely
+//! in memory in the tests.
+
+use crate::{construct_path, errors::Error};
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, File},
+    io::{self, Read, Seek, SeekFrom},
+    path::{Component, PathBuf},
+    sync::Mutex,
+};
+use tar::Archive;
+
+/// A key-value store of the encoded objects, addressed by whether the object is stored on or
+/// off chain, the user directory, and the file name.
+pub trait ObjectStore {
+    /// Returns the raw bytes of an object.
+    fn load(&self, on_off_chain: &str, user: &str, file_name: &str) -> Result<Vec<u8>, Error>;
+
+    /// Saves the raw bytes of an object, replacing any previous value.
+    fn save(
+        &self,
+        on_off_chain: &str,
+        user: &str,
+        file_name: &str,
+        data: &[u8],
+    ) -> Result<(), Error>;
+}
+
+/// Stores the objects as files inside the database directory. This is the layout that
+/// all the CLIs use.
+pub struct FileStore {
+    db_dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(db_dir: PathBuf) -> Self {
+        Self { db_dir }
+    }
+}
+
+impl ObjectStore for FileStore {
+    fn load(&self, on_off_chain: &str, user: &str, file_name: &str) -> Result<Vec<u8>, Error> {
+        let file_path = construct_path(self.db_dir.clone(), on_off_chain, user, file_name);
+        std::fs::read(file_path.clone()).map_err(|error| Error::FileReadError {
+            error,
+            path: file_path,
+        })
+    }
+
+    fn save(
+        &self,
+        on_off_chain: &str,
+        user: &str,
+        file_name: &str,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let mut file_path = self.db_dir.clone();
+        file_path.push(on_off_chain);
+        file_path.push(user);
+
+        // The file_path is now the path to the user directory. Create it if it does not exist.
+        create_dir_all(file_path.clone()).map_err(|error| Error::FileCreationError {
+            error,
+            path: file_path.clone(),
+        })?;
+
+        file_path.push(file_name);
+        std::fs::write(file_path.clone(), data).map_err(|error| Error::ObjectSaveError {
+            error,
+            path: file_path,
+        })
+    }
+}
+
+/// Keeps the objects in memory. Useful for running the validator without touching the
+/// filesystem.
+#[derive(Default)]
+pub struct MemStore {
+    objects: Mutex<HashMap<PathBuf, Vec<u8>>>,
+}
+
+impl MemStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ObjectStore for MemStore {
+    fn load(&self, on_off_chain: &str, user: &str, file_name: &str) -> Result<Vec<u8>, Error> {
+        let path = construct_path(PathBuf::new(), on_off_chain, user, file_name);
+        let objects = self.objects.lock().expect("The object store is poisoned!");
+        objects
+            .get(&path)
+            .cloned()
+            .ok_or_else(|| Error::FileReadError {
+                error: io::Error::from(io::ErrorKind::NotFound),
+                path,
+            })
+    }
+
+    fn save(
+        &self,
+        on_off_chain: &str,
+        user: &str,
+        file_name: &str,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let path = construct_path(PathBuf::new(), on_off_chain, user, file_name);
+        let mut objects = self.objects.lock().expect("The object store is poisoned!");
+        objects.insert(path, data.to_vec());
+        Ok(())
+    }
+}
+
+/// Reads the objects from a tar archive of the database directory, without extracting it to
+/// disk. The archive is indexed once, when the store is created, and each load reads only the
+/// bytes of the requested object. The archive is never modified: the saved objects are kept in
+/// memory, and shadow the archived objects of the same path.
+pub struct TarStore<R> {
+    reader: Mutex<R>,
+    /// The position and size of each file in the archive, keyed by its path relative to the
+    /// database directory.
+    index: HashMap<PathBuf, (u64, u64)>,
+    saved: MemStore,
+}
+
+impl TarStore<File> {
+    /// Opens and indexes the tar archive at `path`.
+    pub fn open(path: PathBuf) -> Result<Self, Error> {
+        let file = File::open(&path).map_err(|error| Error::FileReadError { error, path })?;
+        Self::new(file)
+    }
+}
+
+impl<R: Read + Seek> TarStore<R> {
+    /// Indexes the tar archive that is read from `reader`. The paths in the archive are relative
+    /// to the database directory, e.g., as created by `tar -C <db_dir> -cf <archive> .`.
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let archive_error = |error| Error::ArchiveReadError { error };
+        let mut index = HashMap::new();
+        {
+            let mut archive = Archive::new(&mut reader);
+            for entry in archive.entries().map_err(archive_error)? {
+                let entry = entry.map_err(archive_error)?;
+                if !entry.header().entry_type().is_file() {
+                    continue;
+                }
+                // Drop the leading `./`, so that the paths match the keys of `load`.
+                let path = entry
+                    .path()
+                    .map_err(archive_error)?
+                    .components()
+                    .filter(|component| *component != Component::CurDir)
+                    .collect::<PathBuf>();
+                index.insert(path, (entry.raw_file_position(), entry.size()));
+            }
+        }
+        Ok(Self {
+            reader: Mutex::new(reader),
+            index,
+            saved: MemStore::new(),
+        })
+    }
+}
+
+impl<R: Read + Seek> ObjectStore for TarStore<R> {
+    fn load(&self, on_off_chain: &str, user: &str, file_name: &str) -> Result<Vec<u8>, Error> {
+        if let Ok(data) = self.saved.load(on_off_chain, user, file_name) {
+            return Ok(data);
+        }
+        let path = construct_path(PathBuf::new(), on_off_chain, user, file_name);
+        let (position, size) = match self.index.get(&path) {
+            Some(location) => *location,
+            None => {
+                return Err(Error::FileReadError {
+                    error: io::Error::from(io::ErrorKind::NotFound),
+                    path,
+                })
+            }
+        };
+        let mut reader = self.reader.lock().expect("The archive reader is poisoned!");
+        let mut data = vec![0u8; size as usize];
+        reader
+            .seek(SeekFrom::Start(position))
+            .and_then(|_| reader.read_exact(&mut data))
+            .map_err(|error| Error::FileReadError { error, path })?;
+        Ok(data)
+    }
+
+    fn save(
+        &self,
+        on_off_chain: &str,
+        user: &str,
+        file_name: &str,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        self.saved.save(on_off_chain, user, file_name, data)
+    }
+}
@@ -1,13 +1,21 @@
 use crate::{
     account_create_transaction_file, all_unverified_tx_files, asset_transaction_file,
-    compute_enc_pending_balance, confidential_transaction_file, debug_decrypt, errors::Error,
-    get_asset_ids, get_user_ticker_from, last_ordering_state, load_object, load_tx_file,
-    parse_tx_name, retrieve_auditors_by_names, save_object, save_to_file,
-    user_public_account_balance_file, user_public_account_file, AssetInstruction, CoreTransaction,
-    Direction, OrderedPubAccount, OrderedPubAccountTx, PrintableAccountId, TransferInstruction,
-    ValidationResult, COMMON_OBJECTS_DIR, LAST_VALIDATED_TX_ID_FILE, OFF_CHAIN_DIR, ON_CHAIN_DIR,
+    compute_enc_pending_balance, confidential_transaction_file, debug_decrypt, ensure_state_chain,
+    errors::Error,
+    get_asset_ids_in, get_user_ticker_from, get_user_ticker_in, last_ordering_state,
+    load_account_map_in, load_object, load_object_from, load_object_in, load_tx_file,
+    load_validated_account, load_validated_account_in, parse_tx_name, remove_file,
+    retrieve_auditors_by_names_in, save_object, save_object_if_absent_in, save_object_in,
+    save_to_file, state_hash,
+    store::{FileStore, ObjectStore},
+    user_public_account_balance_file, user_public_account_file, verify_with_timeout,
+    AccountClosureTx, AssetInstruction, CoreTransaction, Direction, OrderedAssetInstruction,
+    OrderedPubAccount, OrderedPubAccountTx, OrderedTransferInstruction, PrintableAccountId,
+    TransferInstruction, ValidationResult, COMMON_OBJECTS_DIR, GENESIS_STATE_HASH,
+    LAST_VALIDATED_TX_ID_FILE, OFF_CHAIN_DIR, ON_CHAIN_DIR, VERIFICATION_TIMEOUT,
 };
 use codec::{Decode, Encode};
+use cryptography_core::asset_proofs::BatchLimits;
 use log::{debug, error, info};
 use mercat::{
     account::AccountValidator, asset::AssetValidator, transaction::TransactionValidator,
@@ -17,28 +25,185 @@ use mercat::{
 };
 use metrics::timing;
 use rand::rngs::OsRng;
-use std::{collections::HashSet, path::PathBuf, time::Instant};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+    time::Instant,
+};
 
-fn load_all_unverified_and_ready(db_dir: PathBuf) -> Result<Vec<CoreTransaction>, Error> {
-    all_unverified_tx_files(db_dir)?
+/// Returns the parsed names of all the unverified transaction files, in the order of their
+/// transaction ids. Only the names are read, so this is cheap for any number of files.
+fn unverified_tx_names(db_dir: PathBuf) -> Result<Vec<(u32, String, String, String)>, Error> {
+    let mut tx_names = all_unverified_tx_files(db_dir)?
         .into_iter()
         .map(parse_tx_name)
-        .map(|res| match res {
-            Err(error) => Err(error),
-            Ok((tx_id, user, state, tx_file_path)) => {
-                load_tx_file(tx_id, user, state, tx_file_path)
-            }
-        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    tx_names.sort_by_key(|(tx_id, _, _, _)| *tx_id);
+    Ok(tx_names)
+}
+
+/// Loads the transaction files in `tx_names` and keeps the ones that are ready for validation.
+fn load_ready(tx_names: &[(u32, String, String, String)]) -> Result<Vec<CoreTransaction>, Error> {
+    tx_names
+        .iter()
+        .cloned()
+        .map(|(tx_id, user, state, tx_file_path)| load_tx_file(tx_id, user, state, tx_file_path))
         .filter(|res| res.is_err() || res.as_ref().unwrap().is_ready_for_validation())
         .collect()
 }
 
+/// Computes the pending balance of the sender of the transfer transaction `tx_id`, i.e.,
+/// the balance of the sender's account after all of the sender's earlier pending transfers.
+fn sender_pending_balance(
+    db_dir: PathBuf,
+    sender: &str,
+    ticker: &str,
+    tx_id: u32,
+) -> Result<EncryptedAmount, Error> {
+    let sender_ordered_pub_account: OrderedPubAccount = load_object(
+        db_dir.clone(),
+        ON_CHAIN_DIR,
+        sender,
+        &user_public_account_file(ticker),
+    )?;
+    let sender_account_balance: EncryptedAmount = load_object(
+        db_dir.clone(),
+        ON_CHAIN_DIR,
+        sender,
+        &user_public_account_balance_file(ticker),
+    )?;
+    let ordering_state = last_ordering_state(
+        sender.to_string(),
+        sender_ordered_pub_account.last_processed_tx_counter,
+        tx_id,
+        db_dir.clone(),
+    )?;
+    compute_enc_pending_balance(
+        sender,
+        ordering_state,
+        sender_ordered_pub_account.last_processed_tx_counter,
+        sender_account_balance,
+        db_dir,
+    )
+}
+
+/// The format of the reports that the validator prints after each validation.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OutputFormat {
+    /// Only the human-readable logs.
+    Text,
+    /// One `ValidationReport` JSON object per line.
+    Json,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Text
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Unknown output format: {}", s)),
+        }
+    }
+}
+
+/// The structured result of a single validation, which a supervising process can parse.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidationReport {
+    pub tx_id: u32,
+    pub old_state: String,
+    pub new_state: String,
+    /// Either `ok` or `error`.
+    pub result: String,
+    pub error: Option<String>,
+}
+
+impl ValidationReport {
+    fn new(tx_id: u32, old_state: String, new_state: String, error: Option<String>) -> Self {
+        let (result, new_state) = match error {
+            None => ("ok", new_state),
+            // A failed validation leaves the transaction in its old state.
+            Some(_) => ("error", old_state.clone()),
+        };
+        Self {
+            tx_id,
+            old_state,
+            new_state,
+            result: result.to_string(),
+            error,
+        }
+    }
+
+    fn write<W: Write>(&self, output: OutputFormat, out: &mut W) -> Result<(), Error> {
+        if output == OutputFormat::Json {
+            let report = serde_json::to_string(self).map_err(|_| Error::SerializeError)?;
+            writeln!(out, "{}", report).map_err(|_| Error::SerializeError)?;
+        }
+        Ok(())
+    }
+}
+
+/// The old and new states of an account creation, which do not have a state enum.
+const ACCOUNT_CREATED_STATE: &str = "account-created";
+const ACCOUNT_VALIDATED_STATE: &str = "account-validated";
+/// The old and new states of an account closure.
+const ACCOUNT_CLOSING_STATE: &str = "account-closing";
+const ACCOUNT_CLOSED_STATE: &str = "account-closed";
+
 pub fn validate_all_pending(db_dir: PathBuf) -> Result<(), Error> {
+    validate_all_pending_with_output(db_dir, OutputFormat::Text, &mut io::sink())
+}
+
+/// Same as `validate_all_pending`, but also writes a `ValidationReport` to `out` after each
+/// validation, in the given `output` format.
+pub fn validate_all_pending_with_output<W: Write>(
+    db_dir: PathBuf,
+    output: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    validate_all_pending_with_limits(db_dir, &BatchLimits::default(), output, out)
+}
+
+/// Same as `validate_all_pending_with_output`, but the pending transactions are loaded and
+/// validated in chunks of at most `limits.max_items`, and each chunk updates the accounts
+/// before the next one is loaded. This bounds the memory of the validator, however large
+/// its backlog is.
+pub fn validate_all_pending_with_limits<W: Write>(
+    db_dir: PathBuf,
+    limits: &BatchLimits,
+    output: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
     // TODO: This function should be called when any justify is called. To be fixed in CRYP-131.
-    let all_unverified_and_ready = load_all_unverified_and_ready(db_dir.clone())?;
     let mut last_tx_id: Option<u32> = None;
+    for chunk in unverified_tx_names(db_dir.clone())?.chunks(limits.max_items) {
+        last_tx_id = validate_ready(db_dir.clone(), load_ready(chunk)?, last_tx_id, output, out)?;
+    }
+    Ok(())
+}
 
+/// Validates the transactions in `all_unverified_and_ready`, updates the balances of the
+/// accounts they touch, and returns the largest validated transaction id, starting from
+/// `last_tx_id`.
+fn validate_ready<W: Write>(
+    db_dir: PathBuf,
+    all_unverified_and_ready: Vec<CoreTransaction>,
+    mut last_tx_id: Option<u32>,
+    output: OutputFormat,
+    out: &mut W,
+) -> Result<Option<u32>, Error> {
     let mut results: Vec<ValidationResult> = vec![];
+    let mut closures: Vec<(u32, AccountClosureTx)> = vec![];
     // For each of them call the validate function and process as needed
     for tx in all_unverified_and_ready {
         match tx {
@@ -57,6 +222,13 @@ pub fn validate_all_pending(db_dir: PathBuf) -> Result<(), Error> {
                     tx_id,
                     &auditors,
                 );
+                ValidationReport::new(
+                    tx_id,
+                    AssetTxState::Initialization(TxSubstate::Started).to_string(),
+                    AssetTxState::Justification(TxSubstate::Validated).to_string(),
+                    result.error.clone(),
+                )
+                .write(output, out)?;
                 results.push(result);
                 last_tx_id = Some(std::cmp::max(last_tx_id.unwrap_or_default(), tx_id));
             }
@@ -68,31 +240,8 @@ pub fn validate_all_pending(db_dir: PathBuf) -> Result<(), Error> {
             } => {
                 let account_id = tx.finalized_data.init_data.memo.sender_account_id;
                 let (sender, ticker, _) = get_user_ticker_from(account_id, db_dir.clone())?;
-                let sender_ordered_pub_account: OrderedPubAccount = load_object(
-                    db_dir.clone(),
-                    ON_CHAIN_DIR,
-                    &sender,
-                    &user_public_account_file(&ticker),
-                )?;
-                let sender_account_balance: EncryptedAmount = load_object(
-                    db_dir.clone(),
-                    ON_CHAIN_DIR,
-                    &sender,
-                    &user_public_account_balance_file(&ticker),
-                )?;
-                let ordering_state = last_ordering_state(
-                    sender.clone(),
-                    sender_ordered_pub_account.last_processed_tx_counter,
-                    tx_id,
-                    db_dir.clone(),
-                )?;
-                let pending_balance = compute_enc_pending_balance(
-                    &sender,
-                    ordering_state,
-                    sender_ordered_pub_account.last_processed_tx_counter,
-                    sender_account_balance,
-                    db_dir.clone(),
-                )?;
+                let pending_balance =
+                    sender_pending_balance(db_dir.clone(), &sender, &ticker, tx_id)?;
                 debug!(
                     "------------> validating tx: {}, pending transfer balance: {}",
                     tx_id,
@@ -106,6 +255,16 @@ pub fn validate_all_pending(db_dir: PathBuf) -> Result<(), Error> {
                     tx_id,
                     &auditors,
                 );
+                ValidationReport::new(
+                    tx_id,
+                    TransferTxState::Justification(TxSubstate::Started).to_string(),
+                    TransferTxState::Justification(TxSubstate::Validated).to_string(),
+                    sender_result
+                        .error
+                        .clone()
+                        .or_else(|| receiver_result.error.clone()),
+                )
+                .write(output, out)?;
                 results.push(sender_result);
                 results.push(receiver_result);
                 last_tx_id = Some(std::cmp::max(last_tx_id.unwrap_or_default(), tx_id));
@@ -115,12 +274,24 @@ pub fn validate_all_pending(db_dir: PathBuf) -> Result<(), Error> {
                 tx_id,
                 ordering_state: _,
             } => {
-                if let Err(error) =
-                    validate_account(db_dir.clone(), account_tx.pub_account.enc_asset_id)
-                {
+                let result = validate_account(db_dir.clone(), account_tx.pub_account.enc_asset_id);
+                if let Err(error) = &result {
                     error!("Error in validation of tx-{}: {:#?}", tx_id, error);
                     error!("tx-{}: Ignoring the validation error and continuing the with rest of the validations.", tx_id);
                 }
+                ValidationReport::new(
+                    tx_id,
+                    ACCOUNT_CREATED_STATE.to_string(),
+                    ACCOUNT_VALIDATED_STATE.to_string(),
+                    result.err().map(|error| error.to_string()),
+                )
+                .write(output, out)?;
+                last_tx_id = Some(std::cmp::max(last_tx_id.unwrap_or_default(), tx_id));
+            }
+            CoreTransaction::AccountClose { closure_tx, tx_id } => {
+                // The closures are validated after the balances are updated below, so that
+                // they see the transfers that are validated along with them.
+                closures.push((tx_id, closure_tx));
                 last_tx_id = Some(std::cmp::max(last_tx_id.unwrap_or_default(), tx_id));
             }
             _ => {
@@ -235,6 +406,21 @@ pub fn validate_all_pending(db_dir: PathBuf) -> Result<(), Error> {
         )?;
     }
 
+    for (tx_id, closure_tx) in closures {
+        let result = validate_account_closure(db_dir.clone(), &closure_tx);
+        if let Err(error) = &result {
+            error!("Error in validation of tx-{}: {:#?}", tx_id, error);
+            error!("tx-{}: Ignoring the validation error and continuing the with rest of the validations.", tx_id);
+        }
+        ValidationReport::new(
+            tx_id,
+            ACCOUNT_CLOSING_STATE.to_string(),
+            ACCOUNT_CLOSED_STATE.to_string(),
+            result.err().map(|error| error.to_string()),
+        )
+        .write(output, out)?;
+    }
+
     save_to_file(
         db_dir,
         OFF_CHAIN_DIR,
@@ -242,23 +428,37 @@ pub fn validate_all_pending(db_dir: PathBuf) -> Result<(), Error> {
         LAST_VALIDATED_TX_ID_FILE,
         &last_tx_id,
     )?;
-    Ok(())
+    Ok(last_tx_id)
 }
 
+/// Verifies the initialized asset issuance and saves it under the validated justification
+/// state. Asset issuances are not justified by a mediator, so a single call takes an
+/// issuance from its initialization to its final state.
 pub fn validate_asset_issuance(
     db_dir: PathBuf,
     amount: u32,
     asset_tx: InitializedAssetTx,
     tx_id: u32,
     auditors: &[String],
+) -> ValidationResult {
+    validate_asset_issuance_in(&FileStore::new(db_dir), amount, asset_tx, tx_id, auditors)
+}
+
+/// Same as `validate_asset_issuance`, but loads and saves the objects through `store`.
+pub fn validate_asset_issuance_in(
+    store: &dyn ObjectStore,
+    amount: u32,
+    asset_tx: InitializedAssetTx,
+    tx_id: u32,
+    auditors: &[String],
 ) -> ValidationResult {
     let load_objects_timer = Instant::now();
 
     let issuer_account_id = asset_tx.account_id;
-    let res = get_user_ticker_from(issuer_account_id, db_dir.clone());
+    let res = get_user_ticker_in(store, issuer_account_id);
     if let Err(error) = res {
         error!("Error in validation of tx-{}: {:#?}", tx_id, error);
-        return ValidationResult::error("n/a", "n/a");
+        return ValidationResult::error("n/a", "n/a", &error);
     }
     let (issuer, ticker, _) = res.unwrap();
     info!(
@@ -266,37 +466,58 @@ pub fn validate_asset_issuance(
         tx_id, issuer, ticker,
     );
 
-    let issuer_ordered_pub_account: Result<OrderedPubAccount, Error> = load_object(
-        db_dir.clone(),
+    let issuer_ordered_pub_account: Result<OrderedPubAccount, Error> = load_object_in(
+        store,
         ON_CHAIN_DIR,
         &issuer,
         &user_public_account_file(&ticker),
     );
     if let Err(error) = issuer_ordered_pub_account {
         error!("Error in validation of tx-{}: {:#?}", tx_id, error);
-        return ValidationResult::error(&issuer, &ticker);
+        return ValidationResult::error(&issuer, &ticker, &error);
     }
     let issuer_ordered_pub_account = issuer_ordered_pub_account.unwrap();
 
-    let issuer_account_balance: Result<EncryptedAmount, Error> = load_object(
-        db_dir.clone(),
+    let issuer_account_balance: Result<EncryptedAmount, Error> = load_object_in(
+        store,
         ON_CHAIN_DIR,
         &issuer,
         &user_public_account_balance_file(&ticker),
     );
     if let Err(error) = issuer_account_balance {
         error!("Error in validation of tx-{}: {:#?}", tx_id, error);
-        return ValidationResult::error(&issuer, &ticker);
+        return ValidationResult::error(&issuer, &ticker, &error);
     }
     let issuer_account_balance = issuer_account_balance.unwrap();
 
-    let auditors = retrieve_auditors_by_names(auditors, db_dir.clone());
+    let auditors = retrieve_auditors_by_names_in(auditors, store);
     if let Err(error) = auditors {
         error!("Error in validation of tx-{}: {:#?}", tx_id, error);
-        return ValidationResult::error("user", "ticker");
+        return ValidationResult::error("user", "ticker", &error);
     };
     let auditors = auditors.unwrap();
 
+    let init_state = AssetTxState::Initialization(TxSubstate::Started);
+    let init_instruction: Result<OrderedAssetInstruction, Error> = load_object_in(
+        store,
+        ON_CHAIN_DIR,
+        COMMON_OBJECTS_DIR,
+        &asset_transaction_file(tx_id, &issuer, init_state),
+    );
+    if let Err(error) = init_instruction {
+        error!("Error in validation of tx-{}: {:#?}", tx_id, error);
+        return ValidationResult::error(&issuer, &ticker, &error);
+    }
+    let init_instruction = init_instruction.unwrap();
+    if init_instruction.prev_state_hash != GENESIS_STATE_HASH {
+        let error = Error::StateChainBroken {
+            tx_id,
+            state: init_state.to_string(),
+        };
+        error!("Error in validation of tx-{}: {:#?}", tx_id, error);
+        return ValidationResult::error(&issuer, &ticker, &error);
+    }
+
     timing!(
         "validator.issuance.load_objects",
         load_objects_timer,
@@ -307,20 +528,24 @@ pub fn validate_asset_issuance(
     let validate_issuance_transaction_timer = Instant::now();
 
     let validator = AssetValidator;
+    let issuer_pub_account = issuer_ordered_pub_account.pub_account.clone();
+    let initialized_asset_tx = asset_tx.clone();
     // TODO: CRYP-165: This requires more work to handle properly. At the moment, I am ignoring the the balance returned.
-    let _ = match validator
-        .verify_asset_transaction(
-            amount,
-            &asset_tx,
-            &issuer_ordered_pub_account.pub_account,
-            &issuer_account_balance,
-            &auditors,
-        )
-        .map_err(|error| Error::LibraryError { error })
-    {
+    let _ = match verify_with_timeout(
+        move || {
+            validator.verify_asset_transaction(
+                amount,
+                &initialized_asset_tx,
+                &issuer_pub_account,
+                &issuer_account_balance,
+                &auditors,
+            )
+        },
+        VERIFICATION_TIMEOUT,
+    ) {
         Err(error) => {
             error!("Error in validation of tx-{}: {:#?}", tx_id, error);
-            return ValidationResult::error(&issuer, &ticker);
+            return ValidationResult::error(&issuer, &ticker, &error);
         }
         Ok(pub_account) => pub_account,
     };
@@ -338,16 +563,17 @@ pub fn validate_asset_issuance(
     let instruction = AssetInstruction {
         state: new_state,
         data: asset_tx.encode().to_vec(),
+        prev_state_hash: state_hash(&init_instruction),
     };
-    if let Err(error) = save_object(
-        db_dir,
+    if let Err(error) = save_object_if_absent_in(
+        store,
         ON_CHAIN_DIR,
         &issuer,
         &asset_transaction_file(tx_id, &issuer, new_state),
         &instruction,
     ) {
         error!("Error in validation of tx-{}: {:#?}", tx_id, error);
-        return ValidationResult::error(&issuer, &ticker);
+        return ValidationResult::error(&issuer, &ticker, &error);
     }
 
     timing!(
@@ -362,14 +588,68 @@ pub fn validate_asset_issuance(
         ticker,
         amount: Some(asset_tx.memo.enc_issued_amount),
         direction: Direction::Incoming,
+        error: None,
+    }
+}
+
+/// Validates all the account creations that are recorded in the account mapping of `store`,
+/// in the order of their transaction ids, and writes a `ValidationReport` to `out` after each
+/// validation. This lets the validator run over a snapshot, e.g., a `TarStore`, without a
+/// database directory. Like `validate_all_pending`, a failed validation is logged and the
+/// rest of the validations continue.
+pub fn validate_all_accounts_in<W: Write>(
+    store: &dyn ObjectStore,
+    output: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    let mut accounts = load_account_map_in(store)
+        .into_iter()
+        .map(|(_, (user, ticker, tx_id))| (tx_id, user, ticker))
+        .collect::<Vec<_>>();
+    accounts.sort();
+
+    for (tx_id, user, ticker) in accounts {
+        let result = load_object_in::<OrderedPubAccountTx>(
+            store,
+            ON_CHAIN_DIR,
+            COMMON_OBJECTS_DIR,
+            &account_create_transaction_file(tx_id, &user, &ticker),
+        )
+        .and_then(|ordered_account_tx| {
+            validate_account_in(
+                store,
+                ordered_account_tx.account_tx.pub_account.enc_asset_id,
+            )
+        });
+        if let Err(error) = &result {
+            error!("Error in validation of tx-{}: {:#?}", tx_id, error);
+            error!("tx-{}: Ignoring the validation error and continuing the with rest of the validations.", tx_id);
+        }
+        ValidationReport::new(
+            tx_id,
+            ACCOUNT_CREATED_STATE.to_string(),
+            ACCOUNT_VALIDATED_STATE.to_string(),
+            result.err().map(|error| error.to_string()),
+        )
+        .write(output, out)?;
     }
+
+    Ok(())
 }
 
 pub fn validate_account(db_dir: PathBuf, account_id: EncryptedAssetId) -> Result<(), Error> {
+    validate_account_in(&FileStore::new(db_dir), account_id)
+}
+
+/// Same as `validate_account`, but loads and saves the objects through `store`.
+pub fn validate_account_in(
+    store: &dyn ObjectStore,
+    account_id: EncryptedAssetId,
+) -> Result<(), Error> {
     // Load the user's public account.
     let load_objects_timer = Instant::now();
 
-    let (user, ticker, tx_id) = get_user_ticker_from(account_id, db_dir.clone())?;
+    let (user, ticker, tx_id) = get_user_ticker_in(store, account_id)?;
     info!(
         "Validating account{{tx_id: {}, account_id: {}, user: {}, ticker: {}}}",
         tx_id,
@@ -377,14 +657,14 @@ pub fn validate_account(db_dir: PathBuf, account_id: EncryptedAssetId) -> Result
         user,
         ticker
     );
-    let ordered_user_account_tx: OrderedPubAccountTx = load_object(
-        db_dir.clone(),
+    let ordered_user_account_tx: OrderedPubAccountTx = load_object_in(
+        store,
         ON_CHAIN_DIR,
         COMMON_OBJECTS_DIR,
         &account_create_transaction_file(tx_id, &user, &ticker),
     )?;
 
-    let valid_asset_ids = get_asset_ids(db_dir.clone())?;
+    let valid_asset_ids = get_asset_ids_in(store)?;
     timing!(
         "validator.account.load_objects",
         load_objects_timer,
@@ -395,9 +675,11 @@ pub fn validate_account(db_dir: PathBuf, account_id: EncryptedAssetId) -> Result
     // Validate the account.
     let validate_account_timer = Instant::now();
     let account_validator = AccountValidator {};
-    account_validator
-        .verify(&ordered_user_account_tx.account_tx, &valid_asset_ids)
-        .map_err(|error| Error::LibraryError { error })?;
+    let account_tx = ordered_user_account_tx.account_tx.clone();
+    verify_with_timeout(
+        move || account_validator.verify(&account_tx, &valid_asset_ids),
+        VERIFICATION_TIMEOUT,
+    )?;
 
     timing!(
         "validator.account",
@@ -412,15 +694,15 @@ pub fn validate_account(db_dir: PathBuf, account_id: EncryptedAssetId) -> Result
         pub_account: ordered_user_account_tx.account_tx.pub_account,
         last_processed_tx_counter: Some(tx_id),
     };
-    save_object(
-        db_dir.clone(),
+    save_object_in(
+        store,
         ON_CHAIN_DIR,
         &user,
         &user_public_account_file(&ticker),
         &ordered_account,
     )?;
-    save_object(
-        db_dir,
+    save_object_in(
+        store,
         ON_CHAIN_DIR,
         &user,
         &user_public_account_balance_file(&ticker),
@@ -437,28 +719,208 @@ pub fn validate_account(db_dir: PathBuf, account_id: EncryptedAssetId) -> Result
     Ok(())
 }
 
+/// Verifies that the validated balance of the account in `closure_tx` is zero, and closes
+/// the account by removing its validated public account and balance. The account can no
+/// longer send or receive assets afterwards.
+pub fn validate_account_closure(
+    db_dir: PathBuf,
+    closure_tx: &AccountClosureTx,
+) -> Result<(), Error> {
+    let (user, ticker, _) = get_user_ticker_from(closure_tx.account_id, db_dir.clone())?;
+    let ordered_pub_account = load_validated_account(db_dir.clone(), &user, &ticker)?;
+    let balance: EncryptedAmount = load_object(
+        db_dir.clone(),
+        ON_CHAIN_DIR,
+        &user,
+        &user_public_account_balance_file(&ticker),
+    )?;
+
+    AccountValidator
+        .verify_closure(
+            &ordered_pub_account.pub_account,
+            &balance,
+            &closure_tx.zero_balance_proof,
+        )
+        .map_err(|error| Error::LibraryError { error })?;
+
+    remove_file(
+        db_dir.clone(),
+        ON_CHAIN_DIR,
+        &user,
+        &user_public_account_file(&ticker),
+    )?;
+    remove_file(
+        db_dir,
+        ON_CHAIN_DIR,
+        &user,
+        &user_public_account_balance_file(&ticker),
+    )
+}
+
+/// Checks that the justified state of a transfer transaction is chained to its
+/// finalized and initialized states.
+pub fn verify_transfer_state_chain(
+    db_dir: PathBuf,
+    tx_id: u32,
+    sender: &str,
+    justified_instruction: &TransferInstruction,
+) -> Result<(), Error> {
+    verify_transfer_state_chain_in(
+        &FileStore::new(db_dir),
+        tx_id,
+        sender,
+        justified_instruction,
+    )
+}
+
+/// Same as `verify_transfer_state_chain`, but loads the instructions from `store`.
+pub fn verify_transfer_state_chain_in(
+    store: &dyn ObjectStore,
+    tx_id: u32,
+    sender: &str,
+    justified_instruction: &TransferInstruction,
+) -> Result<(), Error> {
+    let init_state = TransferTxState::Initialization(TxSubstate::Started);
+    let init_instruction: OrderedTransferInstruction = load_object_in(
+        store,
+        ON_CHAIN_DIR,
+        COMMON_OBJECTS_DIR,
+        &confidential_transaction_file(tx_id, sender, init_state),
+    )?;
+    let finalized_state = TransferTxState::Finalization(TxSubstate::Started);
+    let finalized_instruction: OrderedTransferInstruction = load_object_in(
+        store,
+        ON_CHAIN_DIR,
+        COMMON_OBJECTS_DIR,
+        &confidential_transaction_file(tx_id, sender, finalized_state),
+    )?;
+
+    if init_instruction.prev_state_hash != GENESIS_STATE_HASH {
+        return Err(Error::StateChainBroken {
+            tx_id,
+            state: init_state.to_string(),
+        });
+    }
+    ensure_state_chain(
+        &finalized_instruction.prev_state_hash,
+        &init_instruction,
+        tx_id,
+        finalized_state.to_string(),
+    )?;
+    ensure_state_chain(
+        &justified_instruction.prev_state_hash,
+        &finalized_instruction,
+        tx_id,
+        justified_instruction.state.to_string(),
+    )
+}
+
+/// Lists the on-chain files of the transaction `tx_id` as (user, state, file path) tuples.
+fn transaction_files(db_dir: PathBuf, tx_id: u32) -> Result<Vec<(String, String, String)>, Error> {
+    let mut dir = db_dir;
+    dir.push(ON_CHAIN_DIR);
+    dir.push(COMMON_OBJECTS_DIR);
+
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir.clone()).map_err(|error| Error::FileReadError {
+        error,
+        path: dir.clone(),
+    })? {
+        let entry = entry.map_err(|error| Error::FileReadError {
+            error,
+            path: dir.clone(),
+        })?;
+        let path = String::from(entry.path().to_str().ok_or(Error::PathBufConversionError)?);
+        // Skip the files that do not follow the transaction naming convention, e.g., the
+        // audit results.
+        if let Ok((file_tx_id, user, state, path)) = parse_tx_name(path) {
+            if file_tx_id == tx_id {
+                files.push((user, state, path));
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Discovers the objects of the transfer transaction `tx_id`, i.e., the justified
+/// instruction, the mediator, and the sender's and receiver's accounts, from their file
+/// names in `db_dir`, and verifies the transaction. Meant for debugging a stuck transaction
+/// without supplying the path of each object.
+///
+/// Unlike `validate_all_pending`, nothing is saved. On success, returns the state that the
+/// validator would move the transaction to, or the current state if it has already been
+/// validated. Returns `Error::TransactionIsNotReadyForValidation` if the mediator has not
+/// justified the transaction yet.
+pub fn validate_transaction_auto(db_dir: PathBuf, tx_id: u32) -> Result<TransferTxState, Error> {
+    let files = transaction_files(db_dir.clone(), tx_id)?;
+
+    let validated_state = TransferTxState::Justification(TxSubstate::Validated);
+    if files
+        .iter()
+        .any(|(_, state, _)| *state == validated_state.to_string())
+    {
+        info!("tx-{} has already been validated.", tx_id);
+        return Ok(validated_state);
+    }
+
+    let justified_state = TransferTxState::Justification(TxSubstate::Started).to_string();
+    let (mediator, _, path) = files
+        .into_iter()
+        .find(|(_, state, _)| *state == justified_state)
+        .ok_or(Error::TransactionIsNotReadyForValidation)?;
+    let instruction: TransferInstruction = load_object_from(PathBuf::from(path))?;
+    let tx =
+        JustifiedTransferTx::decode(&mut &instruction.data[..]).map_err(|_| Error::DecodeError)?;
+    let memo = &tx.finalized_data.init_data.memo;
+    let (sender, ticker, _) = get_user_ticker_from(memo.sender_account_id, db_dir.clone())?;
+    let (receiver, _, _) = get_user_ticker_from(memo.receiver_account_id, db_dir.clone())?;
+    info!(
+        "Validating asset transfer{{tx_id: {}, sender: {}, receiver: {}, ticker:{}, mediator: {}}}",
+        tx_id, sender, receiver, ticker, mediator
+    );
+
+    let sender_ordered_pub_account = load_validated_account(db_dir.clone(), &sender, &ticker)?;
+    let receiver_ordered_pub_account = load_validated_account(db_dir.clone(), &receiver, &ticker)?;
+    let pending_balance = sender_pending_balance(db_dir.clone(), &sender, &ticker, tx_id)?;
+
+    verify_transfer_state_chain(db_dir.clone(), tx_id, &sender, &instruction)?;
+    process_transaction(
+        instruction.clone(),
+        sender_ordered_pub_account.pub_account,
+        receiver_ordered_pub_account.pub_account,
+        pending_balance,
+        &instruction.auditors,
+        &FileStore::new(db_dir),
+    )?;
+
+    Ok(validated_state)
+}
+
 fn process_transaction(
     instruction: TransferInstruction,
     sender_pub_account: PubAccount,
     receiver_pub_account: PubAccount,
     pending_balance: EncryptedAmount,
     auditors: &[String],
-    db_dir: PathBuf,
+    store: &dyn ObjectStore,
 ) -> Result<(), Error> {
-    let mut rng = OsRng::default();
     let tx = JustifiedTransferTx::decode(&mut &instruction.data[..]).unwrap();
-    let auditors_accounts = retrieve_auditors_by_names(auditors, db_dir.clone())?;
+    let auditors_accounts = retrieve_auditors_by_names_in(auditors, store)?;
     let validator = TransactionValidator;
-    validator
-        .verify_transaction(
-            &tx,
-            &sender_pub_account,
-            &pending_balance,
-            &receiver_pub_account,
-            &auditors_accounts,
-            &mut rng,
-        )
-        .map_err(|error| Error::LibraryError { error })
+    verify_with_timeout(
+        move || {
+            let mut rng = OsRng::default();
+            validator.verify_transaction(
+                &tx,
+                &sender_pub_account,
+                &pending_balance,
+                &receiver_pub_account,
+                &auditors_accounts,
+                &mut rng,
+            )
+        },
+        VERIFICATION_TIMEOUT,
+    )
 }
 
 pub fn validate_transaction(
@@ -468,37 +930,53 @@ pub fn validate_transaction(
     pending_balance: EncryptedAmount,
     tx_id: u32,
     auditors: &[String],
+) -> (ValidationResult, ValidationResult) {
+    validate_transaction_in(
+        &FileStore::new(db_dir),
+        tx,
+        mediator,
+        pending_balance,
+        tx_id,
+        auditors,
+    )
+}
+
+/// Same as `validate_transaction`, but loads and saves the objects through `store`. The
+/// sender's `pending_balance` is computed by the caller.
+pub fn validate_transaction_in(
+    store: &dyn ObjectStore,
+    tx: JustifiedTransferTx,
+    mediator: String,
+    pending_balance: EncryptedAmount,
+    tx_id: u32,
+    auditors: &[String],
 ) -> (ValidationResult, ValidationResult) {
     let load_objects_timer = Instant::now();
     // Load the transaction, mediator's account, and issuer's public account.
 
-    let (sender, _, _) = match get_user_ticker_from(
-        tx.finalized_data.init_data.memo.sender_account_id,
-        db_dir.clone(),
-    ) {
-        Err(error) => {
-            error!("Error in validation of tx-{}: {:#?}", tx_id, error);
-            return (
-                ValidationResult::error("n/a", "n/a"),
-                ValidationResult::error("n/a", "n/a"),
-            );
-        }
-        Ok(ok) => ok,
-    };
+    let (sender, _, _) =
+        match get_user_ticker_in(store, tx.finalized_data.init_data.memo.sender_account_id) {
+            Err(error) => {
+                error!("Error in validation of tx-{}: {:#?}", tx_id, error);
+                return (
+                    ValidationResult::error("n/a", "n/a", &error),
+                    ValidationResult::error("n/a", "n/a", &error),
+                );
+            }
+            Ok(ok) => ok,
+        };
 
-    let (receiver, ticker, _) = match get_user_ticker_from(
-        tx.finalized_data.init_data.memo.receiver_account_id,
-        db_dir.clone(),
-    ) {
-        Err(error) => {
-            error!("Error in validation of tx-{}: {:#?}", tx_id, error);
-            return (
-                ValidationResult::error("n/a", "n/a"),
-                ValidationResult::error("n/a", "n/a"),
-            );
-        }
-        Ok(ok) => ok,
-    };
+    let (receiver, ticker, _) =
+        match get_user_ticker_in(store, tx.finalized_data.init_data.memo.receiver_account_id) {
+            Err(error) => {
+                error!("Error in validation of tx-{}: {:#?}", tx_id, error);
+                return (
+                    ValidationResult::error("n/a", "n/a", &error),
+                    ValidationResult::error("n/a", "n/a", &error),
+                );
+            }
+            Ok(ok) => ok,
+        };
 
     info!(
         "Validating asset transfer{{tx_id: {}, sender: {}, receiver: {}, ticker:{}, mediator: {}}}",
@@ -506,8 +984,8 @@ pub fn validate_transaction(
     );
     let state = TransferTxState::Justification(TxSubstate::Started);
 
-    let mut instruction: TransferInstruction = match load_object(
-        db_dir.clone(),
+    let mut instruction: TransferInstruction = match load_object_in(
+        store,
         ON_CHAIN_DIR,
         COMMON_OBJECTS_DIR,
         &confidential_transaction_file(tx_id, &mediator, state),
@@ -515,40 +993,30 @@ pub fn validate_transaction(
         Err(error) => {
             error!("Error in validation of tx-{}: {:#?}", tx_id, error);
             return (
-                ValidationResult::error(&sender, &ticker),
-                ValidationResult::error(&receiver, &ticker),
+                ValidationResult::error(&sender, &ticker, &error),
+                ValidationResult::error(&receiver, &ticker, &error),
             );
         }
         Ok(ok) => ok,
     };
 
-    let sender_ordered_pub_account: OrderedPubAccount = match load_object(
-        db_dir.clone(),
-        ON_CHAIN_DIR,
-        &sender,
-        &user_public_account_file(&ticker),
-    ) {
+    let sender_ordered_pub_account = match load_validated_account_in(store, &sender, &ticker) {
         Err(error) => {
             error!("Error in validation of tx-{}: {:#?}", tx_id, error);
             return (
-                ValidationResult::error(&sender, &ticker),
-                ValidationResult::error(&receiver, &ticker),
+                ValidationResult::error(&sender, &ticker, &error),
+                ValidationResult::error(&receiver, &ticker, &error),
             );
         }
         Ok(ok) => ok,
     };
 
-    let receiver_ordered_pub_account: OrderedPubAccount = match load_object(
-        db_dir.clone(),
-        ON_CHAIN_DIR,
-        &receiver,
-        &user_public_account_file(&ticker),
-    ) {
+    let receiver_ordered_pub_account = match load_validated_account_in(store, &receiver, &ticker) {
         Err(error) => {
             error!("Error in validation of tx-{}: {:#?}", tx_id, error);
             return (
-                ValidationResult::error(&sender, &ticker),
-                ValidationResult::error(&receiver, &ticker),
+                ValidationResult::error(&sender, &ticker, &error),
+                ValidationResult::error(&receiver, &ticker, &error),
             );
         }
         Ok(ok) => ok,
@@ -561,6 +1029,14 @@ pub fn validate_transaction(
         "tx_id" => tx_id.to_string()
     );
 
+    if let Err(error) = verify_transfer_state_chain_in(store, tx_id, &sender, &instruction) {
+        error!("Error in validation of tx-{}: {:#?}", tx_id, error);
+        return (
+            ValidationResult::error(&sender, &ticker, &error),
+            ValidationResult::error(&receiver, &ticker, &error),
+        );
+    }
+
     let validate_transaction_timer = Instant::now();
     let _result = match process_transaction(
         instruction.clone(),
@@ -568,13 +1044,13 @@ pub fn validate_transaction(
         receiver_ordered_pub_account.pub_account,
         pending_balance,
         auditors,
-        db_dir.clone(),
+        store,
     ) {
         Err(error) => {
             error!("Error in validation of tx-{}: {:#?}", tx_id, error);
             return (
-                ValidationResult::error(&sender, &ticker),
-                ValidationResult::error(&receiver, &ticker),
+                ValidationResult::error(&sender, &ticker, &error),
+                ValidationResult::error(&receiver, &ticker, &error),
             );
         }
         Ok(ok) => ok,
@@ -589,9 +1065,10 @@ pub fn validate_transaction(
 
     let save_objects_timer = Instant::now();
     // Save the transaction under the new state.
+    instruction.prev_state_hash = state_hash(&instruction);
     instruction.state = TransferTxState::Justification(TxSubstate::Validated);
-    if let Err(error) = save_object(
-        db_dir,
+    if let Err(error) = save_object_if_absent_in(
+        store,
         ON_CHAIN_DIR,
         COMMON_OBJECTS_DIR,
         &confidential_transaction_file(tx_id, &sender, instruction.state),
@@ -599,8 +1076,8 @@ pub fn validate_transaction(
     ) {
         error!("Error in validation of tx-{}: {:#?}", tx_id, error);
         return (
-            ValidationResult::error(&sender, &ticker),
-            ValidationResult::error(&receiver, &ticker),
+            ValidationResult::error(&sender, &ticker, &error),
+            ValidationResult::error(&receiver, &ticker, &error),
         );
     }
 
@@ -617,12 +1094,592 @@ pub fn validate_transaction(
             ticker: ticker.clone(),
             direction: Direction::Outgoing,
             amount: Some(tx.finalized_data.init_data.memo.enc_amount_using_sender),
+            error: None,
         },
         ValidationResult {
             user: receiver,
             ticker,
             direction: Direction::Incoming,
             amount: Some(tx.finalized_data.init_data.memo.enc_amount_using_receiver),
+            error: None,
         },
     )
 }
+
+// ------------------------------------------------------------------------------------------------
+// -                                            Tests                                             -
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        account_close::process_close_account,
+        account_create::{create_secret_account, process_create_account},
+        account_issue::process_issue_asset,
+        account_transfer::{process_create_tx, process_finalize_tx},
+        chain_setup::process_asset_id_creation,
+        create_rng_from_seed, debug_decrypt_account_balance, gen_seed,
+        justify::{justify_asset_transfer_transaction, process_create_mediator},
+        store::{MemStore, TarStore},
+        AssetIdList, OrderingState, ASSET_ID_LIST_FILE, USER_ACCOUNT_MAP,
+    };
+    use mercat::{account::AccountCreator, AccountCreatorInitializer};
+    use std::{collections::HashMap, io::Cursor};
+
+    #[test]
+    fn test_validate_asset_issuance_full_flow() {
+        let db_dir = PathBuf::from("chain_dir/unittest/validate_asset_issuance_full_flow");
+        let _ = std::fs::remove_dir_all(&db_dir);
+        let issuer = "alice";
+        let ticker = "ACME";
+        let seed = gen_seed();
+
+        process_asset_id_creation(db_dir.clone(), vec![ticker.to_string()]).unwrap();
+        process_create_account(
+            Some(seed.clone()),
+            db_dir.clone(),
+            ticker.to_string(),
+            issuer.to_string(),
+            false,
+            1,
+            false,
+        )
+        .unwrap();
+        validate_all_pending(db_dir.clone()).unwrap();
+
+        process_issue_asset(
+            seed,
+            db_dir.clone(),
+            issuer.to_string(),
+            &[],
+            ticker.to_string(),
+            10,
+            false,
+            2,
+            false,
+        )
+        .unwrap();
+        // A single validation verifies the initialization and persists the final state.
+        validate_all_pending(db_dir.clone()).unwrap();
+
+        let final_state = AssetTxState::Justification(TxSubstate::Validated);
+        let instruction: Result<AssetInstruction, Error> = load_object(
+            db_dir.clone(),
+            ON_CHAIN_DIR,
+            issuer,
+            &asset_transaction_file(2, issuer, final_state),
+        );
+        let balance =
+            debug_decrypt_account_balance(issuer.to_string(), ticker.to_string(), db_dir.clone());
+        let _ = std::fs::remove_dir_all(&db_dir);
+
+        assert_eq!(instruction.unwrap().state, final_state);
+        assert_eq!(balance.unwrap(), 10);
+    }
+
+    #[test]
+    fn test_validate_all_pending_in_chunks() {
+        let db_dir = PathBuf::from("chain_dir/unittest/validate_all_pending_in_chunks");
+        let _ = std::fs::remove_dir_all(&db_dir);
+        let ticker = "ACME";
+        let users = ["alice", "bob", "carol"];
+
+        process_asset_id_creation(db_dir.clone(), vec![ticker.to_string()]).unwrap();
+        for (index, user) in users.iter().enumerate() {
+            process_create_account(
+                Some(gen_seed()),
+                db_dir.clone(),
+                ticker.to_string(),
+                user.to_string(),
+                false,
+                index as u32 + 1,
+                false,
+            )
+            .unwrap();
+        }
+
+        // A backlog of three transactions is validated one transaction at a time, instead
+        // of being rejected for exceeding the limit.
+        let limits = BatchLimits {
+            max_items: 1,
+            ..BatchLimits::default()
+        };
+        let mut out = Vec::new();
+        let result =
+            validate_all_pending_with_limits(db_dir.clone(), &limits, OutputFormat::Json, &mut out);
+        let balances: Vec<_> = users
+            .iter()
+            .map(|user| {
+                debug_decrypt_account_balance(user.to_string(), ticker.to_string(), db_dir.clone())
+            })
+            .collect();
+        let _ = std::fs::remove_dir_all(&db_dir);
+
+        assert!(result.is_ok());
+        let reports: Vec<ValidationReport> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| (report.tx_id, report.result.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(1, "ok"), (2, "ok"), (3, "ok")]
+        );
+        for balance in balances {
+            assert_eq!(balance.unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn test_validate_account_closure() {
+        let db_dir = PathBuf::from("chain_dir/unittest/validate_account_closure");
+        let _ = std::fs::remove_dir_all(&db_dir);
+        let ticker = "ACME";
+        let bob_seed = gen_seed();
+
+        process_asset_id_creation(db_dir.clone(), vec![ticker.to_string()]).unwrap();
+        for (user, seed, tx_id) in &[("alice", gen_seed(), 1), ("bob", bob_seed.clone(), 2)] {
+            process_create_account(
+                Some(seed.clone()),
+                db_dir.clone(),
+                ticker.to_string(),
+                user.to_string(),
+                false,
+                *tx_id,
+                false,
+            )
+            .unwrap();
+        }
+        validate_all_pending(db_dir.clone()).unwrap();
+        process_issue_asset(
+            bob_seed,
+            db_dir.clone(),
+            String::from("bob"),
+            &[],
+            ticker.to_string(),
+            10,
+            false,
+            3,
+            false,
+        )
+        .unwrap();
+        validate_all_pending(db_dir.clone()).unwrap();
+
+        // Alice's balance is zero, but Bob's is not.
+        for (user, tx_id) in &[("alice", 4), ("bob", 5)] {
+            process_close_account(
+                gen_seed(),
+                db_dir.clone(),
+                ticker.to_string(),
+                user.to_string(),
+                false,
+                *tx_id,
+            )
+            .unwrap();
+        }
+        let mut out = Vec::new();
+        let result = validate_all_pending_with_output(db_dir.clone(), OutputFormat::Json, &mut out);
+        let alice_account = load_validated_account(db_dir.clone(), "alice", ticker);
+        let bob_account = load_validated_account(db_dir.clone(), "bob", ticker);
+        let _ = std::fs::remove_dir_all(&db_dir);
+
+        assert!(result.is_ok());
+        let reports: Vec<ValidationReport> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| (report.tx_id, report.result.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(4, "ok"), (5, "error")]
+        );
+        assert!(matches!(
+            alice_account,
+            Err(Error::AccountNotValidated { .. })
+        ));
+        assert!(bob_account.is_ok());
+    }
+
+    #[test]
+    fn test_validate_transaction_auto() {
+        let db_dir = PathBuf::from("chain_dir/unittest/validate_transaction_auto");
+        let _ = std::fs::remove_dir_all(&db_dir);
+        let ticker = "ACME";
+        let alice_seed = gen_seed();
+        let tx_id = 4;
+
+        process_asset_id_creation(db_dir.clone(), vec![ticker.to_string()]).unwrap();
+        for (user, seed, account_tx_id) in
+            &[("alice", alice_seed.clone(), 1), ("bob", gen_seed(), 2)]
+        {
+            process_create_account(
+                Some(seed.clone()),
+                db_dir.clone(),
+                ticker.to_string(),
+                user.to_string(),
+                false,
+                *account_tx_id,
+                false,
+            )
+            .unwrap();
+        }
+        process_create_mediator(gen_seed(), db_dir.clone(), String::from("mike")).unwrap();
+        validate_all_pending(db_dir.clone()).unwrap();
+        process_issue_asset(
+            alice_seed,
+            db_dir.clone(),
+            String::from("alice"),
+            &[],
+            ticker.to_string(),
+            10,
+            false,
+            3,
+            false,
+        )
+        .unwrap();
+        validate_all_pending(db_dir.clone()).unwrap();
+
+        process_create_tx(
+            gen_seed(),
+            db_dir.clone(),
+            String::from("alice"),
+            String::from("bob"),
+            String::from("mike"),
+            &[],
+            ticker.to_string(),
+            3,
+            false,
+            tx_id,
+            false,
+        )
+        .unwrap();
+        process_finalize_tx(
+            gen_seed(),
+            db_dir.clone(),
+            String::from("alice"),
+            String::from("bob"),
+            ticker.to_string(),
+            3,
+            false,
+            tx_id,
+            false,
+        )
+        .unwrap();
+        // The mediator has not justified the transaction yet.
+        let not_ready = validate_transaction_auto(db_dir.clone(), tx_id);
+
+        justify_asset_transfer_transaction(
+            db_dir.clone(),
+            String::from("alice"),
+            String::from("bob"),
+            String::from("mike"),
+            &[],
+            ticker.to_string(),
+            gen_seed(),
+            false,
+            tx_id,
+            false,
+            false,
+        )
+        .unwrap();
+        let justified = validate_transaction_auto(db_dir.clone(), tx_id);
+        // The auto path does not save anything, so the regular validation still runs.
+        validate_all_pending(db_dir.clone()).unwrap();
+        let validated = validate_transaction_auto(db_dir.clone(), tx_id);
+        let balance =
+            debug_decrypt_account_balance(String::from("bob"), ticker.to_string(), db_dir.clone());
+        let _ = std::fs::remove_dir_all(&db_dir);
+
+        assert!(matches!(
+            not_ready,
+            Err(Error::TransactionIsNotReadyForValidation)
+        ));
+        assert_eq!(
+            justified.unwrap(),
+            TransferTxState::Justification(TxSubstate::Validated)
+        );
+        assert_eq!(
+            validated.unwrap(),
+            TransferTxState::Justification(TxSubstate::Validated)
+        );
+        assert_eq!(balance.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_validate_account_in_memory() {
+        let store = MemStore::new();
+        let mut rng = create_rng_from_seed(Some(gen_seed())).unwrap();
+        let (user, ticker, tx_id) = ("alice", "ACME", 1);
+
+        let secret_account = create_secret_account(&mut rng, ticker.to_string()).unwrap();
+        let valid_asset_ids = vec![secret_account.asset_id_witness.value()];
+        store
+            .save(
+                ON_CHAIN_DIR,
+                COMMON_OBJECTS_DIR,
+                ASSET_ID_LIST_FILE,
+                &serde_json::to_vec(&AssetIdList(valid_asset_ids.clone())).unwrap(),
+            )
+            .unwrap();
+
+        let account_tx = AccountCreator
+            .create(&secret_account, &valid_asset_ids, &mut rng)
+            .unwrap();
+        let account_id = account_tx.pub_account.enc_asset_id;
+        let initial_balance = account_tx.initial_balance;
+        save_object_in(
+            &store,
+            ON_CHAIN_DIR,
+            COMMON_OBJECTS_DIR,
+            &account_create_transaction_file(tx_id, user, ticker),
+            &OrderedPubAccountTx {
+                account_tx,
+                ordering_state: OrderingState::new(tx_id),
+            },
+        )
+        .unwrap();
+
+        let mut mapping = HashMap::new();
+        mapping.insert(
+            PrintableAccountId(account_id.encode()).to_string(),
+            (user.to_string(), ticker.to_string(), tx_id),
+        );
+        store
+            .save(
+                OFF_CHAIN_DIR,
+                COMMON_OBJECTS_DIR,
+                USER_ACCOUNT_MAP,
+                &serde_json::to_vec(&mapping).unwrap(),
+            )
+            .unwrap();
+
+        validate_account_in(&store, account_id).unwrap();
+
+        let ordered_account: OrderedPubAccount = load_object_in(
+            &store,
+            ON_CHAIN_DIR,
+            user,
+            &user_public_account_file(ticker),
+        )
+        .unwrap();
+        let balance: EncryptedAmount = load_object_in(
+            &store,
+            ON_CHAIN_DIR,
+            user,
+            &user_public_account_balance_file(ticker),
+        )
+        .unwrap();
+        assert_eq!(ordered_account.pub_account.enc_asset_id, account_id);
+        assert_eq!(ordered_account.last_processed_tx_counter, Some(tx_id));
+        assert_eq!(balance, initial_balance);
+    }
+
+    #[test]
+    fn test_validate_accounts_from_tar_archive() {
+        let db_dir = PathBuf::from("chain_dir/unittest/validate_accounts_from_tar_archive");
+        let _ = std::fs::remove_dir_all(&db_dir);
+        let (user, ticker, tx_id) = ("alice", "ACME", 1);
+
+        process_asset_id_creation(db_dir.clone(), vec![ticker.to_string()]).unwrap();
+        process_create_account(
+            Some(gen_seed()),
+            db_dir.clone(),
+            ticker.to_string(),
+            user.to_string(),
+            false,
+            tx_id,
+            false,
+        )
+        .unwrap();
+
+        // Pack the database directory into an in-memory archive, and discard the directory.
+        let mut builder = tar::Builder::new(Vec::new());
+        let packed = builder.append_dir_all(".", &db_dir);
+        let _ = std::fs::remove_dir_all(&db_dir);
+        packed.unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let store = TarStore::new(Cursor::new(archive)).unwrap();
+        let mut out = Vec::new();
+        validate_all_accounts_in(&store, OutputFormat::Json, &mut out).unwrap();
+
+        let report: ValidationReport =
+            serde_json::from_str(String::from_utf8(out).unwrap().trim()).unwrap();
+        assert_eq!(report.tx_id, tx_id);
+        assert_eq!(report.result, "ok");
+        let ordered_account: OrderedPubAccount = load_object_in(
+            &store,
+            ON_CHAIN_DIR,
+            user,
+            &user_public_account_file(ticker),
+        )
+        .unwrap();
+        assert_eq!(ordered_account.last_processed_tx_counter, Some(tx_id));
+    }
+
+    #[test]
+    fn test_validate_asset_issuance_from_tar_archive() {
+        let db_dir = PathBuf::from("chain_dir/unittest/validate_asset_issuance_from_tar_archive");
+        let _ = std::fs::remove_dir_all(&db_dir);
+        let (issuer, ticker, tx_id) = ("alice", "ACME", 2);
+        let seed = gen_seed();
+
+        process_asset_id_creation(db_dir.clone(), vec![ticker.to_string()]).unwrap();
+        process_create_account(
+            Some(seed.clone()),
+            db_dir.clone(),
+            ticker.to_string(),
+            issuer.to_string(),
+            false,
+            1,
+            false,
+        )
+        .unwrap();
+        validate_all_pending(db_dir.clone()).unwrap();
+        process_issue_asset(
+            seed,
+            db_dir.clone(),
+            issuer.to_string(),
+            &[],
+            ticker.to_string(),
+            10,
+            false,
+            tx_id,
+            false,
+        )
+        .unwrap();
+
+        // Pack the database directory into an in-memory archive, and discard the directory.
+        let mut builder = tar::Builder::new(Vec::new());
+        let packed = builder.append_dir_all(".", &db_dir);
+        let _ = std::fs::remove_dir_all(&db_dir);
+        packed.unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let store = TarStore::new(Cursor::new(archive)).unwrap();
+        let init_state = AssetTxState::Initialization(TxSubstate::Started);
+        let init_instruction: OrderedAssetInstruction = load_object_in(
+            &store,
+            ON_CHAIN_DIR,
+            COMMON_OBJECTS_DIR,
+            &asset_transaction_file(tx_id, issuer, init_state),
+        )
+        .unwrap();
+        let asset_tx = InitializedAssetTx::decode(&mut &init_instruction.data[..]).unwrap();
+
+        let result =
+            validate_asset_issuance_in(&store, init_instruction.amount, asset_tx, tx_id, &[]);
+        assert_eq!(result.error, None);
+
+        let final_state = AssetTxState::Justification(TxSubstate::Validated);
+        let instruction: AssetInstruction = load_object_in(
+            &store,
+            ON_CHAIN_DIR,
+            issuer,
+            &asset_transaction_file(tx_id, issuer, final_state),
+        )
+        .unwrap();
+        assert_eq!(instruction.prev_state_hash, state_hash(&init_instruction));
+    }
+
+    #[test]
+    fn test_validation_json_output() {
+        let db_dir = PathBuf::from("chain_dir/unittest/validation_json_output");
+        let _ = std::fs::remove_dir_all(&db_dir);
+
+        process_asset_id_creation(db_dir.clone(), vec![String::from("ACME")]).unwrap();
+        process_create_account(
+            Some(gen_seed()),
+            db_dir.clone(),
+            String::from("ACME"),
+            String::from("alice"),
+            false,
+            1,
+            false,
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        let result = validate_all_pending_with_output(db_dir.clone(), OutputFormat::Json, &mut out);
+        let _ = std::fs::remove_dir_all(&db_dir);
+        result.unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        let report: ValidationReport = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(
+            report,
+            ValidationReport {
+                tx_id: 1,
+                old_state: ACCOUNT_CREATED_STATE.to_string(),
+                new_state: ACCOUNT_VALIDATED_STATE.to_string(),
+                result: String::from("ok"),
+                error: None,
+            }
+        );
+        assert_eq!("json".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_validation_json_output_on_failure() {
+        let db_dir = PathBuf::from("chain_dir/unittest/validation_json_output_on_failure");
+        let _ = std::fs::remove_dir_all(&db_dir);
+        let seed = gen_seed();
+
+        process_asset_id_creation(db_dir.clone(), vec![String::from("ACME")]).unwrap();
+        process_create_account(
+            Some(seed.clone()),
+            db_dir.clone(),
+            String::from("ACME"),
+            String::from("alice"),
+            false,
+            1,
+            false,
+        )
+        .unwrap();
+        validate_all_pending(db_dir.clone()).unwrap();
+
+        // A cheating issuer overwrites the encrypted issued amount, which fails the proofs.
+        process_issue_asset(
+            seed,
+            db_dir.clone(),
+            String::from("alice"),
+            &[],
+            String::from("ACME"),
+            10,
+            false,
+            2,
+            true,
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        let result = validate_all_pending_with_output(db_dir.clone(), OutputFormat::Json, &mut out);
+        let _ = std::fs::remove_dir_all(&db_dir);
+        result.unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        let report: ValidationReport = serde_json::from_str(lines[0]).unwrap();
+        let old_state = AssetTxState::Initialization(TxSubstate::Started).to_string();
+        assert_eq!(report.tx_id, 2);
+        assert_eq!(report.result, "error");
+        assert_eq!(report.new_state, old_state);
+        assert_eq!(report.old_state, old_state);
+        // The report carries the actual error of the failed verification.
+        assert!(report
+            .error
+            .unwrap()
+            .starts_with("An error occurred in the underlying library"));
+    }
+}
@@ -1,4 +1,5 @@
 use log::info;
+use mercat_common::validate::OutputFormat;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use structopt::StructOpt;
@@ -16,6 +17,36 @@ pub struct CLI {
         long
     )]
     pub db_dir: Option<PathBuf>,
+
+    /// A tar archive of a database directory, e.g., a distributed snapshot of the accounts.
+    /// When set, the validator validates the account creations in the archive without
+    /// extracting it, and `db_dir` is ignored. The archive is not modified.
+    #[structopt(
+        parse(from_os_str),
+        help = "The tar archive of a database directory, whose accounts will be validated.",
+        long
+    )]
+    pub archive: Option<PathBuf>,
+
+    /// The format of the report printed to stdout after each validation. With `json`, each
+    /// validation prints a JSON object with the `tx_id`, `old_state`, `new_state`, `result`,
+    /// and `error` fields, which a supervising process can parse.
+    #[structopt(
+        help = "The format of the validation reports: `text` or `json`.",
+        long,
+        default_value = "text"
+    )]
+    pub output: OutputFormat,
+
+    /// The file to which the metrics are written in the Prometheus text format, e.g., for the
+    /// textfile collector of the Prometheus node exporter.
+    #[cfg(feature = "prometheus")]
+    #[structopt(
+        parse(from_os_str),
+        help = "The file to write the metrics to, in the Prometheus text format.",
+        long
+    )]
+    pub metrics_file: Option<PathBuf>,
 }
 
 pub fn parse_input() -> Result<CLI, confy::ConfyError> {
@@ -5,9 +5,14 @@ mod input;
 
 use input::parse_input;
 use log::info;
-use mercat_common::{errors::Error, init_print_logger, validate::validate_all_pending};
+use mercat_common::{
+    errors::Error,
+    init_print_logger,
+    store::TarStore,
+    validate::{validate_all_accounts_in, validate_all_pending_with_output},
+};
 use metrics::timing;
-use std::time::Instant;
+use std::{io, time::Instant};
 
 fn main() {
     env_logger::init();
@@ -17,6 +22,24 @@ fn main() {
     let parse_arg_timer = Instant::now();
     let args = parse_input().unwrap();
     timing!("validator.argument_parse", parse_arg_timer, Instant::now());
-    validate_all_pending(args.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap()).unwrap();
+    match args.archive {
+        Some(archive) => validate_all_accounts_in(
+            &TarStore::open(archive).unwrap(),
+            args.output,
+            &mut io::stdout(),
+        ),
+        None => validate_all_pending_with_output(
+            args.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
+            args.output,
+            &mut io::stdout(),
+        ),
+    }
+    .unwrap();
+    #[cfg(feature = "prometheus")]
+    {
+        if let Some(metrics_file) = args.metrics_file {
+            mercat_common::prometheus::write_metrics(metrics_file).unwrap();
+        }
+    }
     info!("The program finished successfully.");
 }
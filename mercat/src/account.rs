@@ -1,22 +1,37 @@
+#[cfg(feature = "rayon")]
+use crate::EncryptionKeys;
 use crate::{
-    AccountCreatorInitializer, AccountCreatorVerifier, EncryptedAmount, PubAccount, PubAccountTx,
-    SecAccount, BASE, EXPONENT,
+    Account, AccountCreatorInitializer, AccountCreatorVerifier, AccountDiff, EncryptedAmount,
+    EncryptedAssetId, EncryptionPubKey, ProofKind, PubAccount, PubAccountTx, SameAssetTypeProof,
+    SecAccount, StructuralCheck, BASE, EXPONENT,
 };
+use codec::Encode;
 use cryptography_core::{
     asset_proofs::{
         bulletproofs::PedersenGens,
+        ciphertext_refreshment_proof::{
+            CipherTextRefreshmentProverAwaitingChallenge, CipherTextRefreshmentVerifier,
+        },
         correctness_proof::{CorrectnessProverAwaitingChallenge, CorrectnessVerifier},
+        decryption_proof::{prove_correct_decryption, verify_correct_decryption, DecryptionProof},
+        encrypting_same_value_proof::{
+            CipherEqualDifferentPubKeyProof, EncryptingSameValueProverAwaitingChallenge,
+            EncryptingSameValueVerifier,
+        },
         encryption_proofs::single_property_prover,
         encryption_proofs::single_property_verifier,
-        errors::Fallible,
+        encryption_proofs::{decode_versioned, encode_versioned, ProofVersion},
+        errors::{ErrorKind, Fallible},
         membership_proof::{MembershipProofVerifier, MembershipProverAwaitingChallenge},
         one_out_of_many_proof::OooNProofGenerators,
+        range_proof::{verify_within_range, InRangeProof},
         wellformedness_proof::{WellformednessProverAwaitingChallenge, WellformednessVerifier},
-        AssetId, Balance, CommitmentWitness,
+        AssetId, Balance, CommitmentWitness, BALANCE_RANGE,
     },
     curve25519_dalek::scalar::Scalar,
 };
 use rand_core::{CryptoRng, RngCore};
+use sha3::{digest::FixedOutput, Digest, Sha3_256};
 
 use sp_std::vec::Vec;
 use zeroize::Zeroizing;
@@ -32,6 +47,61 @@ pub fn convert_asset_ids(valid_asset_ids: Vec<AssetId>) -> Vec<Scalar> {
         .collect::<Vec<_>>()
 }
 
+/// Derives the seed of the `index`-th account from the master seed, by XORing the little-endian
+/// bytes of the index into the first 8 bytes of the master seed.
+#[cfg(feature = "rayon")]
+fn derive_account_seed(seed: &[u8; 32], index: usize) -> [u8; 32] {
+    let mut account_seed = *seed;
+    for (byte, index_byte) in account_seed
+        .iter_mut()
+        .zip((index as u64).to_le_bytes().iter())
+    {
+        *byte ^= index_byte;
+    }
+    account_seed
+}
+
+/// Generates `n` accounts for the given asset id in parallel.
+///
+/// Each account uses its own RNG, seeded with `seed ⊕ index` (see `derive_account_seed`),
+/// so the result only depends on `seed` and not on how the work is scheduled across threads.
+/// Since the accounts are reproducible from the master seed, the master seed must be kept secret.
+#[cfg(feature = "rayon")]
+pub fn generate_accounts_par(
+    n: usize,
+    seed: [u8; 32],
+    asset_id: AssetId,
+) -> Vec<(SecAccount, PubAccount)> {
+    use cryptography_core::asset_proofs::ElgamalSecretKey;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rayon::prelude::*;
+
+    (0..n)
+        .into_par_iter()
+        .map(|index| {
+            let mut rng = StdRng::from_seed(derive_account_seed(&seed, index));
+            let secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+            let enc_keys = EncryptionKeys {
+                public: secret.get_public_key(),
+                secret,
+            };
+            let asset_id_witness = CommitmentWitness::from((asset_id.into(), &mut rng));
+            let pub_account = PubAccount {
+                enc_asset_id: enc_keys.public.encrypt(&asset_id_witness),
+                owner_enc_pub_key: enc_keys.public,
+            };
+
+            (
+                SecAccount {
+                    enc_keys,
+                    asset_id_witness,
+                },
+                pub_account,
+            )
+        })
+        .collect()
+}
+
 pub struct AccountCreator;
 
 impl AccountCreatorInitializer for AccountCreator {
@@ -109,54 +179,332 @@ pub fn withdraw(
     initial_balance - enc_amount
 }
 
-// ------------------------------------------------------------------------------------------------
-// -                                          Validator                                           -
-// ------------------------------------------------------------------------------------------------
+/// Subtracts `enc_amount` from `balance` and checks that the result is not negative.
+/// Since the underflow cannot be detected homomorphically, the caller has to provide
+/// a range proof for the resulting balance, e.g., the `enough_fund_proof` of a transfer
+/// along with its refreshed balance.
+pub fn apply_debit<R: RngCore + CryptoRng>(
+    balance: &EncryptedAmount,
+    enc_amount: &EncryptedAmount,
+    range_proof: &InRangeProof,
+    rng: &mut R,
+) -> Fallible<EncryptedAmount> {
+    let new_balance = withdraw(balance, enc_amount);
 
-pub struct AccountValidator;
+    // The range proof must be about the resulting balance.
+    ensure!(
+        range_proof.range == BALANCE_RANGE && range_proof.init == new_balance.y.compress(),
+        ErrorKind::InsufficientBalance
+    );
+    verify_within_range(range_proof, rng).map_err(|_| ErrorKind::InsufficientBalance)?;
 
-impl AccountCreatorVerifier for AccountValidator {
-    fn verify(&self, account: &PubAccountTx, valid_asset_ids: &[Scalar]) -> Fallible<()> {
-        let gens = &PedersenGens::default();
+    Ok(new_balance)
+}
 
-        // Verify that the encrypted asset id is wellformed
-        single_property_verifier(
-            &WellformednessVerifier {
-                pub_key: account.pub_account.owner_enc_pub_key,
-                cipher: account.pub_account.enc_asset_id,
-                pc_gens: &gens,
+/// Proves that the account's encrypted balance `enc_balance` is zero. E.g., the owner of a
+/// fully drained account proves this before closing the account. Only the secret key is
+/// needed, since the blinding factor of a balance that results from many transfers is not
+/// known to the owner.
+pub fn prove_balance_zero<R: RngCore + CryptoRng>(
+    secret: &SecAccount,
+    enc_balance: &EncryptedAmount,
+    rng: &mut R,
+) -> Fallible<DecryptionProof> {
+    prove_correct_decryption(&secret.enc_keys.secret, enc_balance, 0, rng)
+}
+
+/// The domain separation label of `PubAccount::content_hash`.
+const PUB_ACCOUNT_HASH_LABEL: &[u8] = b"PolymathPubAccountHash";
+/// The domain separation label of `PubAccount::derive_id`.
+const PUB_ACCOUNT_ID_LABEL: &[u8] = b"PolymathPubAccountId";
+
+impl PubAccount {
+    /// A deterministic, domain-separated hash of the SCALE encoding of the account,
+    /// which can be used as a stable key for indexing accounts.
+    ///
+    /// The hash covers the encrypted asset id and the owner's public key. It does not
+    /// cover the encrypted balance, which is not part of the account and changes with
+    /// every transaction.
+    pub fn content_hash(&self) -> [u8; 32] {
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(
+            &Sha3_256::default()
+                .chain(PUB_ACCOUNT_HASH_LABEL)
+                .chain(self.encode())
+                .fixed_result(),
+        );
+        hash
+    }
+
+    /// A deterministic id, derived from the hash of the owner's encryption public key.
+    /// It is an alternative to the externally assigned ids, which can collide.
+    ///
+    /// Since the id only depends on the owner's key, an owner that uses the same key for
+    /// several accounts gets the same id for all of them.
+    pub fn derive_id(&self) -> [u8; 32] {
+        let mut id = [0u8; 32];
+        id.copy_from_slice(
+            &Sha3_256::default()
+                .chain(PUB_ACCOUNT_ID_LABEL)
+                .chain(self.owner_enc_pub_key.encode())
+                .fixed_result(),
+        );
+        id
+    }
+
+    /// Compares this account and its encrypted `balance` with a later snapshot of them.
+    /// Useful for reconciling the account states of different validators.
+    pub fn diff(
+        &self,
+        balance: &EncryptedAmount,
+        other: &PubAccount,
+        other_balance: &EncryptedAmount,
+    ) -> AccountDiff {
+        AccountDiff {
+            enc_asset_id_changed: self.enc_asset_id != other.enc_asset_id,
+            owner_enc_pub_key_changed: self.owner_enc_pub_key != other.owner_enc_pub_key,
+            balance_change: if balance != other_balance {
+                Some((*balance, *other_balance))
+            } else {
+                None
             },
-            account.asset_wellformedness_proof,
-        )?;
+        }
+    }
+}
 
-        // Verify that the encrypted balance is correct
-        let balance: Balance = 0;
+/// Verifies the proof that two encrypted asset ids, encrypted with two different public keys
+/// (e.g., the issuer's and the mediator's), encrypt the same asset id.
+pub fn verify_asset_id_equality(
+    enc_pub_key: EncryptionPubKey,
+    enc_asset_id: EncryptedAssetId,
+    other_enc_pub_key: EncryptionPubKey,
+    other_enc_asset_id: EncryptedAssetId,
+    proof: CipherEqualDifferentPubKeyProof,
+) -> Fallible<()> {
+    single_property_verifier(
+        &EncryptingSameValueVerifier {
+            pub_key1: enc_pub_key,
+            pub_key2: other_enc_pub_key,
+            cipher1: enc_asset_id,
+            cipher2: other_enc_asset_id,
+            pc_gens: &PedersenGens::default(),
+        },
+        proof,
+    )
+}
+
+/// Creates the first half of a `SameAssetTypeProof`, by the owner of `account_a`.
+/// The asset id of `account_a` is encrypted with the public key of `account_b`, using the
+/// same witness as `account_a`'s encrypted asset id, and the two ciphertexts are proven to
+/// encrypt the same value.
+pub fn prove_same_asset_type<R: RngCore + CryptoRng>(
+    account_a: &SecAccount,
+    account_b: &PubAccount,
+    rng: &mut R,
+) -> Fallible<SameAssetTypeProof> {
+    let witness = &account_a.asset_id_witness;
+    let enc_asset_id_using_other = account_b.owner_enc_pub_key.encrypt(witness);
+    let asset_id_equal_cipher_proof = single_property_prover(
+        EncryptingSameValueProverAwaitingChallenge {
+            pub_key1: account_a.enc_keys.public,
+            pub_key2: account_b.owner_enc_pub_key,
+            w: Zeroizing::new(witness.clone()),
+            pc_gens: &PedersenGens::default(),
+        },
+        rng,
+    )?;
+
+    Ok(SameAssetTypeProof {
+        enc_asset_id_using_other,
+        asset_id_equal_cipher_proof,
+        asset_id_refreshed_same_proof: None,
+    })
+}
+
+/// Completes a `SameAssetTypeProof`, by the owner of `account_b`. Proves that the asset id
+/// that was encrypted for `account_b` equals the encrypted asset id of `account_b`.
+pub fn confirm_same_asset_type<R: RngCore + CryptoRng>(
+    proof: SameAssetTypeProof,
+    account_b: &Account,
+    rng: &mut R,
+) -> Fallible<SameAssetTypeProof> {
+    let asset_id_refreshed_same_proof = single_property_prover(
+        CipherTextRefreshmentProverAwaitingChallenge::new(
+            account_b.secret.enc_keys.secret.clone(),
+            account_b.public.enc_asset_id,
+            proof.enc_asset_id_using_other,
+            &PedersenGens::default(),
+        ),
+        rng,
+    )?;
+
+    Ok(SameAssetTypeProof {
+        asset_id_refreshed_same_proof: Some(asset_id_refreshed_same_proof),
+        ..proof
+    })
+}
+
+/// Verifies that `account_a` and `account_b` hold the same asset type.
+/// Returns `VerificationError` if the proof has not been completed by the owner of `account_b`.
+pub fn verify_same_asset_type(
+    account_a: &PubAccount,
+    account_b: &PubAccount,
+    proof: &SameAssetTypeProof,
+) -> Fallible<()> {
+    let asset_id_refreshed_same_proof = proof
+        .asset_id_refreshed_same_proof
+        .ok_or(ErrorKind::VerificationError)?;
+
+    verify_asset_id_equality(
+        account_a.owner_enc_pub_key,
+        account_a.enc_asset_id,
+        account_b.owner_enc_pub_key,
+        proof.enc_asset_id_using_other,
+        proof.asset_id_equal_cipher_proof,
+    )?;
+    single_property_verifier(
+        &CipherTextRefreshmentVerifier::new(
+            account_b.owner_enc_pub_key,
+            account_b.enc_asset_id,
+            proof.enc_asset_id_using_other,
+            &PedersenGens::default(),
+        ),
+        asset_id_refreshed_same_proof,
+    )
+}
+
+// ------------------------------------------------------------------------------------------------
+// -                                          Validator                                           -
+// ------------------------------------------------------------------------------------------------
+
+impl PubAccountTx {
+    /// Verifies that the encrypted asset id is wellformed.
+    pub fn verify_wellformedness(&self) -> Fallible<()> {
         single_property_verifier(
-            &CorrectnessVerifier {
-                value: balance.into(),
-                pub_key: account.pub_account.owner_enc_pub_key,
-                cipher: account.initial_balance,
-                pc_gens: &gens,
+            &WellformednessVerifier {
+                pub_key: self.pub_account.owner_enc_pub_key,
+                cipher: self.pub_account.enc_asset_id,
+                pc_gens: &PedersenGens::default(),
             },
-            account.initial_balance_correctness_proof,
-        )?;
+            self.asset_wellformedness_proof,
+        )
+    }
 
-        // Verify that the asset is from the proper asset list
-        let membership_proof = account.asset_membership_proof.clone();
+    /// Verifies that the asset id is a member of `valid_asset_ids`.
+    pub fn verify_membership(&self, valid_asset_ids: &[Scalar]) -> Fallible<()> {
         let generators = &OooNProofGenerators::new(BASE, EXPONENT);
         single_property_verifier(
             &MembershipProofVerifier {
-                secret_element_com: account.pub_account.enc_asset_id.y,
+                secret_element_com: self.pub_account.enc_asset_id.y,
                 generators,
                 elements_set: valid_asset_ids,
             },
-            membership_proof,
-        )?;
+            self.asset_membership_proof.clone(),
+        )
+    }
+
+    /// Verifies that the initial encrypted balance is provably zero.
+    pub fn verify_initial_balance(&self) -> Fallible<()> {
+        let balance: Balance = 0;
+        single_property_verifier(
+            &CorrectnessVerifier {
+                value: balance.into(),
+                pub_key: self.pub_account.owner_enc_pub_key,
+                cipher: self.initial_balance,
+                pc_gens: &PedersenGens::default(),
+            },
+            self.initial_balance_correctness_proof,
+        )
+    }
 
+    /// Lists the kinds of the proofs that this account carries, in the order of its fields.
+    /// The proofs are not verified.
+    pub fn describe_proofs(&self) -> Vec<ProofKind> {
+        vec![
+            ProofKind::Wellformedness,
+            ProofKind::Membership,
+            ProofKind::Correctness,
+        ]
+    }
+}
+
+impl StructuralCheck for PubAccountTx {
+    /// The size of the membership proof depends on the set of the valid asset ids, which is
+    /// not known here, so decoding the account is the whole check.
+    fn check_fields(&self) -> Fallible<()> {
         Ok(())
     }
 }
 
+/// Verifies the proof of `prove_balance_zero` that `enc_balance`, encrypted with `pub_key`,
+/// encrypts zero.
+pub fn verify_balance_zero(
+    pub_key: &EncryptionPubKey,
+    enc_balance: &EncryptedAmount,
+    proof: &DecryptionProof,
+) -> Fallible<()> {
+    verify_correct_decryption(pub_key, enc_balance, 0, proof)
+}
+
+/// The magic bytes at the start of a portable account.
+const PORTABLE_ACCOUNT_MAGIC: &[u8; 4] = b"MCPA";
+
+impl PubAccountTx {
+    /// Serializes the public account, its initial balance, and its proofs into a
+    /// self-describing blob, e.g., for backing up an account or moving it between nodes.
+    /// The layout is: `magic (4) | version (1) | SCALE encoding of the PubAccountTx`,
+    /// where the version is the `ProofVersion` of the versioned proofs.
+    pub fn export_portable(&self) -> Vec<u8> {
+        let mut blob = PORTABLE_ACCOUNT_MAGIC.to_vec();
+        blob.extend(encode_versioned(self));
+        blob
+    }
+
+    /// Deserializes a blob created by `export_portable`. Returns `UnsupportedProofVersion`
+    /// if the blob has an unknown version, and `MalformedPortableAccount` if it cannot be
+    /// deserialized. The proofs are not verified; use `AccountValidator` for that.
+    pub fn import_portable(blob: &[u8]) -> Fallible<PubAccountTx> {
+        ensure!(
+            blob.starts_with(PORTABLE_ACCOUNT_MAGIC),
+            ErrorKind::MalformedPortableAccount
+        );
+        let (version, account_tx) = decode_versioned(&blob[PORTABLE_ACCOUNT_MAGIC.len()..])
+            .map_err(|error| match error.kind() {
+                ErrorKind::MalformedProof => ErrorKind::MalformedPortableAccount.into(),
+                _ => error,
+            })?;
+        match version {
+            ProofVersion::V1 => Ok(account_tx),
+        }
+    }
+}
+
+pub struct AccountValidator;
+
+impl AccountCreatorVerifier for AccountValidator {
+    fn verify(&self, account: &PubAccountTx, valid_asset_ids: &[Scalar]) -> Fallible<()> {
+        ensure!(!valid_asset_ids.is_empty(), ErrorKind::NoValidAssetIds);
+        account.verify_wellformedness()?;
+        account.verify_initial_balance()?;
+        account.verify_membership(valid_asset_ids)?;
+
+        Ok(())
+    }
+}
+
+impl AccountValidator {
+    /// An optional check before closing an account: verifies that the owner has proven that
+    /// the account's encrypted balance is zero.
+    pub fn verify_closure(
+        &self,
+        account: &PubAccount,
+        enc_balance: &EncryptedAmount,
+        zero_balance_proof: &DecryptionProof,
+    ) -> Fallible<()> {
+        verify_balance_zero(&account.owner_enc_pub_key, enc_balance, zero_balance_proof)
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // -                                            Tests                                             -
 // ------------------------------------------------------------------------------------------------
@@ -166,7 +514,10 @@ mod tests {
     extern crate wasm_bindgen_test;
     use super::*;
     use crate::EncryptionKeys;
-    use cryptography_core::{asset_proofs::ElgamalSecretKey, curve25519_dalek::scalar::Scalar};
+    use cryptography_core::{
+        asset_proofs::{range_proof::prove_within_range, ElgamalSecretKey},
+        curve25519_dalek::scalar::Scalar,
+    };
     use rand::{rngs::StdRng, SeedableRng};
     use wasm_bindgen_test::*;
 
@@ -209,6 +560,162 @@ mod tests {
         result.unwrap();
     }
 
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_account_with_no_valid_asset_ids() {
+        let mut rng = StdRng::from_seed([12u8; 32]);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let enc_keys = EncryptionKeys {
+            public: elg_secret.get_public_key(),
+            secret: elg_secret,
+        };
+        let asset_id = AssetId::from(1);
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+        let secret_account = SecAccount {
+            enc_keys,
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+        let account_tx = AccountCreator
+            .create(&secret_account, &valid_asset_ids, &mut rng)
+            .unwrap();
+
+        assert_err!(
+            AccountValidator.verify(&account_tx, &[]),
+            ErrorKind::NoValidAssetIds
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_account_with_nonzero_initial_balance() {
+        // ----------------------- setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let enc_keys = EncryptionKeys {
+            public: elg_pub,
+            secret: elg_secret,
+        };
+        let asset_id = AssetId::from(1);
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+        let asset_id_witness = CommitmentWitness::from((asset_id.into(), &mut rng));
+        let secret_account = SecAccount {
+            enc_keys,
+            asset_id_witness,
+        };
+        let account_vldtr = AccountValidator;
+
+        // ----------------------- test
+        let account_tx = AccountCreator
+            .create(&secret_account, &valid_asset_ids, &mut rng)
+            .unwrap();
+
+        // Replace the initial balance with a nonzero one, along with a valid proof for it.
+        let balance: Balance = 5;
+        let balance_witness = CommitmentWitness::new(balance.into(), Scalar::random(&mut rng));
+        let mut bad_account_tx = account_tx.clone();
+        bad_account_tx.initial_balance = elg_pub.encrypt(&balance_witness);
+        bad_account_tx.initial_balance_correctness_proof = single_property_prover(
+            CorrectnessProverAwaitingChallenge {
+                pub_key: elg_pub,
+                w: balance_witness,
+                pc_gens: &PedersenGens::default(),
+            },
+            &mut rng,
+        )
+        .unwrap();
+        assert_err!(
+            account_vldtr.verify(&bad_account_tx, &valid_asset_ids),
+            ErrorKind::CorrectnessFinalResponseVerificationError { check: 2 }
+        );
+
+        // A default proof is rejected as well.
+        let mut bad_account_tx = account_tx;
+        bad_account_tx.initial_balance_correctness_proof = Default::default();
+        assert!(account_vldtr
+            .verify(&bad_account_tx, &valid_asset_ids)
+            .is_err());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_balance_zero_proof() {
+        let mut rng = StdRng::from_seed([11u8; 32]);
+        let account = new_account(1, &mut rng);
+        let pub_key = account.secret.enc_keys.public;
+
+        // A drained account: a deposit and a withdrawal of the same amount, with different
+        // blinding factors, cancel each other out.
+        let (_, deposit_amount) = pub_key.encrypt_value(5u32.into(), &mut rng);
+        let (_, withdraw_amount) = pub_key.encrypt_value(5u32.into(), &mut rng);
+        let zero_balance = &deposit_amount - &withdraw_amount;
+        let proof = prove_balance_zero(&account.secret, &zero_balance, &mut rng).unwrap();
+        verify_balance_zero(&pub_key, &zero_balance, &proof).unwrap();
+        AccountValidator
+            .verify_closure(&account.public, &zero_balance, &proof)
+            .unwrap();
+
+        // A nonzero balance cannot be proven to be zero.
+        let nonzero_balance = deposit_amount;
+        assert!(verify_balance_zero(&pub_key, &nonzero_balance, &proof).is_err());
+        let bad_proof = prove_balance_zero(&account.secret, &nonzero_balance, &mut rng).unwrap();
+        assert_err!(
+            AccountValidator.verify_closure(&account.public, &nonzero_balance, &bad_proof),
+            ErrorKind::CiphertextRefreshmentFinalResponseVerificationError { check: 1 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_account_proofs_verified_independently() {
+        // ----------------------- setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let valid_asset_ids = convert_asset_ids(vec![AssetId::from(1), AssetId::from(2)]);
+        let mut create_account_tx = |asset_id: u32| {
+            let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+            let secret_account = SecAccount {
+                enc_keys: EncryptionKeys {
+                    public: elg_secret.get_public_key(),
+                    secret: elg_secret,
+                },
+                asset_id_witness: CommitmentWitness::from((
+                    AssetId::from(asset_id).into(),
+                    &mut rng,
+                )),
+            };
+            AccountCreator
+                .create(&secret_account, &valid_asset_ids, &mut rng)
+                .unwrap()
+        };
+        let account_tx = create_account_tx(1);
+        let other_account_tx = create_account_tx(2);
+
+        // ----------------------- test
+        assert!(account_tx.verify_wellformedness().is_ok());
+        assert!(account_tx.verify_membership(&valid_asset_ids).is_ok());
+        assert!(account_tx.verify_initial_balance().is_ok());
+
+        // Only the check of the tampered proof fails.
+        let mut bad_account_tx = account_tx.clone();
+        bad_account_tx.asset_wellformedness_proof = other_account_tx.asset_wellformedness_proof;
+        assert!(bad_account_tx.verify_wellformedness().is_err());
+        assert!(bad_account_tx.verify_membership(&valid_asset_ids).is_ok());
+        assert!(bad_account_tx.verify_initial_balance().is_ok());
+
+        let mut bad_account_tx = account_tx.clone();
+        bad_account_tx.asset_membership_proof = other_account_tx.asset_membership_proof.clone();
+        assert!(bad_account_tx.verify_wellformedness().is_ok());
+        assert!(bad_account_tx.verify_membership(&valid_asset_ids).is_err());
+        assert!(bad_account_tx.verify_initial_balance().is_ok());
+
+        let mut bad_account_tx = account_tx;
+        bad_account_tx.initial_balance_correctness_proof =
+            other_account_tx.initial_balance_correctness_proof;
+        assert!(bad_account_tx.verify_wellformedness().is_ok());
+        assert!(bad_account_tx.verify_membership(&valid_asset_ids).is_ok());
+        assert!(bad_account_tx.verify_initial_balance().is_err());
+    }
+
     #[test]
     #[wasm_bindgen_test]
     fn test_account_updates() {
@@ -272,4 +779,305 @@ mod tests {
             .unwrap();
         assert_eq!(balance, 5);
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_apply_debit() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+
+        let balance: Balance = 10;
+        let (balance_witness, enc_balance) = elg_pub.encrypt_value(balance.into(), &mut rng);
+
+        // Positive test: debit an amount that is covered by the balance.
+        let amount: Balance = 4;
+        let (amount_witness, enc_amount) = elg_pub.encrypt_value(amount.into(), &mut rng);
+        let range_proof = prove_within_range(
+            (balance - amount).into(),
+            balance_witness.blinding() - amount_witness.blinding(),
+            BALANCE_RANGE,
+            &mut rng,
+        )
+        .unwrap();
+
+        let new_enc_balance =
+            apply_debit(&enc_balance, &enc_amount, &range_proof, &mut rng).unwrap();
+        assert_eq!(elg_secret.decrypt(&new_enc_balance).unwrap(), 6);
+
+        // Negative test: the range proof does not belong to the resulting balance.
+        let (_, other_enc_amount) = elg_pub.encrypt_value(amount.into(), &mut rng);
+        assert_err!(
+            apply_debit(&enc_balance, &other_enc_amount, &range_proof, &mut rng),
+            ErrorKind::InsufficientBalance
+        );
+
+        // Negative test: debit an amount that is larger than the balance.
+        let amount: Balance = 15;
+        let (amount_witness, enc_amount) = elg_pub.encrypt_value(amount.into(), &mut rng);
+        let range_proof = prove_within_range(
+            u64::from(balance).wrapping_sub(amount.into()),
+            balance_witness.blinding() - amount_witness.blinding(),
+            BALANCE_RANGE,
+            &mut rng,
+        )
+        .unwrap();
+        assert_err!(
+            apply_debit(&enc_balance, &enc_amount, &range_proof, &mut rng),
+            ErrorKind::InsufficientBalance
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_account_diff() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let enc_keys = EncryptionKeys {
+            public: elg_secret.get_public_key(),
+            secret: elg_secret,
+        };
+        let valid_asset_ids = convert_asset_ids(vec![AssetId::from(1)]);
+        let secret_account = SecAccount {
+            enc_keys,
+            asset_id_witness: CommitmentWitness::from((AssetId::from(1).into(), &mut rng)),
+        };
+        let account_tx = AccountCreator
+            .create(&secret_account, &valid_asset_ids, &mut rng)
+            .unwrap();
+        let account = account_tx.pub_account;
+        let balance = account_tx.initial_balance;
+
+        // Same snapshot.
+        assert!(account.diff(&balance, &account, &balance).is_empty());
+
+        // Only the balance changes after a deposit.
+        let ten: Balance = 10;
+        let ten = secret_account
+            .enc_keys
+            .public
+            .encrypt_value(ten.into(), &mut rng)
+            .1;
+        let new_balance = deposit(&balance, &ten);
+        let diff = account.diff(&balance, &account, &new_balance);
+        assert!(!diff.enc_asset_id_changed);
+        assert!(!diff.owner_enc_pub_key_changed);
+        assert_eq!(diff.balance_change, Some((balance, new_balance)));
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_verify_asset_id_equality() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issuer_enc_pub_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let mediator_enc_pub_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let gens = PedersenGens::default();
+
+        let asset_id_witness = CommitmentWitness::from((AssetId::from(1).into(), &mut rng));
+        let issuer_enc_asset_id = issuer_enc_pub_key.encrypt(&asset_id_witness);
+        let mediator_enc_asset_id = mediator_enc_pub_key.encrypt(&asset_id_witness);
+        let proof = single_property_prover(
+            EncryptingSameValueProverAwaitingChallenge {
+                pub_key1: issuer_enc_pub_key,
+                pub_key2: mediator_enc_pub_key,
+                w: Zeroizing::new(asset_id_witness.clone()),
+                pc_gens: &gens,
+            },
+            &mut rng,
+        )
+        .unwrap();
+
+        // Positive test.
+        assert!(verify_asset_id_equality(
+            issuer_enc_pub_key,
+            issuer_enc_asset_id,
+            mediator_enc_pub_key,
+            mediator_enc_asset_id,
+            proof,
+        )
+        .is_ok());
+
+        // Negative test: the mediator's ciphertext encrypts a different asset id.
+        let other_witness =
+            CommitmentWitness::new(AssetId::from(2).into(), asset_id_witness.blinding());
+        let other_mediator_enc_asset_id = mediator_enc_pub_key.encrypt(&other_witness);
+        assert!(verify_asset_id_equality(
+            issuer_enc_pub_key,
+            issuer_enc_asset_id,
+            mediator_enc_pub_key,
+            other_mediator_enc_asset_id,
+            proof,
+        )
+        .is_err());
+    }
+
+    fn new_account(asset_id: u32, rng: &mut StdRng) -> Account {
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(rng));
+        let enc_keys = EncryptionKeys {
+            public: elg_secret.get_public_key(),
+            secret: elg_secret,
+        };
+        let asset_id_witness = CommitmentWitness::from((AssetId::from(asset_id).into(), rng));
+        let public = PubAccount {
+            enc_asset_id: enc_keys.public.encrypt(&asset_id_witness),
+            owner_enc_pub_key: enc_keys.public,
+        };
+        Account {
+            public,
+            secret: SecAccount {
+                enc_keys,
+                asset_id_witness,
+            },
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_same_asset_type() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let account_a = new_account(1, &mut rng);
+        let account_b = new_account(1, &mut rng);
+
+        // Positive test.
+        let proof = prove_same_asset_type(&account_a.secret, &account_b.public, &mut rng).unwrap();
+        // The proof is not valid until it is confirmed by the owner of the second account.
+        assert_err!(
+            verify_same_asset_type(&account_a.public, &account_b.public, &proof),
+            ErrorKind::VerificationError
+        );
+        let proof = confirm_same_asset_type(proof, &account_b, &mut rng).unwrap();
+        assert!(verify_same_asset_type(&account_a.public, &account_b.public, &proof).is_ok());
+
+        // Negative test: the proof does not hold for a third account.
+        let account_c = new_account(1, &mut rng);
+        assert!(verify_same_asset_type(&account_a.public, &account_c.public, &proof).is_err());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_different_asset_type() {
+        let mut rng = StdRng::from_seed([11u8; 32]);
+        let account_a = new_account(1, &mut rng);
+        let account_b = new_account(2, &mut rng);
+
+        let proof = prove_same_asset_type(&account_a.secret, &account_b.public, &mut rng).unwrap();
+        let proof = confirm_same_asset_type(proof, &account_b, &mut rng).unwrap();
+        assert!(verify_same_asset_type(&account_a.public, &account_b.public, &proof).is_err());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_account_content_hash() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let enc_keys = EncryptionKeys {
+            public: elg_secret.get_public_key(),
+            secret: elg_secret,
+        };
+        let asset_id_witness = CommitmentWitness::from((AssetId::from(1).into(), &mut rng));
+        let account = PubAccount {
+            enc_asset_id: enc_keys.public.encrypt(&asset_id_witness),
+            owner_enc_pub_key: enc_keys.public,
+        };
+
+        // Equal accounts have the same hash.
+        assert_eq!(account.content_hash(), account.clone().content_hash());
+
+        // Re-encrypting the same asset id changes the account and its hash.
+        let other_witness = CommitmentWitness::from((AssetId::from(1).into(), &mut rng));
+        let other_account = PubAccount {
+            enc_asset_id: enc_keys.public.encrypt(&other_witness),
+            owner_enc_pub_key: enc_keys.public,
+        };
+        assert_ne!(account.content_hash(), other_account.content_hash());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_account_derive_id() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let account = new_account(1, &mut rng);
+        let other_account = new_account(1, &mut rng);
+
+        // The id is stable, and does not depend on the encrypted asset id.
+        let mut reencrypted = account.public.clone();
+        reencrypted.enc_asset_id =
+            account
+                .secret
+                .enc_keys
+                .public
+                .encrypt(&CommitmentWitness::from((
+                    AssetId::from(1).into(),
+                    &mut rng,
+                )));
+        assert_eq!(account.public.derive_id(), account.public.derive_id());
+        assert_eq!(account.public.derive_id(), reencrypted.derive_id());
+
+        // Different keys derive different ids.
+        assert_ne!(account.public.derive_id(), other_account.public.derive_id());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_portable_account() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let account = new_account(1, &mut rng);
+        let valid_asset_ids = convert_asset_ids(vec![AssetId::from(1)]);
+        let account_tx = AccountCreator
+            .create(&account.secret, &valid_asset_ids, &mut rng)
+            .unwrap();
+
+        // The imported account is the same, and its proofs still verify.
+        let blob = account_tx.export_portable();
+        let imported = PubAccountTx::import_portable(&blob).unwrap();
+        assert_eq!(imported, account_tx);
+        AccountValidator
+            .verify(&imported, &valid_asset_ids)
+            .unwrap();
+
+        // An unknown version is rejected.
+        let mut bad_blob = blob.clone();
+        bad_blob[PORTABLE_ACCOUNT_MAGIC.len()] = 99;
+        assert_err!(
+            PubAccountTx::import_portable(&bad_blob),
+            ErrorKind::UnsupportedProofVersion { version: 99 }
+        );
+
+        // So are truncated blobs, and blobs with trailing bytes.
+        assert_err!(
+            PubAccountTx::import_portable(&blob[..blob.len() - 1]),
+            ErrorKind::MalformedPortableAccount
+        );
+        let mut long_blob = blob;
+        long_blob.push(0);
+        assert_err!(
+            PubAccountTx::import_portable(&long_blob),
+            ErrorKind::MalformedPortableAccount
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_generate_accounts_par() {
+        let seed = [7u8; 32];
+        let asset_id = AssetId::from(1);
+
+        let accounts = generate_accounts_par(16, seed, asset_id);
+        let other_accounts = generate_accounts_par(16, seed, asset_id);
+        assert_eq!(accounts.len(), 16);
+        for ((sec, public), (other_sec, other_public)) in accounts.iter().zip(other_accounts.iter())
+        {
+            assert_eq!(sec.encode(), other_sec.encode());
+            assert_eq!(public, other_public);
+        }
+
+        // Accounts are distinct and do not depend on the total number of accounts.
+        assert_ne!(accounts[0].1, accounts[1].1);
+        let fewer_accounts = generate_accounts_par(4, seed, asset_id);
+        assert_eq!(fewer_accounts[3].1, accounts[3].1);
+
+        // A different seed yields different accounts.
+        let accounts_from_other_seed = generate_accounts_par(1, [8u8; 32], asset_id);
+        assert_ne!(accounts_from_other_seed[0].1, accounts[0].1);
+    }
 }
@@ -2,102 +2,124 @@
 
 use crate::{
     account::deposit, Account, AssetMemo, AssetTransactionAuditor, AssetTransactionIssuer,
-    AssetTransactionVerifier, AuditorAccount, AuditorPayload, AuditorPubAccount, EncryptedAmount,
-    EncryptionPubKey, InitializedAssetTx, PubAccount,
+    AssetTransactionVerifier, AssetTxMetadata, AuditorAccount, AuditorPayload, AuditorPubAccount,
+    EncryptedAmount, EncryptionPubKey, InitializedAssetTx, IssuanceCiphertexts, ProofKind,
+    PubAccount, StructuralCheck, MAX_ASSET_METADATA_LEN, MAX_BALANCE,
 };
 use cryptography_core::asset_proofs::{
     bulletproofs::PedersenGens,
-    correctness_proof::{CorrectnessProverAwaitingChallenge, CorrectnessVerifier},
+    correctness_proof::{
+        CorrectnessProof, CorrectnessProverAwaitingChallenge, CorrectnessVerifier,
+    },
     encrypting_same_value_proof::{
-        EncryptingSameValueProverAwaitingChallenge, EncryptingSameValueVerifier,
+        CipherEqualDifferentPubKeyProof, EncryptingSameValueProverAwaitingChallenge,
+        EncryptingSameValueVerifier,
+    },
+    encryption_proofs::{
+        single_property_prover, single_property_prover_with_context, single_property_verifier,
+        single_property_verifier_with_context, AssetProofVerifier, ZKProofResponse,
     },
-    encryption_proofs::single_property_prover,
-    encryption_proofs::single_property_verifier,
     errors::{ErrorKind, Fallible},
-    wellformedness_proof::{WellformednessProverAwaitingChallenge, WellformednessVerifier},
-    Balance, CommitmentWitness,
+    wellformedness_proof::{
+        WellformednessProof, WellformednessProverAwaitingChallenge, WellformednessVerifier,
+    },
+    Balance, BatchLimits, CommitmentWitness,
 };
+use cryptography_core::curve25519_dalek::scalar::Scalar;
 
+use codec::{Decode, Encode};
 use rand_core::{CryptoRng, RngCore};
-use sp_std::vec::Vec;
+use sha3::{digest::FixedOutput, Digest, Sha3_256};
+use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
 use zeroize::Zeroizing;
 
+fn verify_metadata_length(metadata: &[u8]) -> Fallible<()> {
+    ensure!(
+        metadata.len() <= MAX_ASSET_METADATA_LEN,
+        ErrorKind::MetadataTooLarge {
+            length: metadata.len(),
+            max_length: MAX_ASSET_METADATA_LEN
+        }
+    );
+    Ok(())
+}
+
+/// Verifies one of the sigma proofs of an asset issuance. All of them are bound to the
+/// `metadata` of the issuance, so that it cannot be altered without invalidating them. The
+/// tests count the calls, to check that a malformed issuance is rejected before any of its
+/// proofs is verified.
+fn verify_issuance_proof<Verifier: AssetProofVerifier>(
+    verifier: &Verifier,
+    proof: ZKProofResponse<Verifier::ZKInitialMessage, Verifier::ZKFinalResponse>,
+    metadata: &[u8],
+) -> Fallible<()> {
+    #[cfg(test)]
+    tests::VERIFIED_PROOFS.with(|count| count.set(count.get() + 1));
+    single_property_verifier_with_context(verifier, proof, metadata)
+}
+
 /// Helper function to verify the proofs on an asset initialization transaction.
 fn asset_issuance_init_verify_proofs(
     asset_tx: &InitializedAssetTx,
     issr_pub_account: &PubAccount,
+    gens: &PedersenGens,
 ) -> Fallible<()> {
-    let gens = PedersenGens::default();
-
     // Verify the proof of memo's wellformedness.
-    single_property_verifier(
+    verify_issuance_proof(
         &WellformednessVerifier {
             pub_key: issr_pub_account.owner_enc_pub_key,
             cipher: asset_tx.memo.enc_issued_amount,
-            pc_gens: &gens,
+            pc_gens: gens,
         },
         asset_tx.balance_wellformedness_proof,
+        &asset_tx.memo.metadata,
     )?;
 
     Ok(())
 }
 
-fn asset_issuance_init_verify(
-    asset_tx: &InitializedAssetTx,
-    issr_pub_account: &PubAccount,
-    auditors_enc_pub_keys: &[AuditorPubAccount],
-) -> Fallible<()> {
-    asset_issuance_init_verify_proofs(asset_tx, issr_pub_account)?;
-
-    // Verify auditors payload.
-    verify_auditor_payload(
-        &asset_tx.auditors_payload,
-        auditors_enc_pub_keys,
-        issr_pub_account.owner_enc_pub_key,
-        asset_tx.memo.enc_issued_amount,
-    )
-}
-
 fn verify_auditor_payload(
     auditors_payload: &[AuditorPayload],
     auditors_enc_pub_keys: &[AuditorPubAccount],
     issuer_enc_pub_key: EncryptionPubKey,
     issuer_enc_amount: EncryptedAmount,
+    metadata: &[u8],
+    gens: &PedersenGens,
 ) -> Fallible<()> {
     ensure!(
         auditors_payload.len() == auditors_enc_pub_keys.len(),
         ErrorKind::AuditorPayloadError
     );
 
-    let gens = &PedersenGens::default();
-    let _: Fallible<()> = auditors_enc_pub_keys
+    auditors_enc_pub_keys
         .iter()
         .map(|auditor| {
             let mut found_auditor = false;
-            let _: Fallible<()> = auditors_payload
+            auditors_payload
                 .iter()
                 .map(|payload| {
                     if auditor.auditor_id == payload.auditor_id {
                         // Verify that the encrypted amounts are equal.
-                        single_property_verifier(
+                        verify_issuance_proof(
                             &EncryptingSameValueVerifier {
                                 pub_key1: issuer_enc_pub_key,
                                 pub_key2: auditor.encryption_public_key,
                                 cipher1: issuer_enc_amount,
                                 cipher2: payload.encrypted_amount.elgamal_cipher,
-                                pc_gens: &gens,
+                                pc_gens: gens,
                             },
                             payload.amount_equal_cipher_proof,
+                            metadata,
                         )?;
                         found_auditor |= true;
                     }
                     Ok(())
                 })
-                .collect();
+                .collect::<Fallible<()>>()?;
             ensure!(found_auditor, ErrorKind::AuditorPayloadError);
             Ok(())
         })
-        .collect();
+        .collect::<Fallible<()>>()?;
 
     Ok(())
 }
@@ -114,10 +136,50 @@ impl AssetTransactionIssuer for AssetIssuer {
     fn initialize_asset_transaction<T: RngCore + CryptoRng>(
         &self,
         issr_account: &Account,
+        valid_asset_ids: &[Scalar],
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        amount: Balance,
+        rng: &mut T,
+    ) -> Fallible<InitializedAssetTx> {
+        self.initialize_asset_transaction_with_metadata(
+            issr_account,
+            valid_asset_ids,
+            auditors_enc_pub_keys,
+            amount,
+            Vec::new(),
+            rng,
+        )
+    }
+}
+
+impl AssetIssuer {
+    /// Same as `initialize_asset_transaction`, but attaches the opaque `metadata` to the
+    /// issuance. Returns `MetadataTooLarge` if it is longer than `MAX_ASSET_METADATA_LEN`.
+    pub fn initialize_asset_transaction_with_metadata<T: RngCore + CryptoRng>(
+        &self,
+        issr_account: &Account,
+        valid_asset_ids: &[Scalar],
         auditors_enc_pub_keys: &[AuditorPubAccount],
         amount: Balance,
+        metadata: Vec<u8>,
         rng: &mut T,
     ) -> Fallible<InitializedAssetTx> {
+        verify_metadata_length(&metadata)?;
+        ensure!(
+            u64::from(amount) <= MAX_BALANCE,
+            ErrorKind::BalanceOverflow {
+                amount: amount.into(),
+                max_balance: MAX_BALANCE
+            }
+        );
+
+        // Fail early if the issuer's account is not for a registered asset. The membership proof
+        // of the account creation enforces this as well, but only at the validation time.
+        ensure!(
+            valid_asset_ids.contains(&issr_account.secret.asset_id_witness.value()),
+            ErrorKind::UnknownAssetType
+        );
+
         let gens = PedersenGens::default();
 
         // Encrypt the balance to issuer's public key (memo).
@@ -128,25 +190,28 @@ impl AssetTransactionIssuer for AssetIssuer {
             .encrypt_value(amount.into(), rng);
         let memo = AssetMemo {
             enc_issued_amount: issr_enc_amount,
+            metadata,
         };
 
-        // Proof of memo's wellformedness.
-        let memo_wellformedness_proof = single_property_prover(
+        // Proof of memo's wellformedness. This and the other proofs are bound to the metadata.
+        let memo_wellformedness_proof = single_property_prover_with_context(
             WellformednessProverAwaitingChallenge {
                 pub_key: issr_account.secret.enc_keys.public,
                 w: Zeroizing::new(issr_amount_witness.clone()),
                 pc_gens: &gens,
             },
+            &memo.metadata,
             rng,
         )?;
 
         // Proof of memo's correctness.
-        let memo_correctness_proof = single_property_prover(
+        let memo_correctness_proof = single_property_prover_with_context(
             CorrectnessProverAwaitingChallenge {
                 pub_key: issr_account.secret.enc_keys.public,
                 w: issr_amount_witness.clone(),
                 pc_gens: &gens,
             },
+            &memo.metadata,
             rng,
         )?;
 
@@ -155,6 +220,7 @@ impl AssetTransactionIssuer for AssetIssuer {
             auditors_enc_pub_keys,
             &issr_account.secret.enc_keys.public,
             &issr_amount_witness,
+            &memo.metadata,
             rng,
         )?;
 
@@ -173,6 +239,7 @@ fn add_asset_transaction_auditor<T: RngCore + CryptoRng>(
     auditors_enc_pub_keys: &[AuditorPubAccount],
     issuer_enc_pub_key: &EncryptionPubKey,
     amount_witness: &CommitmentWitness,
+    metadata: &[u8],
     rng: &mut T,
 ) -> Fallible<Vec<AuditorPayload>> {
     let gens = PedersenGens::default();
@@ -188,13 +255,14 @@ fn add_asset_transaction_auditor<T: RngCore + CryptoRng>(
 
             // Prove that the sender and auditor's ciphertexts are encrypting the same
             // commitment witness.
-            let amount_equal_cipher_proof = single_property_prover(
+            let amount_equal_cipher_proof = single_property_prover_with_context(
                 EncryptingSameValueProverAwaitingChallenge {
                     pub_key1: *issuer_enc_pub_key,
                     pub_key2: auditor.encryption_public_key,
                     w: Zeroizing::new(amount_witness.clone()),
                     pc_gens: &gens,
                 },
+                metadata,
                 rng,
             )?;
 
@@ -209,61 +277,542 @@ fn add_asset_transaction_auditor<T: RngCore + CryptoRng>(
         })
         .collect();
 
+    // Sort the payloads by the auditor id, so that the encoding of the transaction does not
+    // depend on the order in which the auditors were given.
+    payload_vec.sort_by(|a, b| a.auditor_id.cmp(&b.auditor_id));
+
     Ok(payload_vec)
 }
 
+/// Encrypts the issued `amount` to the issuer, the mediator, and the optional auditor in one
+/// go. All the ciphertexts share the same commitment witness, and the mediator's and the
+/// auditor's ciphertexts are proven to encrypt the same amount as the issuer's.
+pub fn encrypt_issuance_amount<T: RngCore + CryptoRng>(
+    amount: Balance,
+    issuer_key: &EncryptionPubKey,
+    mediator_key: &EncryptionPubKey,
+    auditor_key: Option<&EncryptionPubKey>,
+    rng: &mut T,
+) -> Fallible<IssuanceCiphertexts> {
+    ensure!(
+        u64::from(amount) <= MAX_BALANCE,
+        ErrorKind::BalanceOverflow {
+            amount: amount.into(),
+            max_balance: MAX_BALANCE
+        }
+    );
+
+    let gens = PedersenGens::default();
+    let witness = CommitmentWitness::new(amount.into(), Scalar::random(rng));
+    let mut encrypt_to = |pub_key: &EncryptionPubKey| -> Fallible<_> {
+        let cipher = pub_key.encrypt(&witness);
+        let proof = single_property_prover(
+            EncryptingSameValueProverAwaitingChallenge {
+                pub_key1: *issuer_key,
+                pub_key2: *pub_key,
+                w: Zeroizing::new(witness.clone()),
+                pc_gens: &gens,
+            },
+            rng,
+        )?;
+        Ok((cipher, proof))
+    };
+
+    let (mediator, mediator_equal_cipher_proof) = encrypt_to(mediator_key)?;
+    let auditor = auditor_key.map(encrypt_to).transpose()?;
+
+    Ok(IssuanceCiphertexts {
+        issuer: issuer_key.encrypt(&witness),
+        mediator,
+        mediator_equal_cipher_proof,
+        auditor,
+    })
+}
+
+impl IssuanceCiphertexts {
+    /// Verifies that the mediator's and the auditor's ciphertexts encrypt the same amount as
+    /// the issuer's. Fails if an auditor key is given but there is no auditor ciphertext, or
+    /// vice versa.
+    pub fn verify(
+        &self,
+        issuer_key: &EncryptionPubKey,
+        mediator_key: &EncryptionPubKey,
+        auditor_key: Option<&EncryptionPubKey>,
+    ) -> Fallible<()> {
+        let gens = PedersenGens::default();
+        let verify_equal = |pub_key: &EncryptionPubKey,
+                            cipher: EncryptedAmount,
+                            proof: CipherEqualDifferentPubKeyProof| {
+            single_property_verifier(
+                &EncryptingSameValueVerifier {
+                    pub_key1: *issuer_key,
+                    pub_key2: *pub_key,
+                    cipher1: self.issuer,
+                    cipher2: cipher,
+                    pc_gens: &gens,
+                },
+                proof,
+            )
+        };
+
+        verify_equal(
+            mediator_key,
+            self.mediator,
+            self.mediator_equal_cipher_proof,
+        )?;
+        match (auditor_key, &self.auditor) {
+            (Some(key), Some((cipher, proof))) => verify_equal(key, *cipher, *proof),
+            (None, None) => Ok(()),
+            _ => Err(ErrorKind::AuditorPayloadError.into()),
+        }
+    }
+}
+
+/// The domain label of the hash of the proofs blob of a split asset transaction.
+const ASSET_TX_PROOFS_LABEL: &[u8] = b"PolymathMercatAssetTxProofs";
+
+/// The proofs of an asset transaction, in the order of its fields.
+type AssetTxProofs = (
+    WellformednessProof,
+    CorrectnessProof,
+    Vec<CipherEqualDifferentPubKeyProof>,
+);
+
+fn asset_tx_proofs_hash(proofs: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(
+        &Sha3_256::default()
+            .chain(ASSET_TX_PROOFS_LABEL)
+            .chain(proofs)
+            .fixed_result(),
+    );
+    hash
+}
+
+impl InitializedAssetTx {
+    /// Splits the transaction into its metadata and the encoded blob of its proofs, so that
+    /// the two can be stored in different places. `reassemble` is the inverse.
+    pub fn split(&self) -> (AssetTxMetadata, Vec<u8>) {
+        let proofs: AssetTxProofs = (
+            self.balance_wellformedness_proof,
+            self.balance_correctness_proof,
+            self.auditors_payload
+                .iter()
+                .map(|payload| payload.amount_equal_cipher_proof)
+                .collect(),
+        );
+        let proofs = proofs.encode();
+
+        let metadata = AssetTxMetadata {
+            account_id: self.account_id,
+            memo: self.memo.clone(),
+            auditors: self
+                .auditors_payload
+                .iter()
+                .map(|payload| (payload.auditor_id, payload.encrypted_amount))
+                .collect(),
+            proofs_hash: asset_tx_proofs_hash(&proofs),
+        };
+        (metadata, proofs)
+    }
+
+    /// Recombines the metadata and the proofs blob of `split` into the transaction. Returns
+    /// `ProofsHashMismatch` if the proofs are not the ones that the metadata commits to. The
+    /// proofs are not verified.
+    pub fn reassemble(metadata: AssetTxMetadata, proofs: &[u8]) -> Fallible<Self> {
+        ensure!(
+            asset_tx_proofs_hash(proofs) == metadata.proofs_hash,
+            ErrorKind::ProofsHashMismatch
+        );
+        let mut input = proofs;
+        let (balance_wellformedness_proof, balance_correctness_proof, equality_proofs) =
+            AssetTxProofs::decode(&mut input).map_err(|_| ErrorKind::MalformedTransaction)?;
+        ensure!(
+            input.is_empty() && equality_proofs.len() == metadata.auditors.len(),
+            ErrorKind::MalformedTransaction
+        );
+
+        let auditors_payload = metadata
+            .auditors
+            .into_iter()
+            .zip(equality_proofs)
+            .map(
+                |((auditor_id, encrypted_amount), amount_equal_cipher_proof)| AuditorPayload {
+                    auditor_id,
+                    encrypted_amount,
+                    amount_equal_cipher_proof,
+                },
+            )
+            .collect();
+
+        Ok(InitializedAssetTx {
+            account_id: metadata.account_id,
+            memo: metadata.memo,
+            balance_wellformedness_proof,
+            balance_correctness_proof,
+            auditors_payload,
+        })
+    }
+
+    /// Lists the kinds of the proofs that this transaction carries, in the order of its
+    /// fields, followed by an equality proof per auditor. The proofs are not verified.
+    pub fn describe_proofs(&self) -> Vec<ProofKind> {
+        let mut proofs = vec![ProofKind::Wellformedness, ProofKind::Correctness];
+        proofs.extend(
+            self.auditors_payload
+                .iter()
+                .map(|_| ProofKind::EqualityAcrossKeys),
+        );
+        proofs
+    }
+}
+
+impl StructuralCheck for InitializedAssetTx {
+    fn check_fields(&self) -> Fallible<()> {
+        ensure!(
+            self.memo.metadata.len() <= MAX_ASSET_METADATA_LEN,
+            ErrorKind::MetadataTooLarge {
+                length: self.memo.metadata.len(),
+                max_length: MAX_ASSET_METADATA_LEN,
+            }
+        );
+        Ok(())
+    }
+}
+
 // -------------------------------------------------------------------------------------
 // -                                    Validator                                      -
 // -------------------------------------------------------------------------------------
 
 pub struct AssetValidator;
 
-/// Called by validators to verify the ZKP of the wellformedness of encrypted balance.
-fn verify_initialization(
-    asset_tx: &InitializedAssetTx,
-    issr_pub_account: &PubAccount,
+/// Selects the checks that are performed by `verify_asset_transaction_with_options`.
+///
+/// WARNING: Disabling any of the checks is NOT safe for production. This is only meant
+/// for benchmarks that need to isolate the cost of each check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// Verify the issuer's wellformedness and correctness proofs.
+    pub check_proofs: bool,
+    /// Verify that the auditors' payload is complete and encrypts the issued amount.
+    pub check_auditors_payload: bool,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        VerifyOptions {
+            check_proofs: true,
+            check_auditors_payload: true,
+        }
+    }
+}
+
+/// The label that is hashed into the keys of the `VerificationCache`.
+const VERIFICATION_CACHE_LABEL: &[u8] = b"PolymathMercatVerificationCache";
+
+/// A bounded cache of the results of asset issuance verifications, which lets a validator
+/// skip the expensive verification of a transaction that it has already processed, e.g.,
+/// after a restart. The entries are keyed by the SHA3-256 hash of all the inputs of the
+/// verification, and the least recently used entry is evicted when the cache is full.
+///
+/// The cache is owned by the caller and passed to
+/// `AssetValidator::verify_asset_transaction_cached` explicitly.
+#[derive(Clone, Debug)]
+pub struct VerificationCache {
+    capacity: usize,
+    /// The entries, ordered from the least to the most recently used.
+    entries: Vec<([u8; 32], Result<EncryptedAmount, ErrorKind>)>,
+    hits: usize,
+}
+
+impl VerificationCache {
+    /// Creates an empty cache that holds at most `capacity` results.
+    pub fn new(capacity: usize) -> Self {
+        VerificationCache {
+            capacity,
+            entries: Vec::with_capacity(capacity),
+            hits: 0,
+        }
+    }
+
+    /// The number of cached results.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no results are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of lookups that were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    fn get(&mut self, key: &[u8; 32]) -> Option<Fallible<EncryptedAmount>> {
+        let index = self
+            .entries
+            .iter()
+            .position(|(entry_key, _)| entry_key == key)?;
+        let entry = self.entries.remove(index);
+        let result = entry.1.clone().map_err(Into::into);
+        self.entries.push(entry);
+        self.hits += 1;
+        Some(result)
+    }
+
+    fn insert(&mut self, key: [u8; 32], result: &Fallible<EncryptedAmount>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.remove(0);
+        }
+        let result = match result {
+            Ok(balance) => Ok(*balance),
+            Err(error) => Err(error.kind().clone()),
+        };
+        self.entries.push((key, result));
+    }
+}
+
+/// Computes the key of a verification in the `VerificationCache`.
+fn verification_cache_key(
+    amount: u32,
+    initialized_asset_tx: &InitializedAssetTx,
+    issr_account: &PubAccount,
+    issr_init_balance: &EncryptedAmount,
     auditors_enc_pub_keys: &[AuditorPubAccount],
-) -> Fallible<()> {
-    Ok(asset_issuance_init_verify(
-        asset_tx,
-        issr_pub_account,
-        auditors_enc_pub_keys,
-    )?)
+) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key.copy_from_slice(
+        &Sha3_256::default()
+            .chain(VERIFICATION_CACHE_LABEL)
+            .chain(amount.encode())
+            .chain(initialized_asset_tx.encode())
+            .chain(issr_account.encode())
+            .chain(issr_init_balance.encode())
+            .chain(auditors_enc_pub_keys.encode())
+            .fixed_result(),
+    );
+    key
 }
 
-impl AssetTransactionVerifier for AssetValidator {
-    /// Called by validators to verify the justification and processing of the transaction.
-    fn verify_asset_transaction(
+/// An asset issuance transaction, along with the issuer's public account and balance,
+/// to be verified by `AssetValidator::verify_asset_transactions_batch`.
+#[derive(Clone, Debug)]
+pub struct AssetIssuanceToVerify {
+    pub amount: u32,
+    pub initialized_asset_tx: InitializedAssetTx,
+    pub issr_account: PubAccount,
+    pub issr_init_balance: EncryptedAmount,
+}
+
+impl AssetValidator {
+    /// Runs the checks of `verify_asset_transaction` that only need the transaction and the
+    /// issuer's public account, i.e., the metadata length and the wellformedness proof of the
+    /// issued amount. This lets a validator reject a malformed issuance as soon as it is
+    /// submitted. Passing this check does not replace `verify_asset_transaction`.
+    ///
+    /// Note that an issuance is not justified by a mediator, so there is no mediator
+    /// signature or key to check.
+    pub fn pre_verify_issuance(
+        &self,
+        initialized_asset_tx: &InitializedAssetTx,
+        issr_account: &PubAccount,
+    ) -> Fallible<()> {
+        verify_metadata_length(&initialized_asset_tx.memo.metadata)?;
+        asset_issuance_init_verify_proofs(
+            initialized_asset_tx,
+            issr_account,
+            &PedersenGens::default(),
+        )
+    }
+
+    /// Same as `verify_asset_transaction`, but only performs the checks that are
+    /// enabled in `options`. See the warning on `VerifyOptions`.
+    pub fn verify_asset_transaction_with_options(
+        &self,
+        amount: u32,
+        initialized_asset_tx: &InitializedAssetTx,
+        issr_account: &PubAccount,
+        issr_init_balance: &EncryptedAmount,
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        options: VerifyOptions,
+    ) -> Fallible<EncryptedAmount> {
+        verify_asset_transaction_with_gens(
+            amount,
+            initialized_asset_tx,
+            issr_account,
+            issr_init_balance,
+            auditors_enc_pub_keys,
+            options,
+            &PedersenGens::default(),
+        )
+    }
+
+    /// Same as `verify_asset_transaction`, but returns the cached result if the same
+    /// verification is found in `cache`. Otherwise, verifies the transaction and caches
+    /// its result.
+    pub fn verify_asset_transaction_cached(
         &self,
         amount: u32,
         initialized_asset_tx: &InitializedAssetTx,
         issr_account: &PubAccount,
         issr_init_balance: &EncryptedAmount,
         auditors_enc_pub_keys: &[AuditorPubAccount],
+        cache: &mut VerificationCache,
     ) -> Fallible<EncryptedAmount> {
+        let key = verification_cache_key(
+            amount,
+            initialized_asset_tx,
+            issr_account,
+            issr_init_balance,
+            auditors_enc_pub_keys,
+        );
+        if let Some(result) = cache.get(&key) {
+            return result;
+        }
+
+        let result = self.verify_asset_transaction(
+            amount,
+            initialized_asset_tx,
+            issr_account,
+            issr_init_balance,
+            auditors_enc_pub_keys,
+        );
+        cache.insert(key, &result);
+        result
+    }
+
+    /// Verifies a batch of independent asset issuances, e.g., all the issuances in a block.
+    /// Returns the result of each issuance, in the same order as `issuances`, so that a
+    /// single invalid issuance does not affect the others.
+    ///
+    /// Note that the issuances are still verified one by one. Only the Pedersen generators,
+    /// which are cheap to build, are shared. Asset issuances carry no signatures, so there
+    /// is nothing to batch verify with schnorrkel. A batch that exceeds the default
+    /// `BatchLimits` is rejected with `ErrorKind::BatchTooLarge` before any verification.
+    pub fn verify_asset_transactions_batch(
+        &self,
+        issuances: &[AssetIssuanceToVerify],
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+    ) -> Fallible<Vec<Fallible<EncryptedAmount>>> {
+        self.verify_asset_transactions_batch_with_limits(
+            issuances,
+            auditors_enc_pub_keys,
+            &BatchLimits::default(),
+        )
+    }
+
+    /// Same as `verify_asset_transactions_batch`, but rejects more than `limits.max_items`
+    /// issuances before verifying any of them.
+    pub fn verify_asset_transactions_batch_with_limits(
+        &self,
+        issuances: &[AssetIssuanceToVerify],
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        limits: &BatchLimits,
+    ) -> Fallible<Vec<Fallible<EncryptedAmount>>> {
+        limits.ensure_items(issuances.len())?;
+
         let gens = PedersenGens::default();
+        Ok(issuances
+            .iter()
+            .map(|issuance| {
+                verify_asset_transaction_with_gens(
+                    issuance.amount,
+                    &issuance.initialized_asset_tx,
+                    &issuance.issr_account,
+                    &issuance.issr_init_balance,
+                    auditors_enc_pub_keys,
+                    VerifyOptions::default(),
+                    &gens,
+                )
+            })
+            .collect())
+    }
+}
+
+fn verify_asset_transaction_with_gens(
+    amount: u32,
+    initialized_asset_tx: &InitializedAssetTx,
+    issr_account: &PubAccount,
+    issr_init_balance: &EncryptedAmount,
+    auditors_enc_pub_keys: &[AuditorPubAccount],
+    options: VerifyOptions,
+    gens: &PedersenGens,
+) -> Fallible<EncryptedAmount> {
+    // The checks run from the cheapest to the most expensive, and each returns early, so
+    // that a malformed submission is rejected before any proof is verified:
+    // 1. The lengths of the metadata and of the auditors' payload.
+    // 2. The issuer's sigma proofs.
+    // 3. The auditors' sigma proofs.
+    // An issuance has no signatures and no range proofs.
+    verify_metadata_length(&initialized_asset_tx.memo.metadata)?;
+    if options.check_auditors_payload {
+        ensure!(
+            initialized_asset_tx.auditors_payload.len() == auditors_enc_pub_keys.len(),
+            ErrorKind::AuditorPayloadError
+        );
+    }
 
+    if options.check_proofs {
         // Verify issuer's initialization proofs.
-        verify_initialization(&initialized_asset_tx, &issr_account, auditors_enc_pub_keys)?;
+        asset_issuance_init_verify_proofs(&initialized_asset_tx, &issr_account, gens)?;
 
-        single_property_verifier(
+        verify_issuance_proof(
             &CorrectnessVerifier {
                 value: amount.into(),
                 pub_key: issr_account.owner_enc_pub_key,
                 cipher: initialized_asset_tx.memo.enc_issued_amount,
-                pc_gens: &gens,
+                pc_gens: gens,
             },
             initialized_asset_tx.balance_correctness_proof,
+            &initialized_asset_tx.memo.metadata,
         )?;
+    }
 
-        // After successfully verifying the transaction, validator deposits the amount
-        // to issuer's account (aka processing phase).
-        let updated_issr_balance = deposit(
-            issr_init_balance,
-            &initialized_asset_tx.memo.enc_issued_amount,
-        );
+    if options.check_auditors_payload {
+        // Verify auditors payload.
+        verify_auditor_payload(
+            &initialized_asset_tx.auditors_payload,
+            auditors_enc_pub_keys,
+            issr_account.owner_enc_pub_key,
+            initialized_asset_tx.memo.enc_issued_amount,
+            &initialized_asset_tx.memo.metadata,
+            gens,
+        )?;
+    }
+
+    // After successfully verifying the transaction, validator deposits the amount
+    // to issuer's account (aka processing phase).
+    let updated_issr_balance = deposit(
+        issr_init_balance,
+        &initialized_asset_tx.memo.enc_issued_amount,
+    );
+
+    Ok(updated_issr_balance)
+}
 
-        Ok(updated_issr_balance)
+impl AssetTransactionVerifier for AssetValidator {
+    /// Called by validators to verify the justification and processing of the transaction.
+    fn verify_asset_transaction(
+        &self,
+        amount: u32,
+        initialized_asset_tx: &InitializedAssetTx,
+        issr_account: &PubAccount,
+        issr_init_balance: &EncryptedAmount,
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+    ) -> Fallible<EncryptedAmount> {
+        self.verify_asset_transaction_with_options(
+            amount,
+            initialized_asset_tx,
+            issr_account,
+            issr_init_balance,
+            auditors_enc_pub_keys,
+            VerifyOptions::default(),
+        )
     }
 }
 
@@ -287,7 +836,7 @@ impl AssetTransactionAuditor for AssetAuditor {
         let gens = PedersenGens::default();
 
         // Verify issuer's initialization proofs.
-        asset_issuance_init_verify_proofs(&initialized_asset_tx, issuer_account)?;
+        asset_issuance_init_verify_proofs(&initialized_asset_tx, issuer_account, &gens)?;
 
         // If all checks pass, decrypt the encrypted amount and verify issuer's correctness proof.
         initialized_asset_tx
@@ -300,7 +849,7 @@ impl AssetTransactionAuditor for AssetAuditor {
                     .secret
                     .const_time_decrypt(&payload.encrypted_amount)?;
 
-                single_property_verifier(
+                single_property_verifier_with_context(
                     &CorrectnessVerifier {
                         value: amount.into(),
                         pub_key: issuer_account.owner_enc_pub_key,
@@ -308,12 +857,105 @@ impl AssetTransactionAuditor for AssetAuditor {
                         pc_gens: &gens,
                     },
                     initialized_asset_tx.balance_correctness_proof,
+                    &initialized_asset_tx.memo.metadata,
                 )
             })
             .collect()
     }
 }
 
+// ------------------------------------------------------------------------------------------------
+// -                                       Issuance Policy                                        -
+// ------------------------------------------------------------------------------------------------
+
+/// A rule that a party who can see the issued amounts, e.g., an auditor, applies to the
+/// issuances of an asset on top of verifying their proofs.
+pub trait IssuancePolicy {
+    /// Records the issuance `tx_id` of `amount` in the running total of the current window,
+    /// or rejects it, without recording it, if it would break the policy. An issuance that
+    /// is already recorded in the current window is not counted again.
+    fn check_cumulative(&mut self, tx_id: u32, amount: Balance) -> Fallible<()>;
+}
+
+/// Caps the total amount that is issued within a window. The caller decides where a window
+/// ends, e.g., at an epoch or a block boundary, and calls `reset_window` to start a new one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WindowedCapPolicy {
+    cap: u64,
+    issued: u64,
+    /// The ids of the issuances that are counted in `issued`.
+    counted: BTreeSet<u32>,
+}
+
+impl WindowedCapPolicy {
+    pub fn new(cap: u64) -> Self {
+        WindowedCapPolicy {
+            cap,
+            issued: 0,
+            counted: BTreeSet::new(),
+        }
+    }
+
+    /// The total that is issued in the current window so far.
+    pub fn issued(&self) -> u64 {
+        self.issued
+    }
+
+    /// Starts a new window with a zero running total.
+    pub fn reset_window(&mut self) {
+        self.issued = 0;
+        self.counted.clear();
+    }
+}
+
+impl IssuancePolicy for WindowedCapPolicy {
+    fn check_cumulative(&mut self, tx_id: u32, amount: Balance) -> Fallible<()> {
+        if self.counted.contains(&tx_id) {
+            return Ok(());
+        }
+        let amount = u64::from(amount);
+        let total = self.issued.saturating_add(amount);
+        ensure!(
+            total <= self.cap,
+            ErrorKind::IssuanceCapExceeded {
+                amount,
+                total,
+                cap: self.cap,
+            }
+        );
+        self.issued = total;
+        self.counted.insert(tx_id);
+        Ok(())
+    }
+}
+
+impl AssetAuditor {
+    /// Audits the transaction as in `audit_asset_transaction`, then decrypts the issued amount
+    /// and checks it against the `policy`. The amount is recorded in the policy under `tx_id`
+    /// only if the transaction passes both checks.
+    pub fn audit_asset_transaction_with_policy<P: IssuancePolicy>(
+        &self,
+        tx_id: u32,
+        initialized_asset_tx: &InitializedAssetTx,
+        issuer_account: &PubAccount,
+        auditor_enc_key: &AuditorAccount,
+        policy: &mut P,
+    ) -> Fallible<()> {
+        self.audit_asset_transaction(initialized_asset_tx, issuer_account, auditor_enc_key)?;
+
+        let payload = initialized_asset_tx
+            .auditors_payload
+            .iter()
+            .find(|payload| payload.auditor_id == auditor_enc_key.auditor_id)
+            .ok_or(ErrorKind::AuditorPayloadError)?;
+        let amount = auditor_enc_key
+            .encryption_key
+            .secret
+            .const_time_decrypt(&payload.encrypted_amount)?;
+        policy.check_cumulative(tx_id, amount)
+    }
+}
+
 // ------------------------------------------------------------------------
 // Tests
 // ------------------------------------------------------------------------
@@ -323,17 +965,25 @@ mod tests {
     extern crate wasm_bindgen_test;
     use super::*;
     use crate::{
-        account::{convert_asset_ids, AccountCreator},
-        AccountCreatorInitializer, EncryptionKeys, SecAccount,
+        account::{convert_asset_ids, AccountCreator, AccountValidator},
+        kind_of, to_balance, AccountCreatorInitializer, AccountCreatorVerifier, EncryptionKeys,
+        PubAccountTx, SecAccount,
     };
+    use codec::{Decode, Encode};
     use cryptography_core::{
         asset_proofs::{errors::ErrorKind, AssetId, CommitmentWitness, ElgamalSecretKey},
         curve25519_dalek::scalar::Scalar,
     };
     use rand::rngs::StdRng;
     use rand::SeedableRng;
+    use std::cell::Cell;
     use wasm_bindgen_test::*;
 
+    std::thread_local! {
+        /// The number of issuance proofs that were verified on this thread.
+        pub(super) static VERIFIED_PROOFS: Cell<usize> = Cell::new(0);
+    }
+
     #[test]
     #[wasm_bindgen_test]
     fn asset_issuance_and_validation() {
@@ -375,7 +1025,13 @@ mod tests {
         // ----------------------- Initialization
         let issuer = AssetIssuer;
         let asset_tx = issuer
-            .initialize_asset_transaction(&issuer_account, &[], issued_amount, &mut rng)
+            .initialize_asset_transaction(
+                &issuer_account,
+                &valid_asset_ids,
+                &[],
+                issued_amount,
+                &mut rng,
+            )
             .unwrap();
 
         // Positive test.
@@ -398,44 +1054,718 @@ mod tests {
             .is_ok());
     }
 
-    fn asset_issuance_auditing_helper(
-        issuer_auditor_list: &[AuditorPubAccount],
-        validator_auditor_list: &[AuditorPubAccount],
-        validator_check_fails: bool,
-        auditors_list: &[AuditorAccount],
-    ) {
-        // ----------------------- Setup
-        let mut rng = StdRng::from_seed([10u8; 32]);
-        let issued_amount: Balance = 20u32;
+    /// Creates three valid issuances of 10, 20, and 30 by distinct issuers, along with the
+    /// issuers' keys.
+    fn asset_issuance_batch_helper(
+        rng: &mut StdRng,
+    ) -> (Vec<AssetIssuanceToVerify>, Vec<EncryptionKeys>) {
+        let asset_id = AssetId::from(1);
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+        let issuer = AssetIssuer;
 
-        // Generate keys for the issuer.
+        let mut issuances = Vec::new();
+        let mut issuer_keys = Vec::new();
+        for issued_amount in &[10u32, 20, 30] {
+            let elg_secret_key = ElgamalSecretKey::new(Scalar::random(rng));
+            let enc_keys = EncryptionKeys {
+                public: elg_secret_key.get_public_key(),
+                secret: elg_secret_key,
+            };
+            let secret_account = SecAccount {
+                enc_keys: enc_keys.clone(),
+                asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut *rng)),
+            };
+            let account_tx = AccountCreator
+                .create(&secret_account, &valid_asset_ids, rng)
+                .unwrap();
+            let account = Account {
+                public: account_tx.pub_account.clone(),
+                secret: secret_account,
+            };
+            let asset_tx = issuer
+                .initialize_asset_transaction(&account, &valid_asset_ids, &[], *issued_amount, rng)
+                .unwrap();
+
+            issuances.push(AssetIssuanceToVerify {
+                amount: *issued_amount,
+                initialized_asset_tx: asset_tx,
+                issr_account: account_tx.pub_account,
+                issr_init_balance: account_tx.initial_balance,
+            });
+            issuer_keys.push(enc_keys);
+        }
+
+        (issuances, issuer_keys)
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_batch_validation() {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let (mut issuances, issuer_keys) = asset_issuance_batch_helper(&mut rng);
+
+        // Tamper with the second issuance's proof by using the third one's.
+        issuances[1].initialized_asset_tx.balance_correctness_proof =
+            issuances[2].initialized_asset_tx.balance_correctness_proof;
+
+        // ----------------------- Test
+        let results = AssetValidator
+            .verify_asset_transactions_batch(&issuances, &[])
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(issuer_keys[0]
+            .secret
+            .verify(results[0].as_ref().unwrap(), &10u32.into())
+            .is_ok());
+        assert!(results[1].is_err());
+        assert!(issuer_keys[2]
+            .secret
+            .verify(results[2].as_ref().unwrap(), &30u32.into())
+            .is_ok());
+
+        // An oversized batch is rejected as a whole, before any issuance is verified.
+        let limits = BatchLimits {
+            max_items: 2,
+            ..BatchLimits::default()
+        };
+        assert_err!(
+            AssetValidator.verify_asset_transactions_batch_with_limits(&issuances, &[], &limits),
+            ErrorKind::BatchTooLarge { size: 3, max: 2 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_batch_per_item_results() {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([11u8; 32]);
+        let (mut issuances, _) = asset_issuance_batch_helper(&mut rng);
+
+        // The second issuer claims to have issued a different amount than the one it
+        // encrypted and proved.
+        issuances[1].amount += 1;
+
+        // ----------------------- Test
+        let results = AssetValidator
+            .verify_asset_transactions_batch(&issuances, &[])
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+
+        // Each result is the one of verifying the issuance on its own.
+        for (issuance, result) in issuances.iter().zip(results.iter()) {
+            let expected = AssetValidator.verify_asset_transaction(
+                issuance.amount,
+                &issuance.initialized_asset_tx,
+                &issuance.issr_account,
+                &issuance.issr_init_balance,
+                &[],
+            );
+            match (result, expected) {
+                (Ok(balance), Ok(expected)) => assert_eq!(*balance, expected),
+                (Err(error), Err(expected)) => assert_eq!(error.kind(), expected.kind()),
+                _ => panic!("The batch and the single verification disagree"),
+            }
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_cached_validation() {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let asset_id = AssetId::from(1);
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+        let issued_amount: Balance = 20u32;
+
+        let elg_secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let enc_keys = EncryptionKeys {
+            public: elg_secret_key.get_public_key(),
+            secret: elg_secret_key,
+        };
+        let secret_account = SecAccount {
+            enc_keys: enc_keys.clone(),
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+        let account_tx = AccountCreator
+            .create(&secret_account, &valid_asset_ids, &mut rng)
+            .unwrap();
+        let account = Account {
+            public: account_tx.pub_account.clone(),
+            secret: secret_account,
+        };
+        let asset_tx = AssetIssuer
+            .initialize_asset_transaction(&account, &valid_asset_ids, &[], issued_amount, &mut rng)
+            .unwrap();
+
+        // ----------------------- Test
+        let mut cache = VerificationCache::new(2);
+        let verify = |amount: Balance, cache: &mut VerificationCache| {
+            AssetValidator.verify_asset_transaction_cached(
+                amount,
+                &asset_tx,
+                &account_tx.pub_account,
+                &account_tx.initial_balance,
+                &[],
+                cache,
+            )
+        };
+
+        let first = verify(issued_amount, &mut cache).unwrap();
+        assert_eq!(cache.hits(), 0);
+        assert_eq!(cache.len(), 1);
+
+        // The second verification of the same transaction hits the cache.
+        let second = verify(issued_amount, &mut cache).unwrap();
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(first, second);
+        assert!(enc_keys
+            .secret
+            .verify(&second, &issued_amount.into())
+            .is_ok());
+
+        // Failures are cached as well.
+        let error = verify(issued_amount + 1, &mut cache).unwrap_err();
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(
+            verify(issued_amount + 1, &mut cache).unwrap_err().kind(),
+            error.kind()
+        );
+        assert_eq!(cache.hits(), 2);
+
+        // The least recently used entry is evicted when the cache is full.
+        verify(issued_amount + 2, &mut cache).unwrap_err();
+        assert_eq!(cache.len(), 2);
+        verify(issued_amount, &mut cache).unwrap();
+        assert_eq!(cache.hits(), 2);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_validation_with_options() {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issued_amount: Balance = 20u32;
+
+        // Generate keys for the issuer.
         let issuer_elg_secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
         let issuer_enc_key = EncryptionKeys {
             public: issuer_elg_secret_key.get_public_key(),
             secret: issuer_elg_secret_key,
         };
         let asset_id = AssetId::from(1);
-
         let issuer_secret_account = SecAccount {
             enc_keys: issuer_enc_key.clone(),
             asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
         };
-
-        let pub_account_enc_asset_id = issuer_enc_key
-            .public
-            .encrypt(&issuer_secret_account.asset_id_witness);
-
-        // Note that we use default proof values since we don't reverify these proofs during asset issuance.
         let issuer_public_account = PubAccount {
-            enc_asset_id: pub_account_enc_asset_id,
+            enc_asset_id: issuer_enc_key
+                .public
+                .encrypt(&issuer_secret_account.asset_id_witness),
             owner_enc_pub_key: issuer_enc_key.public,
         };
-        // Set the initial encrypted balance to 0.
         let issuer_init_balance = EncryptedAmount::default();
         let issuer_account = Account {
             public: issuer_public_account.clone(),
             secret: issuer_secret_account,
         };
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+
+        let auditor_keys = gen_enc_key_pair(0);
+        let auditors = vec![AuditorPubAccount {
+            auditor_id: [0u8; 32],
+            encryption_public_key: auditor_keys.public,
+        }];
+
+        // The issuer does not include the auditor's payload.
+        let asset_tx = AssetIssuer
+            .initialize_asset_transaction(
+                &issuer_account,
+                &valid_asset_ids,
+                &[],
+                issued_amount,
+                &mut rng,
+            )
+            .unwrap();
+
+        // ----------------------- Test
+        let validator = AssetValidator;
+        let verify = |amount: Balance, options: VerifyOptions| {
+            validator.verify_asset_transaction_with_options(
+                amount,
+                &asset_tx,
+                &issuer_public_account,
+                &issuer_init_balance,
+                &auditors,
+                options,
+            )
+        };
+
+        // All checks are enabled by default.
+        assert_err!(
+            verify(issued_amount, VerifyOptions::default()),
+            ErrorKind::AuditorPayloadError
+        );
+
+        // Skipping the auditors' payload checks still verifies the proofs.
+        let proofs_only = VerifyOptions {
+            check_proofs: true,
+            check_auditors_payload: false,
+        };
+        assert!(verify(issued_amount, proofs_only).is_ok());
+        assert_err!(
+            verify(issued_amount + 1, proofs_only),
+            ErrorKind::CorrectnessFinalResponseVerificationError { check: 2 }
+        );
+
+        // Disabling all the checks only processes the transaction.
+        let no_checks = VerifyOptions {
+            check_proofs: false,
+            check_auditors_payload: false,
+        };
+        let updated_issuer_balance = verify(issued_amount + 1, no_checks).unwrap();
+        assert!(issuer_enc_key
+            .secret
+            .verify(&updated_issuer_balance, &issued_amount.into())
+            .is_ok());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_with_max_balance() {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+
+        // Generate keys for the issuer.
+        let issuer_elg_secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let issuer_enc_key = EncryptionKeys {
+            public: issuer_elg_secret_key.get_public_key(),
+            secret: issuer_elg_secret_key,
+        };
+        let asset_id = AssetId::from(1);
+        let issuer_secret_account = SecAccount {
+            enc_keys: issuer_enc_key.clone(),
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+        let issuer_account = Account {
+            public: PubAccount {
+                enc_asset_id: issuer_enc_key
+                    .public
+                    .encrypt(&issuer_secret_account.asset_id_witness),
+                owner_enc_pub_key: issuer_enc_key.public,
+            },
+            secret: issuer_secret_account,
+        };
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+
+        // ----------------------- Test
+        // The maximum balance can be issued.
+        let issued_amount = to_balance(MAX_BALANCE).unwrap();
+        let asset_tx = AssetIssuer
+            .initialize_asset_transaction(
+                &issuer_account,
+                &valid_asset_ids,
+                &[],
+                issued_amount,
+                &mut rng,
+            )
+            .unwrap();
+        assert!(issuer_enc_key
+            .secret
+            .verify(&asset_tx.memo.enc_issued_amount, &issued_amount.into())
+            .is_ok());
+
+        // Larger amounts are rejected before the issuance.
+        assert_err!(
+            to_balance(MAX_BALANCE + 1),
+            ErrorKind::BalanceOverflow {
+                amount: MAX_BALANCE + 1,
+                max_balance: MAX_BALANCE
+            }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_with_metadata() {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issued_amount: Balance = 20u32;
+        let asset_id = AssetId::from(1);
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+
+        let issuer_elg_secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let issuer_secret_account = SecAccount {
+            enc_keys: EncryptionKeys {
+                public: issuer_elg_secret_key.get_public_key(),
+                secret: issuer_elg_secret_key,
+            },
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+        let issuer_account_tx = AccountCreator
+            .create(&issuer_secret_account, &valid_asset_ids, &mut rng)
+            .unwrap();
+        let issuer_public_account = issuer_account_tx.pub_account;
+        let issuer_init_balance = issuer_account_tx.initial_balance;
+        let issuer_account = Account {
+            public: issuer_public_account.clone(),
+            secret: issuer_secret_account,
+        };
+
+        // ----------------------- Test
+        let metadata = b"hash of the off-chain terms".to_vec();
+        let asset_tx = AssetIssuer
+            .initialize_asset_transaction_with_metadata(
+                &issuer_account,
+                &valid_asset_ids,
+                &[],
+                issued_amount,
+                metadata.clone(),
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(asset_tx.memo.metadata, metadata);
+        assert!(AssetValidator
+            .verify_asset_transaction(
+                issued_amount,
+                &asset_tx,
+                &issuer_public_account,
+                &issuer_init_balance,
+                &[],
+            )
+            .is_ok());
+
+        // The proofs are bound to the metadata, so it cannot be altered.
+        let mut tampered_asset_tx = asset_tx.clone();
+        tampered_asset_tx.memo.metadata = b"hash of the altered terms!!".to_vec();
+        assert_err!(
+            AssetValidator.verify_asset_transaction(
+                issued_amount,
+                &tampered_asset_tx,
+                &issuer_public_account,
+                &issuer_init_balance,
+                &[],
+            ),
+            ErrorKind::WellformednessFinalResponseVerificationError { check: 1 }
+        );
+
+        // Oversized metadata is rejected by the issuer and by the validator.
+        let oversized = vec![0u8; MAX_ASSET_METADATA_LEN + 1];
+        let too_large = ErrorKind::MetadataTooLarge {
+            length: MAX_ASSET_METADATA_LEN + 1,
+            max_length: MAX_ASSET_METADATA_LEN,
+        };
+        assert_err!(
+            AssetIssuer.initialize_asset_transaction_with_metadata(
+                &issuer_account,
+                &valid_asset_ids,
+                &[],
+                issued_amount,
+                oversized.clone(),
+                &mut rng,
+            ),
+            too_large.clone()
+        );
+        let mut bad_asset_tx = asset_tx;
+        bad_asset_tx.memo.metadata = oversized;
+        assert_err!(
+            AssetValidator.verify_asset_transaction(
+                issued_amount,
+                &bad_asset_tx,
+                &issuer_public_account,
+                &issuer_init_balance,
+                &[],
+            ),
+            too_large
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_pre_verification() {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issued_amount: Balance = 20u32;
+        let asset_id = AssetId::from(1);
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+
+        let issuer_elg_secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let issuer_secret_account = SecAccount {
+            enc_keys: EncryptionKeys {
+                public: issuer_elg_secret_key.get_public_key(),
+                secret: issuer_elg_secret_key,
+            },
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+        let issuer_account_tx = AccountCreator
+            .create(&issuer_secret_account, &valid_asset_ids, &mut rng)
+            .unwrap();
+        let issuer_public_account = issuer_account_tx.pub_account;
+        let issuer_account = Account {
+            public: issuer_public_account.clone(),
+            secret: issuer_secret_account,
+        };
+
+        let asset_tx = AssetIssuer
+            .initialize_asset_transaction(
+                &issuer_account,
+                &valid_asset_ids,
+                &[],
+                issued_amount,
+                &mut rng,
+            )
+            .unwrap();
+        let other_asset_tx = AssetIssuer
+            .initialize_asset_transaction(
+                &issuer_account,
+                &valid_asset_ids,
+                &[],
+                issued_amount,
+                &mut rng,
+            )
+            .unwrap();
+
+        // ----------------------- Test
+        AssetValidator
+            .pre_verify_issuance(&asset_tx, &issuer_public_account)
+            .unwrap();
+
+        // A wellformedness proof of another issuance is rejected.
+        let mut bad_asset_tx = asset_tx.clone();
+        bad_asset_tx.balance_wellformedness_proof = other_asset_tx.balance_wellformedness_proof;
+        assert!(AssetValidator
+            .pre_verify_issuance(&bad_asset_tx, &issuer_public_account)
+            .is_err());
+
+        // So is oversized metadata.
+        let mut bad_asset_tx = asset_tx;
+        bad_asset_tx.memo.metadata = vec![0u8; MAX_ASSET_METADATA_LEN + 1];
+        assert_err!(
+            AssetValidator.pre_verify_issuance(&bad_asset_tx, &issuer_public_account),
+            ErrorKind::MetadataTooLarge {
+                length: MAX_ASSET_METADATA_LEN + 1,
+                max_length: MAX_ASSET_METADATA_LEN,
+            }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn malformed_asset_issuance_rejected_before_proofs() {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issued_amount: Balance = 20u32;
+        let asset_id = AssetId::from(1);
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+
+        let issuer_elg_secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let issuer_secret_account = SecAccount {
+            enc_keys: EncryptionKeys {
+                public: issuer_elg_secret_key.get_public_key(),
+                secret: issuer_elg_secret_key,
+            },
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+        let issuer_account_tx = AccountCreator
+            .create(&issuer_secret_account, &valid_asset_ids, &mut rng)
+            .unwrap();
+        let issuer_public_account = issuer_account_tx.pub_account;
+        let issuer_init_balance = issuer_account_tx.initial_balance;
+        let issuer_account = Account {
+            public: issuer_public_account.clone(),
+            secret: issuer_secret_account,
+        };
+        let auditors = vec![AuditorPubAccount {
+            auditor_id: [0u8; 32],
+            encryption_public_key: gen_enc_key_pair(0).public,
+        }];
+
+        let asset_tx = AssetIssuer
+            .initialize_asset_transaction(
+                &issuer_account,
+                &valid_asset_ids,
+                &[],
+                issued_amount,
+                &mut rng,
+            )
+            .unwrap();
+
+        // ----------------------- Test
+        let verify = |asset_tx: &InitializedAssetTx, auditors: &[AuditorPubAccount]| {
+            VERIFIED_PROOFS.with(|count| count.set(0));
+            let result = AssetValidator.verify_asset_transaction(
+                issued_amount,
+                asset_tx,
+                &issuer_public_account,
+                &issuer_init_balance,
+                auditors,
+            );
+            (result, VERIFIED_PROOFS.with(Cell::get))
+        };
+
+        // The malformed submissions are rejected without verifying any proof.
+        let (result, verified_proofs) = verify(&asset_tx, &auditors);
+        assert_err!(result, ErrorKind::AuditorPayloadError);
+        assert_eq!(verified_proofs, 0);
+
+        let mut oversized_asset_tx = asset_tx.clone();
+        oversized_asset_tx.memo.metadata = vec![0u8; MAX_ASSET_METADATA_LEN + 1];
+        let (result, verified_proofs) = verify(&oversized_asset_tx, &[]);
+        assert_err!(
+            result,
+            ErrorKind::MetadataTooLarge {
+                length: MAX_ASSET_METADATA_LEN + 1,
+                max_length: MAX_ASSET_METADATA_LEN,
+            }
+        );
+        assert_eq!(verified_proofs, 0);
+
+        // A well-formed submission has its wellformedness and correctness proofs verified.
+        let (result, verified_proofs) = verify(&asset_tx, &[]);
+        assert!(result.is_ok());
+        assert_eq!(verified_proofs, 2);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_with_corrupted_auditor_proof() {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issued_amount: Balance = 20u32;
+        let asset_id = AssetId::from(1);
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+        let (issuer_account, issuer_init_balance) = issuer_account_helper(asset_id, &mut rng);
+        let auditors = vec![
+            AuditorPubAccount {
+                auditor_id: [0u8; 32],
+                encryption_public_key: gen_enc_key_pair(0).public,
+            },
+            AuditorPubAccount {
+                auditor_id: [1u8; 32],
+                encryption_public_key: gen_enc_key_pair(1).public,
+            },
+        ];
+
+        let asset_tx = AssetIssuer
+            .initialize_asset_transaction(
+                &issuer_account,
+                &valid_asset_ids,
+                &auditors,
+                issued_amount,
+                &mut rng,
+            )
+            .unwrap();
+        let verify = |asset_tx: &InitializedAssetTx| {
+            AssetValidator.verify_asset_transaction(
+                issued_amount,
+                asset_tx,
+                &issuer_account.public,
+                &issuer_init_balance,
+                &auditors,
+            )
+        };
+        assert!(verify(&asset_tx).is_ok());
+
+        // ----------------------- Test
+        // The equality proof of the second auditor is replaced by the one of the first. It
+        // still holds for the issuer's key, but not for the second auditor's key.
+        let mut bad_asset_tx = asset_tx;
+        bad_asset_tx.auditors_payload[1].amount_equal_cipher_proof =
+            bad_asset_tx.auditors_payload[0].amount_equal_cipher_proof;
+        assert_err!(
+            verify(&bad_asset_tx),
+            ErrorKind::EncryptingSameValueFinalResponseVerificationError { check: 2 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_with_unregistered_asset_id() {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issued_amount: Balance = 20u32;
+        let (issuer_account, _) = issuer_account_helper(AssetId::from(4), &mut rng);
+
+        let valid_asset_ids: Vec<AssetId> =
+            vec![1, 2, 3].iter().map(|id| AssetId::from(*id)).collect();
+        let valid_asset_ids = convert_asset_ids(valid_asset_ids);
+
+        // ----------------------- Initialization
+        let result = AssetIssuer.initialize_asset_transaction(
+            &issuer_account,
+            &valid_asset_ids,
+            &[],
+            issued_amount,
+            &mut rng,
+        );
+        assert_err!(result, ErrorKind::UnknownAssetType);
+    }
+
+    /// Creates an issuer account for `asset_id` with the `AccountCreator`, and returns it
+    /// along with its initial balance.
+    fn issuer_account_helper(asset_id: AssetId, rng: &mut StdRng) -> (Account, EncryptedAmount) {
+        let elg_secret_key = ElgamalSecretKey::new(Scalar::random(rng));
+        let secret_account = SecAccount {
+            enc_keys: EncryptionKeys {
+                public: elg_secret_key.get_public_key(),
+                secret: elg_secret_key,
+            },
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut *rng)),
+        };
+        let account_tx = AccountCreator
+            .create(&secret_account, &convert_asset_ids(vec![asset_id]), rng)
+            .unwrap();
+        (
+            Account {
+                public: account_tx.pub_account,
+                secret: secret_account,
+            },
+            account_tx.initial_balance,
+        )
+    }
+
+    fn asset_issuance_auditing_helper(
+        issuer_auditor_list: &[AuditorPubAccount],
+        validator_auditor_list: &[AuditorPubAccount],
+        validator_check_fails: bool,
+        auditors_list: &[AuditorAccount],
+    ) {
+        // ----------------------- Setup
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issued_amount: Balance = 20u32;
+
+        // Generate keys for the issuer.
+        let issuer_elg_secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let issuer_enc_key = EncryptionKeys {
+            public: issuer_elg_secret_key.get_public_key(),
+            secret: issuer_elg_secret_key,
+        };
+        let asset_id = AssetId::from(1);
+
+        let issuer_secret_account = SecAccount {
+            enc_keys: issuer_enc_key.clone(),
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+
+        // Create the account with real proofs, including the proof that its initial balance
+        // is zero, and verify them before using the account for the issuance.
+        let issuer_account_tx = AccountCreator
+            .create(&issuer_secret_account, &valid_asset_ids, &mut rng)
+            .unwrap();
+        AccountValidator
+            .verify(&issuer_account_tx, &valid_asset_ids)
+            .unwrap();
+        let issuer_public_account = issuer_account_tx.pub_account;
+        let issuer_init_balance = issuer_account_tx.initial_balance;
+        let issuer_account = Account {
+            public: issuer_public_account.clone(),
+            secret: issuer_secret_account,
+        };
 
         let mut seed = [0u8; 32];
         rng.fill_bytes(&mut seed);
@@ -445,6 +1775,7 @@ mod tests {
         let asset_tx = issuer
             .initialize_asset_transaction(
                 &issuer_account,
+                &valid_asset_ids,
                 issuer_auditor_list,
                 issued_amount,
                 &mut rng,
@@ -591,4 +1922,345 @@ mod tests {
             auditors_secret_account_list,
         );
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_asset_transaction_canonical_encoding() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issuer_enc_key = gen_enc_key_pair(10u8);
+        let asset_id = AssetId::from(1);
+        let issuer_secret_account = SecAccount {
+            enc_keys: issuer_enc_key.clone(),
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+        let issuer_account = Account {
+            public: PubAccount {
+                enc_asset_id: issuer_enc_key
+                    .public
+                    .encrypt(&issuer_secret_account.asset_id_witness),
+                owner_enc_pub_key: issuer_enc_key.public,
+            },
+            secret: issuer_secret_account,
+        };
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+
+        let auditors: Vec<AuditorPubAccount> = (0..3u8)
+            .map(|index| AuditorPubAccount {
+                auditor_id: [index; 32],
+                encryption_public_key: gen_enc_key_pair(index).public,
+            })
+            .collect();
+        let reversed_auditors: Vec<AuditorPubAccount> = auditors.iter().rev().cloned().collect();
+
+        let mut issue = |auditors: &[AuditorPubAccount]| {
+            AssetIssuer
+                .initialize_asset_transaction(
+                    &issuer_account,
+                    &valid_asset_ids,
+                    auditors,
+                    20u32,
+                    &mut rng,
+                )
+                .unwrap()
+        };
+        let asset_tx = issue(&auditors);
+        let reversed_asset_tx = issue(&reversed_auditors);
+
+        // The auditors' payloads are in the same order regardless of the auditors' order.
+        let auditor_ids = |tx: &InitializedAssetTx| {
+            tx.auditors_payload
+                .iter()
+                .map(|payload| payload.auditor_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            auditor_ids(&asset_tx),
+            vec![[0u8; 32], [1u8; 32], [2u8; 32]]
+        );
+        assert_eq!(auditor_ids(&asset_tx), auditor_ids(&reversed_asset_tx));
+
+        // Encoding the same content twice yields identical bytes.
+        let encoded = asset_tx.encode();
+        assert_eq!(encoded, asset_tx.clone().encode());
+        let decoded = InitializedAssetTx::decode(&mut &encoded[..]).unwrap();
+        assert_eq!(decoded.encode(), encoded);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_describe_proofs() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issuer_enc_key = gen_enc_key_pair(10u8);
+        let asset_id = AssetId::from(1);
+        let issuer_secret_account = SecAccount {
+            enc_keys: issuer_enc_key.clone(),
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+        let issuer_account = Account {
+            public: PubAccount {
+                enc_asset_id: issuer_enc_key
+                    .public
+                    .encrypt(&issuer_secret_account.asset_id_witness),
+                owner_enc_pub_key: issuer_enc_key.public,
+            },
+            secret: issuer_secret_account,
+        };
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+        let auditors: Vec<AuditorPubAccount> = (0..2u8)
+            .map(|index| AuditorPubAccount {
+                auditor_id: [index; 32],
+                encryption_public_key: gen_enc_key_pair(index).public,
+            })
+            .collect();
+
+        let asset_tx = AssetIssuer
+            .initialize_asset_transaction(
+                &issuer_account,
+                &valid_asset_ids,
+                &auditors,
+                20u32,
+                &mut rng,
+            )
+            .unwrap();
+        // The described proofs match the proof fields of the decoded transaction. Listing
+        // every field makes this fail to compile when a field is added.
+        let decoded = InitializedAssetTx::decode(&mut &asset_tx.encode()[..]).unwrap();
+        let InitializedAssetTx {
+            account_id: _,
+            memo: _,
+            balance_wellformedness_proof,
+            balance_correctness_proof,
+            auditors_payload,
+        } = &decoded;
+        let mut present = vec![
+            kind_of(balance_wellformedness_proof),
+            kind_of(balance_correctness_proof),
+        ];
+        present.extend(
+            auditors_payload
+                .iter()
+                .map(|payload| kind_of(&payload.amount_equal_cipher_proof)),
+        );
+        assert_eq!(auditors_payload.len(), auditors.len());
+        assert_eq!(decoded.describe_proofs(), present);
+
+        // The issuer's account carries its own proofs.
+        let account_tx = AccountCreator
+            .create(&issuer_account.secret, &valid_asset_ids, &mut rng)
+            .unwrap();
+        let decoded = PubAccountTx::decode(&mut &account_tx.encode()[..]).unwrap();
+        let PubAccountTx {
+            pub_account: _,
+            initial_balance: _,
+            asset_wellformedness_proof,
+            asset_membership_proof,
+            initial_balance_correctness_proof,
+        } = &decoded;
+        assert_eq!(
+            decoded.describe_proofs(),
+            vec![
+                kind_of(asset_wellformedness_proof),
+                kind_of(asset_membership_proof),
+                kind_of(initial_balance_correctness_proof),
+            ]
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_split_and_reassemble() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issuer_enc_key = gen_enc_key_pair(10u8);
+        let asset_id = AssetId::from(1);
+        let issuer_secret_account = SecAccount {
+            enc_keys: issuer_enc_key.clone(),
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+        let issuer_account = Account {
+            public: PubAccount {
+                enc_asset_id: issuer_enc_key
+                    .public
+                    .encrypt(&issuer_secret_account.asset_id_witness),
+                owner_enc_pub_key: issuer_enc_key.public,
+            },
+            secret: issuer_secret_account,
+        };
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+        let auditors: Vec<AuditorPubAccount> = (0..2u8)
+            .map(|index| AuditorPubAccount {
+                auditor_id: [index; 32],
+                encryption_public_key: gen_enc_key_pair(index).public,
+            })
+            .collect();
+
+        let asset_tx = AssetIssuer
+            .initialize_asset_transaction(
+                &issuer_account,
+                &valid_asset_ids,
+                &auditors,
+                20u32,
+                &mut rng,
+            )
+            .unwrap();
+
+        // The parts are stored separately, and recombined into the original transaction.
+        let (metadata, proofs) = asset_tx.split();
+        let metadata = AssetTxMetadata::decode(&mut &metadata.encode()[..]).unwrap();
+        let reassembled = InitializedAssetTx::reassemble(metadata.clone(), &proofs).unwrap();
+        assert_eq!(reassembled, asset_tx);
+        assert_eq!(reassembled.encode(), asset_tx.encode());
+        assert!(AssetValidator
+            .verify_asset_transaction(
+                20u32,
+                &reassembled,
+                &issuer_account.public,
+                &EncryptedAmount::default(),
+                &auditors,
+            )
+            .is_ok());
+
+        // The metadata only accepts the proofs that it commits to.
+        let (_, other_proofs) = AssetIssuer
+            .initialize_asset_transaction(
+                &issuer_account,
+                &valid_asset_ids,
+                &auditors,
+                20u32,
+                &mut rng,
+            )
+            .unwrap()
+            .split();
+        assert_err!(
+            InitializedAssetTx::reassemble(metadata.clone(), &other_proofs),
+            ErrorKind::ProofsHashMismatch
+        );
+        assert_err!(
+            InitializedAssetTx::reassemble(metadata, &proofs[1..]),
+            ErrorKind::ProofsHashMismatch
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_encrypt_issuance_amount() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issuer = gen_enc_key_pair(20);
+        let mediator = gen_enc_key_pair(21);
+        let auditor = gen_enc_key_pair(22);
+        let amount: Balance = 300;
+
+        let ciphers = encrypt_issuance_amount(
+            amount,
+            &issuer.public,
+            &mediator.public,
+            Some(&auditor.public),
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(issuer.secret.decrypt(&ciphers.issuer).unwrap(), amount);
+        assert_eq!(mediator.secret.decrypt(&ciphers.mediator).unwrap(), amount);
+        let (auditor_cipher, _) = ciphers.auditor.as_ref().unwrap();
+        assert_eq!(auditor.secret.decrypt(auditor_cipher).unwrap(), amount);
+        assert!(ciphers
+            .verify(&issuer.public, &mediator.public, Some(&auditor.public))
+            .is_ok());
+
+        // The equality proofs do not verify against the wrong keys, and the auditor's
+        // ciphertext is required if and only if an auditor key is given.
+        assert!(ciphers
+            .verify(&issuer.public, &auditor.public, Some(&auditor.public))
+            .is_err());
+        assert_err!(
+            ciphers.verify(&issuer.public, &mediator.public, None),
+            ErrorKind::AuditorPayloadError
+        );
+
+        let ciphers =
+            encrypt_issuance_amount(amount, &issuer.public, &mediator.public, None, &mut rng)
+                .unwrap();
+        assert!(ciphers.auditor.is_none());
+        assert_eq!(mediator.secret.decrypt(&ciphers.mediator).unwrap(), amount);
+        assert!(ciphers
+            .verify(&issuer.public, &mediator.public, None)
+            .is_ok());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn asset_issuance_with_windowed_cap() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let issuer_enc_key = gen_enc_key_pair(10u8);
+        let asset_id = AssetId::from(1);
+        let issuer_secret_account = SecAccount {
+            enc_keys: issuer_enc_key.clone(),
+            asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+        };
+        let issuer_account = Account {
+            public: PubAccount {
+                enc_asset_id: issuer_enc_key
+                    .public
+                    .encrypt(&issuer_secret_account.asset_id_witness),
+                owner_enc_pub_key: issuer_enc_key.public,
+            },
+            secret: issuer_secret_account,
+        };
+        let valid_asset_ids = convert_asset_ids(vec![asset_id]);
+        let auditor = AuditorAccount {
+            auditor_id: [1u8; 32],
+            encryption_key: gen_enc_key_pair(1u8),
+        };
+        let auditors = vec![AuditorPubAccount {
+            auditor_id: auditor.auditor_id,
+            encryption_public_key: auditor.encryption_key.public,
+        }];
+
+        let asset_txs: Vec<InitializedAssetTx> = (0..3)
+            .map(|_| {
+                AssetIssuer
+                    .initialize_asset_transaction(
+                        &issuer_account,
+                        &valid_asset_ids,
+                        &auditors,
+                        20u32,
+                        &mut rng,
+                    )
+                    .unwrap()
+            })
+            .collect();
+        let mut policy = WindowedCapPolicy::new(50);
+        let audit = |policy: &mut WindowedCapPolicy, tx_id: u32| {
+            AssetAuditor.audit_asset_transaction_with_policy(
+                tx_id,
+                &asset_txs[tx_id as usize],
+                &issuer_account.public,
+                &auditor,
+                policy,
+            )
+        };
+
+        // Issuing under the cap repeatedly is accepted.
+        audit(&mut policy, 0).unwrap();
+        audit(&mut policy, 1).unwrap();
+        assert_eq!(policy.issued(), 40);
+
+        // Auditing an issuance again does not count it twice.
+        audit(&mut policy, 1).unwrap();
+        assert_eq!(policy.issued(), 40);
+
+        // The issuance that exceeds the cap is rejected and not recorded.
+        assert_err!(
+            audit(&mut policy, 2),
+            ErrorKind::IssuanceCapExceeded {
+                amount: 20,
+                total: 60,
+                cap: 50,
+            }
+        );
+        assert_eq!(policy.issued(), 40);
+
+        // A new window starts from zero.
+        policy.reset_window();
+        audit(&mut policy, 2).unwrap();
+        assert_eq!(policy.issued(), 20);
+    }
 }
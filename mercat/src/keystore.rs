@@ -0,0 +1,184 @@
+//! Password protected keystores for the secret accounts.
+//!
+//! The secret account is encrypted with ChaCha20-Poly1305 under a key that is derived from
+//! the password with scrypt. The keystore layout is:
+//! `version (1) | log2 of the scrypt cost (1) | salt (16) | nonce (12) | ciphertext and tag`.
+
+use crate::SecAccount;
+use chacha20poly1305::{
+    aead::{Aead, NewAead},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use codec::{Decode, Encode};
+use cryptography_core::asset_proofs::errors::{ErrorKind, Fallible};
+use rand_core::{CryptoRng, RngCore};
+use scrypt::{scrypt, ScryptParams};
+use sp_std::vec::Vec;
+use zeroize::Zeroizing;
+
+/// The version of the keystore layout.
+const KEYSTORE_VERSION: u8 = 1;
+
+/// The log2 of the scrypt cost parameter used for new keystores.
+pub const KEYSTORE_SCRYPT_LOG_N: u8 = 15;
+
+/// The largest scrypt cost accepted when reading a keystore, to bound the work that a
+/// malicious keystore can cause.
+const KEYSTORE_SCRYPT_MAX_LOG_N: u8 = 20;
+
+const KEYSTORE_SCRYPT_R: u32 = 8;
+const KEYSTORE_SCRYPT_P: u32 = 1;
+const KEYSTORE_SALT_LEN: usize = 16;
+const KEYSTORE_NONCE_LEN: usize = 12;
+const KEYSTORE_HEADER_LEN: usize = 2 + KEYSTORE_SALT_LEN + KEYSTORE_NONCE_LEN;
+
+/// Derives the encryption key of a keystore from the password.
+fn derive_key(password: &str, salt: &[u8], log_n: u8) -> Fallible<Zeroizing<[u8; 32]>> {
+    let params = ScryptParams::new(log_n, KEYSTORE_SCRYPT_R, KEYSTORE_SCRYPT_P)
+        .map_err(|_| ErrorKind::KeystoreDecryptFailed)?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt(password.as_bytes(), salt, &params, &mut key[..])
+        .map_err(|_| ErrorKind::KeystoreDecryptFailed)?;
+    Ok(key)
+}
+
+impl SecAccount {
+    /// Encrypts the secret account with a key derived from `password`.
+    /// The result can be stored at rest, and is decrypted by `from_encrypted_keystore`.
+    pub fn to_encrypted_keystore<R: RngCore + CryptoRng>(
+        &self,
+        password: &str,
+        rng: &mut R,
+    ) -> Fallible<Vec<u8>> {
+        self.to_encrypted_keystore_with_cost(password, KEYSTORE_SCRYPT_LOG_N, rng)
+    }
+
+    fn to_encrypted_keystore_with_cost<R: RngCore + CryptoRng>(
+        &self,
+        password: &str,
+        log_n: u8,
+        rng: &mut R,
+    ) -> Fallible<Vec<u8>> {
+        let mut salt = [0u8; KEYSTORE_SALT_LEN];
+        rng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; KEYSTORE_NONCE_LEN];
+        rng.fill_bytes(&mut nonce);
+
+        let key = derive_key(password, &salt, log_n)?;
+        let plaintext = Zeroizing::new(self.encode());
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key[..]))
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| ErrorKind::SerializationError)?;
+
+        let mut keystore = Vec::with_capacity(KEYSTORE_HEADER_LEN + ciphertext.len());
+        keystore.push(KEYSTORE_VERSION);
+        keystore.push(log_n);
+        keystore.extend_from_slice(&salt);
+        keystore.extend_from_slice(&nonce);
+        keystore.extend_from_slice(&ciphertext);
+
+        Ok(keystore)
+    }
+
+    /// Decrypts a keystore created by `to_encrypted_keystore`. Returns
+    /// `KeystoreDecryptFailed` if the password is wrong or the keystore is corrupted.
+    pub fn from_encrypted_keystore(keystore: &[u8], password: &str) -> Fallible<SecAccount> {
+        ensure!(
+            keystore.len() > KEYSTORE_HEADER_LEN
+                && keystore[0] == KEYSTORE_VERSION
+                && keystore[1] <= KEYSTORE_SCRYPT_MAX_LOG_N,
+            ErrorKind::KeystoreDecryptFailed
+        );
+        let log_n = keystore[1];
+        let (salt, rest) = keystore[2..].split_at(KEYSTORE_SALT_LEN);
+        let (nonce, ciphertext) = rest.split_at(KEYSTORE_NONCE_LEN);
+
+        let key = derive_key(password, salt, log_n)?;
+        let plaintext = Zeroizing::new(
+            ChaCha20Poly1305::new(Key::from_slice(&key[..]))
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|_| ErrorKind::KeystoreDecryptFailed)?,
+        );
+
+        let sec_account = SecAccount::decode(&mut &plaintext[..])
+            .map_err(|_| ErrorKind::KeystoreDecryptFailed)?;
+        Ok(sec_account)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// -                                            Tests                                             -
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::EncryptionKeys;
+    use cryptography_core::{
+        asset_proofs::{AssetId, CommitmentWitness, ElgamalSecretKey},
+        curve25519_dalek::scalar::Scalar,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    // A low cost keeps the tests fast.
+    const TEST_LOG_N: u8 = 4;
+
+    fn gen_sec_account(rng: &mut StdRng) -> SecAccount {
+        let secret = ElgamalSecretKey::new(Scalar::random(rng));
+        SecAccount {
+            enc_keys: EncryptionKeys {
+                public: secret.get_public_key(),
+                secret,
+            },
+            asset_id_witness: CommitmentWitness::from((AssetId::from(1).into(), rng)),
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_keystore_round_trip() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let sec_account = gen_sec_account(&mut rng);
+
+        let keystore = sec_account
+            .to_encrypted_keystore_with_cost("correct horse", TEST_LOG_N, &mut rng)
+            .unwrap();
+        let decrypted = SecAccount::from_encrypted_keystore(&keystore, "correct horse").unwrap();
+        assert_eq!(decrypted.encode(), sec_account.encode());
+
+        // The keystore does not contain the secret material in the clear.
+        let secret_bytes = sec_account.enc_keys.secret.encode();
+        assert!(!keystore
+            .windows(secret_bytes.len())
+            .any(|window| window == &secret_bytes[..]));
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_keystore_wrong_password() {
+        let mut rng = StdRng::from_seed([10u8; 32]);
+        let sec_account = gen_sec_account(&mut rng);
+        let keystore = sec_account
+            .to_encrypted_keystore_with_cost("correct horse", TEST_LOG_N, &mut rng)
+            .unwrap();
+
+        assert_err!(
+            SecAccount::from_encrypted_keystore(&keystore, "battery staple"),
+            ErrorKind::KeystoreDecryptFailed
+        );
+
+        // A corrupted keystore is rejected as well.
+        let mut corrupted = keystore.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert_err!(
+            SecAccount::from_encrypted_keystore(&corrupted, "correct horse"),
+            ErrorKind::KeystoreDecryptFailed
+        );
+        assert_err!(
+            SecAccount::from_encrypted_keystore(&keystore[..KEYSTORE_HEADER_LEN], "correct horse"),
+            ErrorKind::KeystoreDecryptFailed
+        );
+    }
+}
@@ -13,10 +13,10 @@ use cryptography_core::{
     asset_proofs::{
         ciphertext_refreshment_proof::CipherEqualSamePubKeyProof,
         correctness_proof::CorrectnessProof,
-        encrypting_same_value_proof::CipherEqualDifferentPubKeyProof, errors::Fallible,
-        membership_proof::MembershipProof, range_proof::InRangeProof,
+        encrypting_same_value_proof::CipherEqualDifferentPubKeyProof, errors::ErrorKind,
+        errors::Fallible, membership_proof::MembershipProof, range_proof::InRangeProof,
         wellformedness_proof::WellformednessProof, AssetId, Balance, CipherText,
-        CipherTextWithHint, CommitmentWitness, ElgamalPublicKey, ElgamalSecretKey,
+        CipherTextWithHint, CommitmentWitness, ElgamalPublicKey, ElgamalSecretKey, BALANCE_RANGE,
     },
     curve25519_dalek::scalar::Scalar,
 };
@@ -51,6 +51,26 @@ macro_rules! assert_err {
 const EXPONENT: u32 = 8;
 const BASE: u32 = 4;
 
+/// The maximum balance that can be proven by the `BALANCE_RANGE` bit range proofs.
+/// Amounts and balances larger than this value are rejected.
+pub const MAX_BALANCE: u64 = (1u64 << BALANCE_RANGE) - 1;
+
+/// The maximum length, in bytes, of the opaque metadata of an asset issuance.
+pub const MAX_ASSET_METADATA_LEN: usize = 256;
+
+/// Converts an amount from a wider integer type to a `Balance`, and rejects it
+/// if it is larger than `MAX_BALANCE`.
+pub fn to_balance(amount: u64) -> Fallible<Balance> {
+    ensure!(
+        amount <= MAX_BALANCE,
+        ErrorKind::BalanceOverflow {
+            amount,
+            max_balance: MAX_BALANCE
+        }
+    );
+    Ok(amount as Balance)
+}
+
 // -------------------------------------------------------------------------------------
 // -                                 New Type Def                                      -
 // -------------------------------------------------------------------------------------
@@ -110,6 +130,27 @@ pub struct PubAccount {
     pub owner_enc_pub_key: EncryptionPubKey,
 }
 
+/// The difference between two snapshots of a public account and its encrypted balance.
+/// Returned by `PubAccount::diff`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountDiff {
+    /// Whether the encrypted asset id (i.e., the account id) has changed.
+    pub enc_asset_id_changed: bool,
+    /// Whether the owner's encryption public key has changed.
+    pub owner_enc_pub_key_changed: bool,
+    /// The old and the new encrypted balances, if the balance ciphertext has changed.
+    pub balance_change: Option<(EncryptedAmount, EncryptedAmount)>,
+}
+
+impl AccountDiff {
+    /// Returns true if none of the fields have changed.
+    pub fn is_empty(&self) -> bool {
+        !self.enc_asset_id_changed
+            && !self.owner_enc_pub_key_changed
+            && self.balance_change.is_none()
+    }
+}
+
 /// Holds contents of the public portion of an account which can be safely put on the chain.
 #[derive(Clone, Encode, Decode, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -136,6 +177,22 @@ pub struct Account {
     pub secret: SecAccount,
 }
 
+/// Proves that the encrypted asset ids of two accounts with different owners encrypt the
+/// same asset id, e.g., to match the counterparties of a swap. It is created by the owner
+/// of the first account with `account::prove_same_asset_type`, and completed by the owner
+/// of the second account with `account::confirm_same_asset_type`.
+#[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SameAssetTypeProof {
+    /// The first account's asset id, encrypted with the second account's public key.
+    pub enc_asset_id_using_other: EncryptedAssetId,
+    /// Proves that the first account's asset id and `enc_asset_id_using_other` are equal.
+    pub asset_id_equal_cipher_proof: CipherEqualDifferentPubKeyProof,
+    /// Proves that `enc_asset_id_using_other` and the second account's asset id are equal.
+    /// It is set by the owner of the second account.
+    pub asset_id_refreshed_same_proof: Option<CipherEqualSamePubKeyProof>,
+}
+
 /// The interface for the account creation.
 pub trait AccountCreatorInitializer {
     /// Creates a public account for a user and initializes the balance to zero.
@@ -262,6 +319,10 @@ impl core::fmt::Debug for TransferTxState {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AssetMemo {
     pub enc_issued_amount: EncryptedAmount,
+    /// An opaque blob that the issuer attaches to the issuance, e.g., the hash of the
+    /// off-chain terms. All the proofs of the issuance are bound to it, and it is at most
+    /// `MAX_ASSET_METADATA_LEN` bytes long.
+    pub metadata: Vec<u8>,
 }
 
 /// Holds the public portion of an asset issuance transaction after initialization.
@@ -276,6 +337,32 @@ pub struct InitializedAssetTx {
     pub auditors_payload: Vec<AuditorPayload>,
 }
 
+/// The issued amount encrypted to the issuer, the mediator, and optionally an auditor, under
+/// the same commitment witness. Each of the other ciphertexts carries a proof that it
+/// encrypts the same amount as the issuer's ciphertext.
+#[derive(Clone, Encode, Decode, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IssuanceCiphertexts {
+    pub issuer: EncryptedAmount,
+    pub mediator: EncryptedAmount,
+    pub mediator_equal_cipher_proof: CipherEqualDifferentPubKeyProof,
+    pub auditor: Option<(EncryptedAmount, CipherEqualDifferentPubKeyProof)>,
+}
+
+/// The part of an `InitializedAssetTx` without its proofs, see `InitializedAssetTx::split`.
+/// The proofs can then be stored separately, e.g., in a data availability layer, while the
+/// metadata commits to them with the hash of the proofs blob.
+#[derive(Clone, Encode, Decode, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AssetTxMetadata {
+    pub account_id: EncryptedAssetId,
+    pub memo: AssetMemo,
+    /// The auditor ids and encrypted amounts of the auditors' payload, in the same order.
+    pub auditors: Vec<([u8; 32], EncryptedAmountWithHint)>,
+    /// The hash of the proofs blob.
+    pub proofs_hash: [u8; 32],
+}
+
 /// The interface for the confidential asset issuance transaction.
 pub trait AssetTransactionIssuer {
     /// Initializes a confidential asset issue transaction. Note that the returning
@@ -284,6 +371,7 @@ pub trait AssetTransactionIssuer {
     fn initialize_asset_transaction<T: RngCore + CryptoRng>(
         &self,
         issr_account: &Account,
+        valid_asset_ids: &[Scalar],
         auditors_enc_pub_keys: &[AuditorPubAccount],
         amount: Balance,
         rng: &mut T,
@@ -371,11 +459,87 @@ pub struct JustifiedTransferTx {
     pub finalized_data: FinalizedTransferTx,
 }
 
+/// The kinds of the proofs that a transaction carries, as listed by the `describe_proofs`
+/// methods of the transaction types, e.g., for debugging and for explorers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofKind {
+    /// The proof that a cipher text is wellformed.
+    Wellformedness,
+    /// The proof that a cipher text encrypts a given value.
+    Correctness,
+    /// The proof that an encrypted value is a member of a set.
+    Membership,
+    /// The proof that an encrypted value is within a range.
+    Range,
+    /// The proof that two cipher texts, encrypted with different keys, encrypt the same value.
+    EqualityAcrossKeys,
+    /// The proof that a refreshed cipher text encrypts the same value as the original.
+    EqualityWithinKey,
+}
+
+/// The `ProofKind` of each proof type, so that the tests can derive the kinds that
+/// `describe_proofs` should report from the fields that a transaction actually holds.
+#[cfg(test)]
+pub(crate) trait ProofField {
+    const KIND: ProofKind;
+}
+
+#[cfg(test)]
+pub(crate) fn kind_of<P: ProofField>(_: &P) -> ProofKind {
+    P::KIND
+}
+
+#[cfg(test)]
+mod proof_fields {
+    use super::*;
+
+    impl ProofField for WellformednessProof {
+        const KIND: ProofKind = ProofKind::Wellformedness;
+    }
+
+    impl ProofField for CorrectnessProof {
+        const KIND: ProofKind = ProofKind::Correctness;
+    }
+
+    impl ProofField for MembershipProof {
+        const KIND: ProofKind = ProofKind::Membership;
+    }
+
+    impl ProofField for InRangeProof {
+        const KIND: ProofKind = ProofKind::Range;
+    }
+
+    impl ProofField for CipherEqualDifferentPubKeyProof {
+        const KIND: ProofKind = ProofKind::EqualityAcrossKeys;
+    }
+
+    impl ProofField for CipherEqualSamePubKeyProof {
+        const KIND: ProofKind = ProofKind::EqualityWithinKey;
+    }
+}
+
+/// An advisory estimate of the cost of verifying a transfer transaction with
+/// `TransactionValidator`. Returned by `estimate_verify_cost` of the transaction types.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VerifyCost {
+    /// The number of range proofs that are verified.
+    pub range_proofs: usize,
+    /// The number of sigma protocol proofs that are verified.
+    pub sigma_proofs: usize,
+    /// A rough estimate of the total verification time in nanoseconds.
+    pub estimated_nanos: u64,
+}
+
 /// The interface for confidential transaction.
 pub trait TransferTransactionSender {
     /// This is called by the sender of a confidential transaction. The outputs
     /// can be safely placed on the chain. It corresponds to `CreateCTX` function of
     /// MERCAT paper.
+    ///
+    /// In one call, this encrypts the amount for the sender, the receiver, the mediator,
+    /// and the auditors, refreshes the sender's balance and asset id, and builds the
+    /// range proofs and the correctness and equality proofs over these ciphertexts.
+    /// The result can be checked with `TransactionValidator::verify_initialized_transaction`.
     fn create_transaction<T: RngCore + CryptoRng>(
         &self,
         sender_account: &Account,
@@ -479,6 +643,59 @@ pub trait ReversedTransferTransactionVerifier {
     ) -> Fallible<TransferTxState>;
 }
 
+// -------------------------------------------------------------------------------------
+// -                               Refund Transaction                                  -
+// -------------------------------------------------------------------------------------
+
+/// Holds a signed adjustment of an account's balance, e.g., a negative adjustment that
+/// reverses a prior debit. The delta is encrypted with the account's public key, and is
+/// proven to be in the signed range of `BALANCE_RANGE` bits.
+///
+/// The refund is created by the owner of the account: the balance is refreshed with the
+/// account's secret key, and the adjusted balance is proven to be non-negative. Since
+/// nothing ties the refund to a counterparty or a mediator, the delta is also proven not
+/// to be positive, so that an owner cannot credit their own account.
+#[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RefundTx {
+    pub enc_delta: EncryptedAmount,
+    /// Binds the encrypted delta to the account's public key.
+    pub delta_wellformedness_proof: WellformednessProof,
+    pub delta_range_proof: InRangeProof,
+    pub refreshed_enc_balance: EncryptedAmount,
+    pub balance_refreshed_same_proof: CipherEqualSamePubKeyProof,
+    /// Proves that `refreshed_enc_balance + enc_delta` is in the range of the balances.
+    pub new_balance_range_proof: InRangeProof,
+    /// Proves that `-enc_delta` is in the range of the balances, i.e., that the delta is
+    /// not positive.
+    pub reversal_range_proof: InRangeProof,
+}
+
+// -------------------------------------------------------------------------------------
+// -                                Structural Checks                                  -
+// -------------------------------------------------------------------------------------
+
+/// The transactions that can be checked with `structural_check`.
+pub trait StructuralCheck: Decode {
+    /// Checks that the decoded fields are plausible, e.g., their sizes, without verifying
+    /// any of the proofs.
+    fn check_fields(&self) -> Fallible<()>;
+}
+
+/// Checks that `tx_bytes` is a well-formed SCALE encoding of a `T`: it must decode, use all
+/// the bytes, and have plausible field values. No proof is verified, so this is cheap enough
+/// for a first-pass filter, e.g., of a mempool, before scheduling the full verification.
+///
+/// Passing this check does NOT mean that the transaction is valid.
+pub fn structural_check<T: StructuralCheck>(tx_bytes: &[u8]) -> Fallible<()> {
+    let mut input = tx_bytes;
+    let tx = T::decode(&mut input).map_err(|_| ErrorKind::MalformedTransaction)?;
+    ensure!(input.is_empty(), ErrorKind::MalformedTransaction);
+    tx.check_fields()
+}
+
 pub mod account;
 pub mod asset;
+#[cfg(feature = "keystore")]
+pub mod keystore;
 pub mod transaction;
@@ -1,9 +1,10 @@
 use crate::{
-    Account, AuditorAccount, AuditorPayload, AuditorPubAccount, EncryptedAmount, EncryptionKeys,
-    EncryptionPubKey, FinalizedTransferTx, InitializedTransferTx, JustifiedTransferTx, PubAccount,
-    TransferTransactionAuditor, TransferTransactionMediator, TransferTransactionReceiver,
-    TransferTransactionSender, TransferTransactionVerifier, TransferTxMemo, TransferTxState,
-    TxSubstate,
+    account::verify_asset_id_equality, Account, AuditorAccount, AuditorPayload, AuditorPubAccount,
+    EncryptedAmount, EncryptedAssetId, EncryptionKeys, EncryptionPubKey, FinalizedTransferTx,
+    InitializedTransferTx, JustifiedTransferTx, ProofKind, PubAccount, RefundTx, SecAccount,
+    StructuralCheck, TransferTransactionAuditor, TransferTransactionMediator,
+    TransferTransactionReceiver, TransferTransactionSender, TransferTransactionVerifier,
+    TransferTxMemo, TransferTxState, TxSubstate, VerifyCost, MAX_BALANCE,
 };
 use cryptography_core::{
     asset_proofs::{
@@ -12,14 +13,19 @@ use cryptography_core::{
             CipherTextRefreshmentProverAwaitingChallenge, CipherTextRefreshmentVerifier,
         },
         correctness_proof::{CorrectnessProverAwaitingChallenge, CorrectnessVerifier},
-        elgamal_encryption::encrypt_using_two_pub_keys,
+        decryption_proof::{prove_correct_decryption, verify_correct_decryption, DecryptionProof},
+        elgamal_encryption::{enc_signed_value, encrypt_using_two_pub_keys},
         encrypting_same_value_proof::{
             EncryptingSameValueProverAwaitingChallenge, EncryptingSameValueVerifier,
         },
         encryption_proofs::single_property_prover,
         encryption_proofs::single_property_verifier,
         errors::{ErrorKind, Fallible},
-        range_proof::{prove_within_range, verify_within_range},
+        range_proof::{
+            prove_signed_within_range, prove_within_range, verify_range_matches_ciphertext,
+            verify_signed_within_range, verify_within_range, InRangeProof,
+        },
+        wellformedness_proof::{WellformednessProverAwaitingChallenge, WellformednessVerifier},
         AssetId, Balance, CommitmentWitness, BALANCE_RANGE,
     },
     curve25519_dalek::scalar::Scalar,
@@ -54,6 +60,14 @@ impl TransferTransactionSender for CtxSender {
         let sender_pub_account = &sender_account.public;
         let receiver_pub_key = receiver_pub_account.owner_enc_pub_key;
 
+        ensure!(
+            u64::from(amount) <= MAX_BALANCE,
+            ErrorKind::BalanceOverflow {
+                amount: amount.into(),
+                max_balance: MAX_BALANCE
+            }
+        );
+
         // NOTE: If this decryption ends up being too slow, we can pass in the balance
         // as input.
         let balance = sender_enc_keys.secret.decrypt(sender_init_balance)?;
@@ -244,6 +258,10 @@ fn add_transaction_auditor<T: RngCore + CryptoRng>(
         })
         .collect();
 
+    // Sort the payloads by the auditor id, so that the encoding of the transaction does not
+    // depend on the order in which the auditors were given.
+    payload_vec.sort_by(|a, b| a.auditor_id.cmp(&b.auditor_id));
+
     Ok(payload_vec)
 }
 
@@ -316,6 +334,48 @@ impl TransferTransactionMediator for CtxMediator {
         asset_id_hint: AssetId,
         rng: &mut R,
     ) -> Fallible<JustifiedTransferTx> {
+        self.justify_transaction_with_min_balance(
+            finalized_transaction,
+            mediator_enc_keys,
+            sender_account,
+            sender_init_balance,
+            receiver_account,
+            auditors_enc_pub_keys,
+            asset_id_hint,
+            None,
+            rng,
+        )
+    }
+}
+
+impl CtxMediator {
+    /// Justifies the transaction as in `justify_transaction`. If a `min_balance` is given,
+    /// it also rejects the transaction when its amount is below that floor.
+    ///
+    /// The accounts are created with a zero balance, so an asset that requires a minimum
+    /// balance requires the receiver's first deposit to meet it. The mediator is the party
+    /// that can decrypt the amount, but it cannot tell whether this is the receiver's first
+    /// deposit. The caller passes the asset's floor only for a first deposit, and `None`
+    /// otherwise.
+    #[allow(clippy::too_many_arguments)]
+    pub fn justify_transaction_with_min_balance<R: RngCore + CryptoRng>(
+        &self,
+        finalized_transaction: FinalizedTransferTx,
+        mediator_enc_keys: &EncryptionKeys,
+        sender_account: &PubAccount,
+        sender_init_balance: &EncryptedAmount,
+        receiver_account: &PubAccount,
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        asset_id_hint: AssetId,
+        min_balance: Option<Balance>,
+        rng: &mut R,
+    ) -> Fallible<JustifiedTransferTx> {
+        // Catch a mixed up key pair early, instead of failing on a decryption or a proof.
+        ensure!(
+            mediator_enc_keys.secret.get_public_key() == mediator_enc_keys.public,
+            ErrorKind::MisconfiguredMediatorKeys
+        );
+
         // Verify receiver's part of the transaction.
         let _ = verify_finalized_transaction(&finalized_transaction, receiver_account)?;
 
@@ -348,6 +408,16 @@ impl TransferTransactionMediator for CtxMediator {
             tx_data.amount_correctness_proof,
         )?;
 
+        if let Some(min_balance) = min_balance {
+            ensure!(
+                amount >= min_balance,
+                ErrorKind::BelowMinimumBalance {
+                    amount,
+                    min_balance
+                }
+            );
+        }
+
         // Verify that the encrypted asset_id is correct.
         mediator_enc_keys.secret.verify(
             &tx_data.memo.enc_asset_id_for_mediator,
@@ -379,6 +449,42 @@ impl TransferTransactionMediator for CtxMediator {
 #[derive(Clone, Debug)]
 pub struct TransactionValidator;
 
+impl TransactionValidator {
+    /// Verifies the proofs of a transaction that has only been initialized by the
+    /// sender, i.e., the output of `CtxSender::create_transaction`. This allows an
+    /// SDK user to check a transaction before it is sent to the receiver.
+    pub fn verify_initialized_transaction<R: RngCore + CryptoRng>(
+        &self,
+        initialized_transaction: &InitializedTransferTx,
+        sender_account: &PubAccount,
+        sender_init_balance: &EncryptedAmount,
+        receiver_account: &PubAccount,
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        rng: &mut R,
+    ) -> Fallible<()> {
+        let memo = &initialized_transaction.memo;
+        ensure!(
+            sender_account.enc_asset_id == memo.sender_account_id,
+            ErrorKind::AccountIdMismatch
+        );
+        ensure!(
+            receiver_account.enc_asset_id == memo.receiver_account_id,
+            ErrorKind::AccountIdMismatch
+        );
+
+        verify_initialized_transaction(
+            initialized_transaction,
+            sender_account,
+            sender_init_balance,
+            receiver_account,
+            auditors_enc_pub_keys,
+            rng,
+        )?;
+
+        Ok(())
+    }
+}
+
 impl TransferTransactionVerifier for TransactionValidator {
     fn verify_transaction<R: RngCore + CryptoRng>(
         &self,
@@ -424,6 +530,195 @@ impl TransferTransactionVerifier for TransactionValidator {
     }
 }
 
+impl StructuralCheck for InitializedTransferTx {
+    fn check_fields(&self) -> Fallible<()> {
+        ensure!(
+            self.non_neg_amount_proof.range == BALANCE_RANGE
+                && self.enough_fund_proof.range == BALANCE_RANGE,
+            ErrorKind::MalformedTransaction
+        );
+        Ok(())
+    }
+}
+
+impl StructuralCheck for FinalizedTransferTx {
+    fn check_fields(&self) -> Fallible<()> {
+        self.init_data.check_fields()
+    }
+}
+
+impl StructuralCheck for JustifiedTransferTx {
+    fn check_fields(&self) -> Fallible<()> {
+        self.finalized_data.check_fields()
+    }
+}
+
+/// The default verification time of a range proof, used when no calibration is available.
+const DEFAULT_RANGE_PROOF_VERIFY_NANOS: u64 = 2_500_000;
+/// The default verification time of a sigma protocol proof, used when no calibration is available.
+const DEFAULT_SIGMA_PROOF_VERIFY_NANOS: u64 = 150_000;
+
+impl VerifyCost {
+    fn new(range_proofs: usize, sigma_proofs: usize) -> Self {
+        let (range_proof_nanos, sigma_proof_nanos) = proof_verify_nanos();
+        VerifyCost {
+            range_proofs,
+            sigma_proofs,
+            estimated_nanos: range_proofs as u64 * range_proof_nanos
+                + sigma_proofs as u64 * sigma_proof_nanos,
+        }
+    }
+}
+
+impl InitializedTransferTx {
+    /// Estimates the cost of verifying the proofs of this transaction with
+    /// `TransactionValidator::verify_initialized_transaction`. This is advisory, not exact.
+    pub fn estimate_verify_cost(&self) -> VerifyCost {
+        // The non-negative amount and enough fund proofs.
+        let range_proofs = 2;
+        // The amount equality, balance and asset id refreshment, and asset id equality
+        // proofs, plus an amount equality proof per auditor.
+        let sigma_proofs = 4 + self.auditors_payload.len();
+        VerifyCost::new(range_proofs, sigma_proofs)
+    }
+
+    /// Lists the kinds of the proofs that this transaction carries, in the order of its
+    /// fields, followed by an equality proof per auditor. The proofs are not verified.
+    pub fn describe_proofs(&self) -> Vec<ProofKind> {
+        let mut proofs = vec![
+            ProofKind::EqualityAcrossKeys,
+            ProofKind::Range,
+            ProofKind::Range,
+            ProofKind::EqualityAcrossKeys,
+            ProofKind::EqualityWithinKey,
+            ProofKind::EqualityWithinKey,
+            ProofKind::Correctness,
+            ProofKind::Correctness,
+        ];
+        proofs.extend(
+            self.auditors_payload
+                .iter()
+                .map(|_| ProofKind::EqualityAcrossKeys),
+        );
+        proofs
+    }
+}
+
+impl FinalizedTransferTx {
+    /// Estimates the cost of verifying the proofs of this transaction with
+    /// `TransactionValidator::verify_transaction`. This is advisory, not exact.
+    pub fn estimate_verify_cost(&self) -> VerifyCost {
+        let init_cost = self.init_data.estimate_verify_cost();
+        // The receiver's asset id equality proof.
+        VerifyCost::new(init_cost.range_proofs, init_cost.sigma_proofs + 1)
+    }
+}
+
+impl JustifiedTransferTx {
+    /// Estimates the cost of verifying the proofs of this transaction with
+    /// `TransactionValidator::verify_transaction`. This is advisory, not exact.
+    pub fn estimate_verify_cost(&self) -> VerifyCost {
+        self.finalized_data.estimate_verify_cost()
+    }
+
+    /// Returns the signed amount that this transaction applies to the balance of the account
+    /// of `sec_account`, i.e., the negated debit of the sender, or the credit of the receiver.
+    /// This is the amount that `withdraw` and `deposit` apply to the encrypted balances.
+    /// Returns `ErrorKind::AccountIdMismatch` if the account is not part of the transaction.
+    pub fn balance_delta(&self, sec_account: &SecAccount) -> Fallible<i128> {
+        let memo = &self.finalized_data.init_data.memo;
+        let secret_key = &sec_account.enc_keys.secret;
+        let asset_id = sec_account.asset_id_witness.value();
+        // An account id decrypts to the asset id only under the key of its owner.
+        let owns = |account_id: &EncryptedAssetId| secret_key.verify(account_id, &asset_id).is_ok();
+
+        if owns(&memo.sender_account_id) {
+            let debit = secret_key.decrypt(&memo.enc_amount_using_sender)?;
+            Ok(-i128::from(debit))
+        } else if owns(&memo.receiver_account_id) {
+            let credit = secret_key.decrypt(&memo.enc_amount_using_receiver)?;
+            Ok(i128::from(credit))
+        } else {
+            Err(ErrorKind::AccountIdMismatch.into())
+        }
+    }
+}
+
+/// Returns the verification times of a range proof and a sigma protocol proof, in nanoseconds.
+/// Where a clock is available, they are measured once with a benchmark on the first call.
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+fn proof_verify_nanos() -> (u64, u64) {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Once,
+    };
+
+    static CALIBRATION: Once = Once::new();
+    static RANGE_PROOF_NANOS: AtomicU64 = AtomicU64::new(DEFAULT_RANGE_PROOF_VERIFY_NANOS);
+    static SIGMA_PROOF_NANOS: AtomicU64 = AtomicU64::new(DEFAULT_SIGMA_PROOF_VERIFY_NANOS);
+
+    CALIBRATION.call_once(|| {
+        if let Ok((range_proof_nanos, sigma_proof_nanos)) = calibrate_proof_verify_nanos() {
+            RANGE_PROOF_NANOS.store(range_proof_nanos, Ordering::Relaxed);
+            SIGMA_PROOF_NANOS.store(sigma_proof_nanos, Ordering::Relaxed);
+        }
+    });
+
+    (
+        RANGE_PROOF_NANOS.load(Ordering::Relaxed),
+        SIGMA_PROOF_NANOS.load(Ordering::Relaxed),
+    )
+}
+
+#[cfg(not(all(feature = "std", not(target_arch = "wasm32"))))]
+fn proof_verify_nanos() -> (u64, u64) {
+    (
+        DEFAULT_RANGE_PROOF_VERIFY_NANOS,
+        DEFAULT_SIGMA_PROOF_VERIFY_NANOS,
+    )
+}
+
+/// Times the verification of a range proof and an encrypting same value proof.
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+fn calibrate_proof_verify_nanos() -> Fallible<(u64, u64)> {
+    use cryptography_core::asset_proofs::ElgamalSecretKey;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::time::Instant;
+
+    let mut rng = StdRng::from_seed([42u8; 32]);
+    let gens = PedersenGens::default();
+
+    let range_proof = prove_within_range(0, Scalar::random(&mut rng), BALANCE_RANGE, &mut rng)?;
+    let start = Instant::now();
+    verify_within_range(&range_proof, &mut rng)?;
+    let range_proof_nanos = start.elapsed().as_nanos() as u64;
+
+    let pub_key1 = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+    let pub_key2 = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+    let witness = CommitmentWitness::new(Scalar::from(0u32), Scalar::random(&mut rng));
+    let sigma_proof = single_property_prover(
+        EncryptingSameValueProverAwaitingChallenge {
+            pub_key1,
+            pub_key2,
+            w: Zeroizing::new(witness.clone()),
+            pc_gens: &gens,
+        },
+        &mut rng,
+    )?;
+    let verifier = EncryptingSameValueVerifier {
+        pub_key1,
+        pub_key2,
+        cipher1: pub_key1.encrypt(&witness),
+        cipher2: pub_key2.encrypt(&witness),
+        pc_gens: &gens,
+    };
+    let start = Instant::now();
+    single_property_verifier(&verifier, sigma_proof)?;
+    let sigma_proof_nanos = start.elapsed().as_nanos() as u64;
+
+    Ok((range_proof_nanos, sigma_proof_nanos))
+}
+
 fn verify_initialized_transaction<R: RngCore + CryptoRng>(
     transaction: &InitializedTransferTx,
     sender_account: &PubAccount,
@@ -491,6 +786,10 @@ fn verify_initial_transaction_proofs<R: RngCore + CryptoRng>(
     )?;
 
     // Verify that the amount is not negative.
+    verify_range_matches_ciphertext(
+        &init_data.non_neg_amount_proof.init,
+        &memo.enc_amount_using_sender,
+    )?;
     verify_within_range(&init_data.non_neg_amount_proof, rng)?;
 
     // verify that the balance refreshment was done correctly.
@@ -505,6 +804,10 @@ fn verify_initial_transaction_proofs<R: RngCore + CryptoRng>(
     )?;
 
     // Verify that the balance has enough fund.
+    verify_range_matches_ciphertext(
+        &init_data.enough_fund_proof.init,
+        &(&memo.refreshed_enc_balance - &memo.enc_amount_using_sender),
+    )?;
     verify_within_range(&init_data.enough_fund_proof, rng)?;
 
     // Verify that the asset id refreshment was done correctly.
@@ -521,14 +824,11 @@ fn verify_initial_transaction_proofs<R: RngCore + CryptoRng>(
     // In the initial transaction, the sender has encrypted the asset id
     // using the receiver pub key. We verify that this encrypted asset id
     // is the same as the one in the sender account.
-    single_property_verifier(
-        &EncryptingSameValueVerifier {
-            pub_key1: sender_account.owner_enc_pub_key,
-            pub_key2: receiver_account.owner_enc_pub_key,
-            cipher1: memo.refreshed_enc_asset_id,
-            cipher2: memo.enc_asset_id_using_receiver,
-            pc_gens: &gens,
-        },
+    verify_asset_id_equality(
+        sender_account.owner_enc_pub_key,
+        memo.refreshed_enc_asset_id,
+        receiver_account.owner_enc_pub_key,
+        memo.enc_asset_id_using_receiver,
         init_data.asset_id_equal_cipher_with_sender_receiver_keys_proof,
     )?;
 
@@ -556,11 +856,11 @@ fn verify_auditor_payload(
     );
 
     let gens = &PedersenGens::default();
-    let _: Fallible<()> = auditors_enc_pub_keys
+    auditors_enc_pub_keys
         .iter()
         .map(|auditor| {
             let mut found_auditor = false;
-            let _: Fallible<()> = auditors_payload
+            auditors_payload
                 .iter()
                 .map(|payload| {
                     if auditor.auditor_id == payload.auditor_id {
@@ -579,15 +879,217 @@ fn verify_auditor_payload(
                     }
                     Ok(())
                 })
-                .collect();
+                .collect::<Fallible<()>>()?;
             ensure!(found_auditor, ErrorKind::AuditorPayloadError);
             Ok(())
         })
-        .collect();
+        .collect::<Fallible<()>>()?;
+
+    Ok(())
+}
+
+/// The homomorphic difference between the sum of the `outputs` and the `sender_debit`, which
+/// encrypts zero if the value is conserved.
+fn conservation_difference(
+    sender_debit: &EncryptedAmount,
+    outputs: &[EncryptedAmount],
+) -> EncryptedAmount {
+    let mut difference = &EncryptedAmount::default() - sender_debit;
+    for output in outputs {
+        difference += output;
+    }
+    difference
+}
+
+/// Proves, for `verify_value_conservation`, that the `outputs` of a multi-output transfer add
+/// up to the `sender_debit`. The outputs and the debit must be encrypted with the sender's
+/// public key, but their blinding factors are arbitrary, since the proof only needs the
+/// sender's secret key. The range proofs of the outputs are created separately, e.g., with
+/// `prove_within_range`.
+pub fn prove_value_conservation<R: RngCore + CryptoRng>(
+    sender_enc_keys: &EncryptionKeys,
+    sender_debit: &EncryptedAmount,
+    outputs: &[EncryptedAmount],
+    rng: &mut R,
+) -> Fallible<DecryptionProof> {
+    prove_correct_decryption(
+        &sender_enc_keys.secret,
+        &conservation_difference(sender_debit, outputs),
+        0,
+        rng,
+    )
+}
+
+/// Verifies that the outputs of a multi-output transfer, i.e., the receivers' credits and the
+/// sender's change, add up to the sender's debit. Each output must come with a range proof,
+/// since a negative output would let the others add up to more than the debit, and
+/// `conservation_proof` must prove that the sum of the outputs minus the debit encrypts zero.
+/// The outputs must be encrypted with the sender's public key. Outputs under other keys must
+/// first be tied to such a ciphertext with an equality proof.
+pub fn verify_value_conservation<R: RngCore + CryptoRng>(
+    sender_enc_pub_key: &EncryptionPubKey,
+    sender_debit: &EncryptedAmount,
+    outputs: &[(EncryptedAmount, InRangeProof)],
+    conservation_proof: &DecryptionProof,
+    rng: &mut R,
+) -> Fallible<()> {
+    for (output, range_proof) in outputs {
+        verify_range_matches_ciphertext(&range_proof.init, output)?;
+        verify_within_range(range_proof, rng)?;
+    }
+
+    let outputs: Vec<EncryptedAmount> = outputs.iter().map(|(output, _)| *output).collect();
+    verify_correct_decryption(
+        sender_enc_pub_key,
+        &conservation_difference(sender_debit, &outputs),
+        0,
+        conservation_proof,
+    )
+    .map_err(|_| ErrorKind::ValueNotConserved)?;
 
     Ok(())
 }
 
+// ------------------------------------------------------------------------------------------------
+// -                                           Refund                                           -
+// ------------------------------------------------------------------------------------------------
+
+impl RefundTx {
+    /// Encrypts the signed `delta` with the account's public key and proves that it is in the
+    /// signed range of `BALANCE_RANGE` bits and not positive. The account's balance,
+    /// `enc_balance`, is refreshed, and the balance after applying the delta is proven to be
+    /// non-negative. A positive delta is rejected with `ErrorKind::PositiveRefund`.
+    pub fn create<R: RngCore + CryptoRng>(
+        account_enc_keys: &EncryptionKeys,
+        enc_balance: &EncryptedAmount,
+        delta: i64,
+        rng: &mut R,
+    ) -> Fallible<Self> {
+        ensure!(delta <= 0, ErrorKind::PositiveRefund { delta });
+
+        let gens = PedersenGens::default();
+        let (witness, enc_delta) = enc_signed_value(delta, &account_enc_keys.public, rng);
+        let delta_range_proof =
+            prove_signed_within_range(delta, witness.blinding(), BALANCE_RANGE, rng)?;
+        let reversal_range_proof =
+            prove_within_range((-delta) as u64, -witness.blinding(), BALANCE_RANGE, rng)?;
+
+        let balance = account_enc_keys.secret.decrypt(enc_balance)?;
+        let new_balance = i64::from(balance) + delta;
+        ensure!(
+            new_balance >= 0,
+            ErrorKind::NotEnoughFund {
+                balance,
+                transaction_amount: (-delta) as u32,
+            }
+        );
+        ensure!(
+            new_balance as u64 <= MAX_BALANCE,
+            ErrorKind::BalanceOverflow {
+                amount: new_balance as u64,
+                max_balance: MAX_BALANCE
+            }
+        );
+
+        let delta_wellformedness_proof = single_property_prover(
+            WellformednessProverAwaitingChallenge {
+                pub_key: account_enc_keys.public,
+                w: Zeroizing::new(witness.clone()),
+                pc_gens: &gens,
+            },
+            rng,
+        )?;
+
+        // Refresh the encrypted balance, so that the blinding of the new balance is known.
+        let balance_refresh_enc_blinding = Scalar::random(rng);
+        let refreshed_enc_balance =
+            enc_balance.refresh(&account_enc_keys.secret, balance_refresh_enc_blinding)?;
+        let balance_refreshed_same_proof = single_property_prover(
+            CipherTextRefreshmentProverAwaitingChallenge::new(
+                account_enc_keys.secret.clone(),
+                *enc_balance,
+                refreshed_enc_balance,
+                &gens,
+            ),
+            rng,
+        )?;
+
+        let new_balance_range_proof = prove_within_range(
+            new_balance as u64,
+            balance_refresh_enc_blinding + witness.blinding(),
+            BALANCE_RANGE,
+            rng,
+        )?;
+
+        Ok(RefundTx {
+            enc_delta,
+            delta_wellformedness_proof,
+            delta_range_proof,
+            refreshed_enc_balance,
+            balance_refreshed_same_proof,
+            new_balance_range_proof,
+            reversal_range_proof,
+        })
+    }
+
+    /// Verifies that the encrypted delta is in the signed range of `BALANCE_RANGE` bits, is
+    /// not positive, and is encrypted with the account's public key, and that applying it to
+    /// `enc_balance` results in a non-negative balance.
+    pub fn verify<R: RngCore + CryptoRng>(
+        &self,
+        account_enc_pub_key: &EncryptionPubKey,
+        enc_balance: &EncryptedAmount,
+        rng: &mut R,
+    ) -> Fallible<()> {
+        let gens = PedersenGens::default();
+        verify_signed_within_range(
+            &self.enc_delta.y,
+            BALANCE_RANGE,
+            &self.delta_range_proof,
+            rng,
+        )?;
+        verify_range_matches_ciphertext(
+            &self.reversal_range_proof.init,
+            &(&EncryptedAmount::default() - &self.enc_delta),
+        )?;
+        verify_within_range(&self.reversal_range_proof, rng)?;
+        single_property_verifier(
+            &WellformednessVerifier {
+                pub_key: *account_enc_pub_key,
+                cipher: self.enc_delta,
+                pc_gens: &gens,
+            },
+            self.delta_wellformedness_proof,
+        )?;
+
+        single_property_verifier(
+            &CipherTextRefreshmentVerifier::new(
+                *account_enc_pub_key,
+                *enc_balance,
+                self.refreshed_enc_balance,
+                &gens,
+            ),
+            self.balance_refreshed_same_proof,
+        )?;
+        verify_range_matches_ciphertext(
+            &self.new_balance_range_proof.init,
+            &(&self.refreshed_enc_balance + &self.enc_delta),
+        )?;
+        verify_within_range(&self.new_balance_range_proof, rng)
+    }
+
+    /// Verifies the refund and returns the account's balance with the delta applied.
+    pub fn apply<R: RngCore + CryptoRng>(
+        &self,
+        account_enc_pub_key: &EncryptionPubKey,
+        enc_balance: &EncryptedAmount,
+        rng: &mut R,
+    ) -> Fallible<EncryptedAmount> {
+        self.verify(account_enc_pub_key, enc_balance, rng)?;
+        Ok(enc_balance + &self.enc_delta)
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // -                                          Auditor                                           -
 // ------------------------------------------------------------------------------------------------
@@ -666,9 +1168,10 @@ mod tests {
     use super::*;
     use crate::{
         account::{deposit, withdraw},
-        EncryptedAmount, EncryptedAmountWithHint, EncryptedAssetId, EncryptionKeys,
-        EncryptionPubKey, SecAccount, TransferTxMemo,
+        kind_of, structural_check, EncryptedAmount, EncryptedAmountWithHint, EncryptedAssetId,
+        EncryptionKeys, EncryptionPubKey, SecAccount, TransferTxMemo,
     };
+    use codec::{Decode, Encode};
     use cryptography_core::{
         asset_proofs::{
             ciphertext_refreshment_proof::CipherEqualSamePubKeyProof,
@@ -976,30 +1479,695 @@ mod tests {
             .is_ok());
     }
 
-    // ------------------------------ Test Auditing Logic
-    fn account_create_helper(
-        seed0: [u8; 32],
-        seed1: u8,
-        balance: Balance,
-        asset_id: AssetId,
-    ) -> (Account, EncryptedAmount) {
-        let mut rng = StdRng::from_seed(seed0);
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_ctx_create_and_verify_initialization() {
+        let sender = CtxSender;
+        let tx_validator = TransactionValidator;
+        let asset_id = AssetId::from(20);
+        let amount = 30;
 
-        let enc_keys = mock_gen_enc_key_pair(seed1);
+        let mut rng = StdRng::from_seed([17u8; 32]);
+        let (sender_account, sender_init_balance) =
+            account_create_helper([18u8; 32], 10u8, 40, asset_id.clone());
+        let (receiver_account, _) = account_create_helper([19u8; 32], 12u8, 0, asset_id);
+        let mediator_enc_keys = mock_gen_enc_key_pair(14u8);
 
-        let (pub_account, init_balance) =
-            mock_gen_account(enc_keys.public, asset_id.clone(), balance, &mut rng).unwrap();
+        // A single call builds the encrypted amounts, range proofs, and correctness proofs.
+        let ctx_init_data = sender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                &receiver_account.public,
+                &mediator_enc_keys.public,
+                &[],
+                amount,
+                &mut rng,
+            )
+            .unwrap();
 
-        (
-            Account {
-                public: pub_account,
-                secret: SecAccount {
-                    enc_keys,
-                    asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
-                },
-            },
-            init_balance,
-        )
+        assert!(tx_validator
+            .verify_initialized_transaction(
+                &ctx_init_data,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                &mut rng,
+            )
+            .is_ok());
+
+        // The transaction cannot be validated against other accounts.
+        assert_err!(
+            tx_validator.verify_initialized_transaction(
+                &ctx_init_data,
+                &receiver_account.public,
+                &sender_init_balance,
+                &sender_account.public,
+                &[],
+                &mut rng,
+            ),
+            ErrorKind::AccountIdMismatch
+        );
+
+        // A range proof about a commitment other than the sender's remaining balance is
+        // rejected, even though the range proof itself is valid.
+        let mut bad_init_data = ctx_init_data.clone();
+        bad_init_data.enough_fund_proof = bad_init_data.non_neg_amount_proof.clone();
+        assert_err!(
+            tx_validator.verify_initialized_transaction(
+                &bad_init_data,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                &mut rng,
+            ),
+            ErrorKind::RangeCommitmentMismatch
+        );
+
+        // A sender's balance that does not match the proofs fails the verification.
+        let (_, other_balance) = sender_account
+            .public
+            .owner_enc_pub_key
+            .encrypt_value(Scalar::from(5u32), &mut rng);
+        assert!(tx_validator
+            .verify_initialized_transaction(
+                &ctx_init_data,
+                &sender_account.public,
+                &other_balance,
+                &receiver_account.public,
+                &[],
+                &mut rng,
+            )
+            .is_err());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_estimate_verify_cost() {
+        let sender = CtxSender;
+        let receiver = CtxReceiver;
+        let mediator = CtxMediator;
+        let asset_id = AssetId::from(20);
+        let amount = 30;
+
+        let mut rng = StdRng::from_seed([17u8; 32]);
+        let (sender_account, sender_init_balance) =
+            account_create_helper([18u8; 32], 10u8, 40, asset_id.clone());
+        let (receiver_account, _) = account_create_helper([19u8; 32], 12u8, 0, asset_id.clone());
+        let mediator_enc_keys = mock_gen_enc_key_pair(14u8);
+        let auditors: Vec<AuditorPubAccount> = (0..2u8)
+            .map(|index| AuditorPubAccount {
+                auditor_id: [index; 32],
+                encryption_public_key: mock_gen_enc_key_pair(index).public,
+            })
+            .collect();
+
+        let ctx_init = sender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                &receiver_account.public,
+                &mediator_enc_keys.public,
+                &auditors,
+                amount,
+                &mut rng,
+            )
+            .unwrap();
+        let init_cost = ctx_init.estimate_verify_cost();
+        assert_eq!(init_cost.range_proofs, 2);
+        assert_eq!(init_cost.sigma_proofs, 4 + ctx_init.auditors_payload.len());
+        assert_eq!(init_cost.sigma_proofs, 6);
+        assert!(init_cost.estimated_nanos > 0);
+
+        let ctx_final = receiver
+            .finalize_transaction(ctx_init, receiver_account.clone(), amount, &mut rng)
+            .unwrap();
+        let final_cost = ctx_final.estimate_verify_cost();
+        assert_eq!(final_cost.range_proofs, init_cost.range_proofs);
+        assert_eq!(final_cost.sigma_proofs, init_cost.sigma_proofs + 1);
+        assert!(final_cost.estimated_nanos > init_cost.estimated_nanos);
+
+        let ctx_just = mediator
+            .justify_transaction(
+                ctx_final,
+                &mediator_enc_keys,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &auditors,
+                asset_id,
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(ctx_just.estimate_verify_cost(), final_cost);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_describe_proofs() {
+        let asset_id = AssetId::from(20);
+        let amount = 30;
+        let mut rng = StdRng::from_seed([17u8; 32]);
+        let (sender_account, sender_init_balance) =
+            account_create_helper([18u8; 32], 10u8, 40, asset_id.clone());
+        let (receiver_account, _) = account_create_helper([19u8; 32], 12u8, 0, asset_id);
+        let mediator_enc_keys = mock_gen_enc_key_pair(14u8);
+        let auditors: Vec<AuditorPubAccount> = (0..2u8)
+            .map(|index| AuditorPubAccount {
+                auditor_id: [index; 32],
+                encryption_public_key: mock_gen_enc_key_pair(index).public,
+            })
+            .collect();
+
+        let ctx_init = CtxSender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                &receiver_account.public,
+                &mediator_enc_keys.public,
+                &auditors,
+                amount,
+                &mut rng,
+            )
+            .unwrap();
+
+        // The described proofs match the proof fields of the decoded transaction. Listing
+        // every field makes this fail to compile when a field is added.
+        let decoded = InitializedTransferTx::decode(&mut &ctx_init.encode()[..]).unwrap();
+        let InitializedTransferTx {
+            amount_equal_cipher_proof,
+            non_neg_amount_proof,
+            enough_fund_proof,
+            memo: _,
+            asset_id_equal_cipher_with_sender_receiver_keys_proof,
+            balance_refreshed_same_proof,
+            asset_id_refreshed_same_proof,
+            asset_id_correctness_proof,
+            amount_correctness_proof,
+            auditors_payload,
+        } = &decoded;
+        let mut present = vec![
+            kind_of(amount_equal_cipher_proof),
+            kind_of(non_neg_amount_proof),
+            kind_of(enough_fund_proof),
+            kind_of(asset_id_equal_cipher_with_sender_receiver_keys_proof),
+            kind_of(balance_refreshed_same_proof),
+            kind_of(asset_id_refreshed_same_proof),
+            kind_of(asset_id_correctness_proof),
+            kind_of(amount_correctness_proof),
+        ];
+        present.extend(
+            auditors_payload
+                .iter()
+                .map(|payload| kind_of(&payload.amount_equal_cipher_proof)),
+        );
+        assert_eq!(auditors_payload.len(), auditors.len());
+        assert_eq!(decoded.describe_proofs(), present);
+
+        let count = |kind| {
+            decoded
+                .describe_proofs()
+                .into_iter()
+                .filter(|proof| *proof == kind)
+                .count()
+        };
+        assert_eq!(
+            count(ProofKind::Range),
+            decoded.estimate_verify_cost().range_proofs
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_structural_check() {
+        let asset_id = AssetId::from(20);
+        let amount = 30;
+        let mut rng = StdRng::from_seed([25u8; 32]);
+        let (sender_account, sender_init_balance) =
+            account_create_helper([18u8; 32], 10u8, 40, asset_id.clone());
+        let (receiver_account, _) = account_create_helper([19u8; 32], 12u8, 0, asset_id);
+        let mediator_enc_keys = mock_gen_enc_key_pair(14u8);
+
+        let ctx_init = CtxSender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                &receiver_account.public,
+                &mediator_enc_keys.public,
+                &[],
+                amount,
+                &mut rng,
+            )
+            .unwrap();
+        let bytes = ctx_init.encode();
+        assert!(structural_check::<InitializedTransferTx>(&bytes).is_ok());
+
+        // Truncated transactions, and transactions with trailing bytes, are malformed.
+        assert_err!(
+            structural_check::<InitializedTransferTx>(&bytes[..bytes.len() - 1]),
+            ErrorKind::MalformedTransaction
+        );
+        let mut long_bytes = bytes.clone();
+        long_bytes.push(0);
+        assert_err!(
+            structural_check::<InitializedTransferTx>(&long_bytes),
+            ErrorKind::MalformedTransaction
+        );
+
+        // So are the range proofs of an unexpected bitsize.
+        let mut bad_range_tx = ctx_init.clone();
+        bad_range_tx.enough_fund_proof.range = 8;
+        assert_err!(
+            structural_check::<InitializedTransferTx>(&bad_range_tx.encode()),
+            ErrorKind::MalformedTransaction
+        );
+
+        // A structurally valid transaction still requires the full verification.
+        let mut bad_proof_tx = ctx_init;
+        bad_proof_tx.amount_equal_cipher_proof = Default::default();
+        assert!(structural_check::<InitializedTransferTx>(&bad_proof_tx.encode()).is_ok());
+        assert_err!(
+            TransactionValidator.verify_initialized_transaction(
+                &bad_proof_tx,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                &mut rng,
+            ),
+            ErrorKind::EncryptingSameValueFinalResponseVerificationError { check: 1 }
+        );
+    }
+
+    // ------------------------------ Test Auditing Logic
+    fn account_create_helper(
+        seed0: [u8; 32],
+        seed1: u8,
+        balance: Balance,
+        asset_id: AssetId,
+    ) -> (Account, EncryptedAmount) {
+        let mut rng = StdRng::from_seed(seed0);
+
+        let enc_keys = mock_gen_enc_key_pair(seed1);
+
+        let (pub_account, init_balance) =
+            mock_gen_account(enc_keys.public, asset_id.clone(), balance, &mut rng).unwrap();
+
+        (
+            Account {
+                public: pub_account,
+                secret: SecAccount {
+                    enc_keys,
+                    asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+                },
+            },
+            init_balance,
+        )
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_balance_delta() {
+        let asset_id = AssetId::from(20);
+        let amount = 400;
+        let mut rng = StdRng::from_seed([22u8; 32]);
+
+        let mediator_enc_keys = mock_gen_enc_key_pair(140u8);
+        let (receiver_account, _) = account_create_helper([18u8; 32], 120u8, 0, asset_id.clone());
+        let (sender_account, sender_init_balance) =
+            account_create_helper([17u8; 32], 100u8, 500, asset_id.clone());
+        let (other_account, _) = account_create_helper([16u8; 32], 80u8, 0, asset_id.clone());
+
+        let ctx_init = CtxSender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                &receiver_account.public,
+                &mediator_enc_keys.public,
+                &[],
+                amount,
+                &mut rng,
+            )
+            .unwrap();
+        let ctx_final = CtxReceiver
+            .finalize_transaction(ctx_init, receiver_account.clone(), amount, &mut rng)
+            .unwrap();
+        let ctx_just = CtxMediator
+            .justify_transaction(
+                ctx_final,
+                &mediator_enc_keys,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                asset_id,
+                &mut rng,
+            )
+            .unwrap();
+
+        // The sender is debited and the receiver is credited.
+        assert_eq!(
+            ctx_just.balance_delta(&sender_account.secret).unwrap(),
+            -400
+        );
+        assert_eq!(
+            ctx_just.balance_delta(&receiver_account.secret).unwrap(),
+            400
+        );
+
+        // Negative test: the account is not part of the transaction.
+        assert_err!(
+            ctx_just.balance_delta(&other_account.secret),
+            ErrorKind::AccountIdMismatch
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_justify_with_misconfigured_mediator_keys() {
+        let asset_id = AssetId::from(20);
+        let amount = 400;
+        let mut rng = StdRng::from_seed([21u8; 32]);
+
+        let mediator_enc_keys = mock_gen_enc_key_pair(140u8);
+        let other_enc_keys = mock_gen_enc_key_pair(141u8);
+        let (receiver_account, _) = account_create_helper([18u8; 32], 120u8, 0, asset_id.clone());
+        let (sender_account, sender_init_balance) =
+            account_create_helper([17u8; 32], 100u8, 500, asset_id.clone());
+
+        let ctx_init = CtxSender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                &receiver_account.public,
+                &mediator_enc_keys.public,
+                &[],
+                amount,
+                &mut rng,
+            )
+            .unwrap();
+        let ctx_final = CtxReceiver
+            .finalize_transaction(ctx_init, receiver_account.clone(), amount, &mut rng)
+            .unwrap();
+
+        // The mediator's public key is paired with another account's secret key.
+        let swapped_keys = EncryptionKeys {
+            public: mediator_enc_keys.public,
+            secret: other_enc_keys.secret.clone(),
+        };
+        assert_err!(
+            CtxMediator.justify_transaction(
+                ctx_final.clone(),
+                &swapped_keys,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                asset_id.clone(),
+                &mut rng,
+            ),
+            ErrorKind::MisconfiguredMediatorKeys
+        );
+
+        // The correctly paired keys justify the transaction.
+        assert!(CtxMediator
+            .justify_transaction(
+                ctx_final,
+                &mediator_enc_keys,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                asset_id,
+                &mut rng,
+            )
+            .is_ok());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_justify_first_deposit_with_min_balance() {
+        let asset_id = AssetId::from(20);
+        let min_balance = 100;
+        let mut rng = StdRng::from_seed([23u8; 32]);
+
+        let mediator_enc_keys = mock_gen_enc_key_pair(140u8);
+        let (receiver_account, _) = account_create_helper([18u8; 32], 120u8, 0, asset_id.clone());
+        let (sender_account, sender_init_balance) =
+            account_create_helper([17u8; 32], 100u8, 500, asset_id.clone());
+
+        let mut justify = |amount: Balance| {
+            let ctx_init = CtxSender
+                .create_transaction(
+                    &sender_account,
+                    &sender_init_balance,
+                    &receiver_account.public,
+                    &mediator_enc_keys.public,
+                    &[],
+                    amount,
+                    &mut rng,
+                )
+                .unwrap();
+            let ctx_final = CtxReceiver
+                .finalize_transaction(ctx_init, receiver_account.clone(), amount, &mut rng)
+                .unwrap();
+            CtxMediator.justify_transaction_with_min_balance(
+                ctx_final,
+                &mediator_enc_keys,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                asset_id.clone(),
+                Some(min_balance),
+                &mut rng,
+            )
+        };
+
+        // A first deposit below the floor is rejected.
+        assert_err!(
+            justify(99),
+            ErrorKind::BelowMinimumBalance {
+                amount: 99,
+                min_balance,
+            }
+        );
+
+        // A first deposit at or above the floor is justified.
+        assert!(justify(100).is_ok());
+        assert!(justify(400).is_ok());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_refund_with_negative_delta() {
+        let mut rng = StdRng::from_seed([24u8; 32]);
+        let enc_keys = mock_gen_enc_key_pair(150u8);
+        let (_, enc_balance) = enc_keys
+            .public
+            .encrypt_value(Scalar::from(500u32), &mut rng);
+
+        // A negative delta reverses a prior debit.
+        let refund = RefundTx::create(&enc_keys, &enc_balance, -200, &mut rng).unwrap();
+        let new_balance = refund
+            .apply(&enc_keys.public, &enc_balance, &mut rng)
+            .unwrap();
+        assert_eq!(enc_keys.secret.decrypt(&new_balance).unwrap(), 300);
+
+        // The owner cannot credit their own account.
+        assert_err!(
+            RefundTx::create(&enc_keys, &enc_balance, 200, &mut rng),
+            ErrorKind::PositiveRefund { delta: 200 }
+        );
+
+        // The range proof is bound to the encrypted delta.
+        let mut bad_refund = refund.clone();
+        bad_refund.enc_delta = enc_balance;
+        assert_err!(
+            bad_refund.apply(&enc_keys.public, &enc_balance, &mut rng),
+            ErrorKind::RangeCommitmentMismatch
+        );
+
+        // The `x` term of the encrypted delta is bound to the account's public key.
+        let mut bad_refund = refund.clone();
+        bad_refund.enc_delta.x += enc_keys.public.pub_key;
+        assert!(bad_refund
+            .apply(&enc_keys.public, &enc_balance, &mut rng)
+            .is_err());
+
+        // The refund is bound to the account's public key and balance.
+        let other_keys = mock_gen_enc_key_pair(151u8);
+        assert!(refund
+            .apply(&other_keys.public, &enc_balance, &mut rng)
+            .is_err());
+        let (_, small_balance) = enc_keys
+            .public
+            .encrypt_value(Scalar::from(100u32), &mut rng);
+        let refund = RefundTx::create(&enc_keys, &enc_balance, -200, &mut rng).unwrap();
+        assert!(refund
+            .apply(&enc_keys.public, &small_balance, &mut rng)
+            .is_err());
+
+        // A delta that is larger than the balance is rejected.
+        assert_err!(
+            RefundTx::create(&enc_keys, &enc_balance, -501, &mut rng),
+            ErrorKind::NotEnoughFund {
+                balance: 500,
+                transaction_amount: 501,
+            }
+        );
+
+        // The delta must be in the signed range of the balances.
+        assert_err!(
+            RefundTx::create(&enc_keys, &enc_balance, -(1i64 << 31) - 1, &mut rng),
+            ErrorKind::SignedValueOutOfRange {
+                value: -(1i64 << 31) - 1,
+                range: BALANCE_RANGE,
+            }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_self_minted_refund_is_rejected() {
+        let mut rng = StdRng::from_seed([26u8; 32]);
+        let gens = PedersenGens::default();
+        let enc_keys = mock_gen_enc_key_pair(152u8);
+        let (_, enc_balance) = enc_keys
+            .public
+            .encrypt_value(Scalar::from(500u32), &mut rng);
+        let refund = RefundTx::create(&enc_keys, &enc_balance, -200, &mut rng).unwrap();
+
+        // The owner builds a refund that credits 200 to their account. Every proof but the
+        // one that the delta is not positive can be created for it, so the owner reuses the
+        // one of a valid refund.
+        let delta = 200i64;
+        let (witness, enc_delta) = enc_signed_value(delta, &enc_keys.public, &mut rng);
+        let refresh_blinding = Scalar::random(&mut rng);
+        let refreshed_enc_balance = enc_balance
+            .refresh(&enc_keys.secret, refresh_blinding)
+            .unwrap();
+        let forged = RefundTx {
+            enc_delta,
+            delta_wellformedness_proof: single_property_prover(
+                WellformednessProverAwaitingChallenge {
+                    pub_key: enc_keys.public,
+                    w: Zeroizing::new(witness.clone()),
+                    pc_gens: &gens,
+                },
+                &mut rng,
+            )
+            .unwrap(),
+            delta_range_proof: prove_signed_within_range(
+                delta,
+                witness.blinding(),
+                BALANCE_RANGE,
+                &mut rng,
+            )
+            .unwrap(),
+            refreshed_enc_balance,
+            balance_refreshed_same_proof: single_property_prover(
+                CipherTextRefreshmentProverAwaitingChallenge::new(
+                    enc_keys.secret.clone(),
+                    enc_balance,
+                    refreshed_enc_balance,
+                    &gens,
+                ),
+                &mut rng,
+            )
+            .unwrap(),
+            new_balance_range_proof: prove_within_range(
+                700,
+                refresh_blinding + witness.blinding(),
+                BALANCE_RANGE,
+                &mut rng,
+            )
+            .unwrap(),
+            reversal_range_proof: refund.reversal_range_proof,
+        };
+        assert_err!(
+            forged.apply(&enc_keys.public, &enc_balance, &mut rng),
+            ErrorKind::RangeCommitmentMismatch
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_value_conservation() {
+        let mut rng = StdRng::from_seed([25u8; 32]);
+        let enc_keys = mock_gen_enc_key_pair(160u8);
+        let (_, debit) = enc_keys.public.encrypt_value(100u32.into(), &mut rng);
+
+        // Two credits and the change, with blinding factors that are unrelated to the debit's.
+        // The range proof of an output is always about the absolute value of the output.
+        let output = |value: i64, rng: &mut StdRng| {
+            let blinding = Scalar::random(rng);
+            let abs_value = value.abs() as u64;
+            let signed_value = if value < 0 {
+                -Scalar::from(abs_value)
+            } else {
+                Scalar::from(abs_value)
+            };
+            let range_proof = prove_within_range(abs_value, blinding, BALANCE_RANGE, rng).unwrap();
+            let witness = CommitmentWitness::new(signed_value, blinding);
+            (enc_keys.public.encrypt(&witness), range_proof)
+        };
+        let outputs = [
+            output(60, &mut rng),
+            output(30, &mut rng),
+            output(10, &mut rng),
+        ];
+        let enc_outputs: Vec<_> = outputs.iter().map(|(output, _)| *output).collect();
+        let proof = prove_value_conservation(&enc_keys, &debit, &enc_outputs, &mut rng).unwrap();
+        assert!(
+            verify_value_conservation(&enc_keys.public, &debit, &outputs, &proof, &mut rng).is_ok()
+        );
+
+        // An inflated output breaks the conservation.
+        let mut inflated = outputs.clone();
+        inflated[1] = output(31, &mut rng);
+        let enc_inflated: Vec<_> = inflated.iter().map(|(output, _)| *output).collect();
+        let inflated_proof =
+            prove_value_conservation(&enc_keys, &debit, &enc_inflated, &mut rng).unwrap();
+        assert_err!(
+            verify_value_conservation(
+                &enc_keys.public,
+                &debit,
+                &inflated,
+                &inflated_proof,
+                &mut rng
+            ),
+            ErrorKind::ValueNotConserved
+        );
+
+        // A debit without any outputs is not conserved either.
+        assert_err!(
+            verify_value_conservation(&enc_keys.public, &debit, &[], &proof, &mut rng),
+            ErrorKind::ValueNotConserved
+        );
+
+        // A negative output that is offset by an inflated one adds up to the debit, but it
+        // cannot be proven to be in range.
+        let negative = [
+            output(160, &mut rng),
+            output(-70, &mut rng),
+            output(10, &mut rng),
+        ];
+        let enc_negative: Vec<_> = negative.iter().map(|(output, _)| *output).collect();
+        let negative_proof =
+            prove_value_conservation(&enc_keys, &debit, &enc_negative, &mut rng).unwrap();
+        assert_err!(
+            verify_value_conservation(
+                &enc_keys.public,
+                &debit,
+                &negative,
+                &negative_proof,
+                &mut rng
+            ),
+            ErrorKind::RangeCommitmentMismatch
+        );
     }
 
     fn test_transaction_auditor_helper(
@@ -341,6 +341,9 @@ pub fn create_mediator_account() -> CreateMediatorAccountOutput {
 /// # Arguments
 /// * `amount`: An integer with a max value of `2^32` representing the mint amount.
 /// * `issuer_account`: The mercat account. Can be obtained from `CreateAccountOutput.account`.
+/// * `valid_ticker_ids`: The list of all valid confidential ticker ids. These values can be
+///                       obtained from the chain. The values are expected to be a list of
+///                       hex strings (without the 0x).
 ///
 /// # Outputs
 /// * `MintAssetOutput`: The ZKP of minting the asset.
@@ -348,11 +351,34 @@ pub fn create_mediator_account() -> CreateMediatorAccountOutput {
 /// # Errors
 /// * `Base64DecodingError`: If the `issuer_account` cannot be decoded from base64.
 /// * `DeserializationError`: If the `issuer_account` cannot be deserialized to a mercat account.
+/// * `PlainTickerIdsError`: If the `valid_ticker_ids` is not a list of hex strings (without the
+/// 0x)
+/// * `HexDecodingError`: If `valid_ticker_ids`s are not a proper Hex values.
+/// * `AssetIssuanceError`: If mercat library throws an error while minting the asset, e.g.,
+/// when the ticker of the `issuer_account` is not among the `valid_ticker_ids`.
 #[wasm_bindgen]
-pub fn mint_asset(amount: u32, issuer_account: Account) -> Fallible<MintAssetOutput> {
+pub fn mint_asset(
+    amount: u32,
+    issuer_account: Account,
+    valid_ticker_ids: JsValue,
+) -> Fallible<MintAssetOutput> {
     let mut rng = OsRng;
+    let valid_ticker_ids: Vec<String> = valid_ticker_ids
+        .into_serde()
+        .map_err(|_| WasmError::PlainTickerIdsError)?;
+    let valid_asset_ids: Vec<AssetId> = valid_ticker_ids
+        .into_iter()
+        .map(ticker_id_to_asset_id)
+        .collect::<Fallible<Vec<AssetId>>>()?;
+    let valid_asset_ids = convert_asset_ids(valid_asset_ids);
     let asset_tx: InitializedAssetTx = AssetIssuer
-        .initialize_asset_transaction(&issuer_account.to_mercat()?, &[], amount, &mut rng)
+        .initialize_asset_transaction(
+            &issuer_account.to_mercat()?,
+            &valid_asset_ids,
+            &[],
+            amount,
+            &mut rng,
+        )
         .map_err(|_| WasmError::AssetIssuanceError)?;
 
     Ok(MintAssetOutput {